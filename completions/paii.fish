@@ -36,7 +36,9 @@ complete -c paii -n "__fish_paii_needs_command" -f -a "hook" -d 'Handle hook eve
 complete -c paii -n "__fish_paii_needs_command" -f -a "history" -d 'Query and manage history'
 complete -c paii -n "__fish_paii_needs_command" -f -a "config" -d 'Manage configuration'
 complete -c paii -n "__fish_paii_needs_command" -f -a "registry" -d 'Manage plugin registries'
+complete -c paii -n "__fish_paii_needs_command" -f -a "skill" -d 'Inspect and sync Claude Code skills'
 complete -c paii -n "__fish_paii_needs_command" -f -a "run" -d 'Run a plugin action directly'
+complete -c paii -n "__fish_paii_needs_command" -f -a "apply" -d 'Install, update and remove plugins to match [plugins] in paii.toml'
 complete -c paii -n "__fish_paii_needs_command" -f -a "status" -d 'Show system status'
 complete -c paii -n "__fish_paii_needs_command" -f -a "completions" -d 'Generate shell completions'
 complete -c paii -n "__fish_paii_needs_command" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
@@ -50,18 +52,19 @@ complete -c paii -n "__fish_paii_using_subcommand doctor" -s c -l config -d 'Pat
 complete -c paii -n "__fish_paii_using_subcommand doctor" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand doctor" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand doctor" -s h -l help -d 'Print help'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -s c -l config -d 'Path to paii.toml config file' -r -F
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -s v -l verbose -d 'Enable verbose output'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -s q -l quiet -d 'Suppress non-error output'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -s h -l help -d 'Print help'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "list" -d 'List installed plugins'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "install" -d 'Install a plugin'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "remove" -d 'Remove a plugin'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "update" -d 'Update a plugin'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "info" -d 'Show plugin details'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "new" -d 'Create a new plugin'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "verify" -d 'Verify plugin installation'
-complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "list" -d 'List installed plugins'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "install" -d 'Install a plugin'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "remove" -d 'Remove a plugin'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "update" -d 'Update a plugin'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "info" -d 'Show plugin details'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "new" -d 'Create a new plugin'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "verify" -d 'Verify plugin installation'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "sync" -d 'Install exactly the plugins recorded in paii.lock'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and not __fish_seen_subcommand_from list install remove update info new verify sync help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from list" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
 json\t'JSON format'
 yaml\t'YAML format'"
@@ -69,6 +72,7 @@ complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcom
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from list" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from list" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from list" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from install" -l max-age -d 'Refresh cached registries older than this first (e.g. 12h, 7d)' -r
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from install" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from install" -l dev -d 'Symlink for development (don\'t copy)'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from install" -l force -d 'Overwrite existing installation'
@@ -99,6 +103,11 @@ complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcom
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from verify" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from verify" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from verify" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from sync" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from sync" -l force -d 'Reinstall plugins that already match the lockfile'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from sync" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from sync" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from sync" -s h -l help -d 'Print help'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "list" -d 'List installed plugins'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "install" -d 'Install a plugin'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "remove" -d 'Remove a plugin'
@@ -106,6 +115,7 @@ complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcom
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "info" -d 'Show plugin details'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "new" -d 'Create a new plugin'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "verify" -d 'Verify plugin installation'
+complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "sync" -d 'Install exactly the plugins recorded in paii.lock'
 complete -c paii -n "__fish_paii_using_subcommand plugin; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand hook; and not __fish_seen_subcommand_from dispatch list help" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand hook; and not __fish_seen_subcommand_from dispatch list help" -s v -l verbose -d 'Enable verbose output'
@@ -127,37 +137,102 @@ complete -c paii -n "__fish_paii_using_subcommand hook; and __fish_seen_subcomma
 complete -c paii -n "__fish_paii_using_subcommand hook; and __fish_seen_subcommand_from help" -f -a "dispatch" -d 'Dispatch a hook event to handlers'
 complete -c paii -n "__fish_paii_using_subcommand hook; and __fish_seen_subcommand_from help" -f -a "list" -d 'List registered hook handlers'
 complete -c paii -n "__fish_paii_using_subcommand hook; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -s c -l config -d 'Path to paii.toml config file' -r -F
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -s v -l verbose -d 'Enable verbose output'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -s q -l quiet -d 'Suppress non-error output'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -s h -l help -d 'Print help'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -f -a "query" -d 'Search history'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -f -a "recent" -d 'Show recent entries'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -f -a "categories" -d 'List available categories'
-complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent categories help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "query" -d 'Search history'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "recent" -d 'Show recent entries'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "stats" -d 'Usage statistics: sessions over time, projects, tools, blocks, tags'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "projects" -d 'Show entry counts and last activity per project'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "show" -d 'Show entries in full by entry id or session id (prefixes work)'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "categories" -d 'List available categories'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "prune" -d 'Apply retention policies and compact old entries into monthly archives'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "export" -d 'Export history entries to share or back up'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "import" -d 'Import entries from a JSONL or Markdown export'
+complete -c paii -n "__fish_paii_using_subcommand history; and not __fish_seen_subcommand_from query recent stats projects show categories prune export import help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -l category -d 'Category to search' -r
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -l limit -d 'Max results' -r
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -l since -d 'Only entries after this date' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -l project -d 'Only entries from this project (id or path)' -r
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
 json\t'JSON format'
 yaml\t'YAML format'"
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -l here -d 'Only entries from the project containing the current directory'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from query" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -l category -d 'Category' -r
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -l count -d 'Number of entries' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -l project -d 'Only entries from this project (id or path)' -r
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -l here -d 'Only entries from the project containing the current directory'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from recent" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -l since -d 'Only activity on or after this date (YYYY-MM-DD)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -l until -d 'Only activity on or before this date (YYYY-MM-DD)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
+json\t'JSON format'
+yaml\t'YAML format'"
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from stats" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from projects" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
+json\t'JSON format'
+yaml\t'YAML format'"
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from projects" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from projects" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from projects" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from projects" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -l category -d 'Only entries in this category (e.g. compactions)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
+json\t'JSON format'
+yaml\t'YAML format'"
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from show" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from categories" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from categories" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from categories" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from categories" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -l category -d 'Only prune this category (`journal` for session journals)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -l dry-run -d 'Show what would be removed or compacted without changing anything'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from prune" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s f -l format -d 'Export format' -r -f -a "jsonl\t'One JSON entry per line'
+markdown\t'A single concatenated Markdown document'
+html\t'A static, browsable HTML report'"
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -l category -d 'Only export this category' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -l since -d 'Only entries on or after this date (YYYY-MM-DD)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -l until -d 'Only entries on or before this date (YYYY-MM-DD)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -l tag -d 'Only entries with this tag (repeatable)' -r
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s O -l output -d 'Write to a file instead of stdout' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from export" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from import" -s f -l format -d 'Input format (detected from the file extension if omitted)' -r -f -a "jsonl\t'One JSON entry per line'
+markdown\t'A single concatenated Markdown document'
+html\t'A static, browsable HTML report'"
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from import" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from import" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from import" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from import" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "query" -d 'Search history'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "recent" -d 'Show recent entries'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "stats" -d 'Usage statistics: sessions over time, projects, tools, blocks, tags'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "projects" -d 'Show entry counts and last activity per project'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "show" -d 'Show entries in full by entry id or session id (prefixes work)'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "categories" -d 'List available categories'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "prune" -d 'Apply retention policies and compact old entries into monthly archives'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "export" -d 'Export history entries to share or back up'
+complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "import" -d 'Import entries from a JSONL or Markdown export'
 complete -c paii -n "__fish_paii_using_subcommand history; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand config; and not __fish_seen_subcommand_from show get set help" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand config; and not __fish_seen_subcommand_from show get set help" -s v -l verbose -d 'Enable verbose output'
@@ -186,17 +261,23 @@ complete -c paii -n "__fish_paii_using_subcommand config; and __fish_seen_subcom
 complete -c paii -n "__fish_paii_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "get" -d 'Get a configuration value'
 complete -c paii -n "__fish_paii_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "set" -d 'Set a configuration value'
 complete -c paii -n "__fish_paii_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -s c -l config -d 'Path to paii.toml config file' -r -F
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -s v -l verbose -d 'Enable verbose output'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -s q -l quiet -d 'Suppress non-error output'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -s h -l help -d 'Print help'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "list" -d 'List configured registries'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "add" -d 'Add a registry'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "remove" -d 'Remove a registry'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "update" -d 'Update registry listings'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "search" -d 'Search for plugins in cached registries'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "show" -d 'Show all plugins in a cached registry'
-complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "list" -d 'List configured registries'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "add" -d 'Add a registry'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "remove" -d 'Remove a registry'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "update" -d 'Update registry listings'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "search" -d 'Search for plugins in cached registries'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "show" -d 'Show all plugins in a cached registry'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "keygen" -d 'Generate an ed25519 key pair for signing registries'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "sign" -d 'Sign a registry file, writing <file>.sig next to it'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "verify" -d 'Check a registry file\'s signature against [trust.keys]'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "init" -d 'Create a registry file'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "publish" -d 'Add or update a plugin\'s registry entry from its plugin.toml'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "lint" -d 'Check that every entry resolves to a plugin with its name and version'
+complete -c paii -n "__fish_paii_using_subcommand registry; and not __fish_seen_subcommand_from list add remove update search show keygen sign verify init publish lint help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from list" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from list" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from list" -s q -l quiet -d 'Suppress non-error output'
@@ -213,6 +294,8 @@ complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subc
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from update" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from update" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from update" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from search" -l provides -d 'Only plugins that provide this contract (e.g. memory, skill)' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from search" -l max-age -d 'Refresh cached registries older than this first (e.g. 12h, 7d)' -r
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from search" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
 json\t'JSON format'
 yaml\t'YAML format'"
@@ -227,17 +310,95 @@ complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subc
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from show" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from show" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from show" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -s O -l output -d 'Where to write the secret key' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -l force -d 'Overwrite an existing key file'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from keygen" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from sign" -s k -l key -d 'Secret key file from `paii registry keygen`' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from sign" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from sign" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from sign" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from sign" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from verify" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from verify" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from verify" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from verify" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -s n -l name -d 'Registry name' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -l description -d 'Registry description' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -l maintainer -d 'Registry maintainer' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -l force -d 'Overwrite an existing file'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from init" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -s r -l registry -d 'Registry file to write' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -l source -d 'Repository to list as the source (default: the plugin\'s repository, else its git remote)' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -l ref -d 'Branch, tag or commit holding this version' -r
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -l hash -d 'Record the content hash of the plugin\'s files'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from publish" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from lint" -l local -d 'Resolve entry paths in this checkout instead of cloning sources' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from lint" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from lint" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from lint" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from lint" -s h -l help -d 'Print help'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "list" -d 'List configured registries'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "add" -d 'Add a registry'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "remove" -d 'Remove a registry'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "update" -d 'Update registry listings'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "search" -d 'Search for plugins in cached registries'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "show" -d 'Show all plugins in a cached registry'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "keygen" -d 'Generate an ed25519 key pair for signing registries'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "sign" -d 'Sign a registry file, writing <file>.sig next to it'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "verify" -d 'Check a registry file\'s signature against [trust.keys]'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "init" -d 'Create a registry file'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "publish" -d 'Add or update a plugin\'s registry entry from its plugin.toml'
+complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "lint" -d 'Check that every entry resolves to a plugin with its name and version'
 complete -c paii -n "__fish_paii_using_subcommand registry; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -f -a "list" -d 'List the skills Claude Code sees (personal and project)'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -f -a "sync" -d 'Re-sync skills from every installed plugin'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -f -a "match" -d 'Rank installed skills against a request'
+complete -c paii -n "__fish_paii_using_subcommand skill; and not __fish_seen_subcommand_from list sync match help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from list" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
+json\t'JSON format'
+yaml\t'YAML format'"
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from list" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from list" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from list" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from list" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from sync" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from sync" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from sync" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from sync" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s n -l limit -d 'Maximum number of skills to show' -r
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
+json\t'JSON format'
+yaml\t'YAML format'"
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from match" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from help" -f -a "list" -d 'List the skills Claude Code sees (personal and project)'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from help" -f -a "sync" -d 'Re-sync skills from every installed plugin'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from help" -f -a "match" -d 'Rank installed skills against a request'
+complete -c paii -n "__fish_paii_using_subcommand skill; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand run" -s c -l config -d 'Path to paii.toml config file' -r -F
 complete -c paii -n "__fish_paii_using_subcommand run" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand run" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand run" -s h -l help -d 'Print help'
+complete -c paii -n "__fish_paii_using_subcommand apply" -s c -l config -d 'Path to paii.toml config file' -r -F
+complete -c paii -n "__fish_paii_using_subcommand apply" -l dry-run -d 'Show the plan without changing anything'
+complete -c paii -n "__fish_paii_using_subcommand apply" -s v -l verbose -d 'Enable verbose output'
+complete -c paii -n "__fish_paii_using_subcommand apply" -s q -l quiet -d 'Suppress non-error output'
+complete -c paii -n "__fish_paii_using_subcommand apply" -s h -l help -d 'Print help'
 complete -c paii -n "__fish_paii_using_subcommand status" -s o -l format -d 'Output format (default: text for TTY, json for pipes)' -r -f -a "text\t'Human-readable text'
 json\t'JSON format'
 yaml\t'YAML format'"
@@ -249,17 +410,19 @@ complete -c paii -n "__fish_paii_using_subcommand completions" -s c -l config -d
 complete -c paii -n "__fish_paii_using_subcommand completions" -s v -l verbose -d 'Enable verbose output'
 complete -c paii -n "__fish_paii_using_subcommand completions" -s q -l quiet -d 'Suppress non-error output'
 complete -c paii -n "__fish_paii_using_subcommand completions" -s h -l help -d 'Print help'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "init" -d 'Initialize PAII configuration'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "doctor" -d 'Diagnose setup issues'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "plugin" -d 'Manage plugins'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "hook" -d 'Handle hook events from Claude Code'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "history" -d 'Query and manage history'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "config" -d 'Manage configuration'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "registry" -d 'Manage plugin registries'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "run" -d 'Run a plugin action directly'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "status" -d 'Show system status'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "completions" -d 'Generate shell completions'
-complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry run status completions help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "init" -d 'Initialize PAII configuration'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "doctor" -d 'Diagnose setup issues'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "plugin" -d 'Manage plugins'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "hook" -d 'Handle hook events from Claude Code'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "history" -d 'Query and manage history'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "config" -d 'Manage configuration'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "registry" -d 'Manage plugin registries'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "skill" -d 'Inspect and sync Claude Code skills'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "run" -d 'Run a plugin action directly'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "apply" -d 'Install, update and remove plugins to match [plugins] in paii.toml'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "status" -d 'Show system status'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "completions" -d 'Generate shell completions'
+complete -c paii -n "__fish_paii_using_subcommand help; and not __fish_seen_subcommand_from init doctor plugin hook history config registry skill run apply status completions help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "list" -d 'List installed plugins'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "install" -d 'Install a plugin'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "remove" -d 'Remove a plugin'
@@ -267,11 +430,18 @@ complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcomma
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "info" -d 'Show plugin details'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "new" -d 'Create a new plugin'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "verify" -d 'Verify plugin installation'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from plugin" -f -a "sync" -d 'Install exactly the plugins recorded in paii.lock'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from hook" -f -a "dispatch" -d 'Dispatch a hook event to handlers'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from hook" -f -a "list" -d 'List registered hook handlers'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "query" -d 'Search history'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "recent" -d 'Show recent entries'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "stats" -d 'Usage statistics: sessions over time, projects, tools, blocks, tags'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "projects" -d 'Show entry counts and last activity per project'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "show" -d 'Show entries in full by entry id or session id (prefixes work)'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "categories" -d 'List available categories'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "prune" -d 'Apply retention policies and compact old entries into monthly archives'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "export" -d 'Export history entries to share or back up'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from history" -f -a "import" -d 'Import entries from a JSONL or Markdown export'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "show" -d 'Show current configuration'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "get" -d 'Get a configuration value'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "set" -d 'Set a configuration value'
@@ -281,3 +451,12 @@ complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcomma
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "update" -d 'Update registry listings'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "search" -d 'Search for plugins in cached registries'
 complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "show" -d 'Show all plugins in a cached registry'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "keygen" -d 'Generate an ed25519 key pair for signing registries'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "sign" -d 'Sign a registry file, writing <file>.sig next to it'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "verify" -d 'Check a registry file\'s signature against [trust.keys]'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "init" -d 'Create a registry file'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "publish" -d 'Add or update a plugin\'s registry entry from its plugin.toml'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from registry" -f -a "lint" -d 'Check that every entry resolves to a plugin with its name and version'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from skill" -f -a "list" -d 'List the skills Claude Code sees (personal and project)'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from skill" -f -a "sync" -d 'Re-sync skills from every installed plugin'
+complete -c paii -n "__fish_paii_using_subcommand help; and __fish_seen_subcommand_from skill" -f -a "match" -d 'Rank installed skills against a request'
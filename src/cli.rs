@@ -31,6 +31,17 @@ impl OutputFormat {
     }
 }
 
+/// File format for history export/import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON entry per line
+    Jsonl,
+    /// A single concatenated Markdown document
+    Markdown,
+    /// A static, browsable HTML report
+    Html,
+}
+
 #[derive(Parser)]
 #[command(
     name = "paii",
@@ -101,6 +112,12 @@ pub enum Commands {
         action: RegistryAction,
     },
 
+    /// Inspect and sync Claude Code skills
+    Skill {
+        #[command(subcommand)]
+        action: SkillAction,
+    },
+
     /// Run a plugin action directly
     Run {
         /// Plugin name
@@ -114,6 +131,13 @@ pub enum Commands {
         args: Vec<String>,
     },
 
+    /// Install, update and remove plugins to match [plugins] in paii.toml
+    Apply {
+        /// Show the plan without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Show system status
     Status {
         /// Output format (default: text for TTY, json for pipes)
@@ -139,7 +163,8 @@ pub enum PluginAction {
 
     /// Install a plugin
     Install {
-        /// Plugin source (name, git URL, or local path)
+        /// Plugin source: local path, git URL or owner/repo (with :subdir and
+        /// #ref), or registry name as [registry:]name[@version]
         source: String,
 
         /// Symlink for development (don't copy)
@@ -149,6 +174,10 @@ pub enum PluginAction {
         /// Overwrite existing installation
         #[arg(long)]
         force: bool,
+
+        /// Refresh cached registries older than this first (e.g. 12h, 7d)
+        #[arg(long)]
+        max_age: Option<String>,
     },
 
     /// Remove a plugin
@@ -196,6 +225,40 @@ pub enum PluginAction {
         /// Plugin name
         name: String,
     },
+
+    /// Install exactly the plugins recorded in paii.lock
+    Sync {
+        /// Reinstall plugins that already match the lockfile
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SkillAction {
+    /// List the skills Claude Code sees (personal and project)
+    List {
+        /// Output format (default: text for TTY, json for pipes)
+        #[arg(long, short = 'o', value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Re-sync skills from every installed plugin
+    Sync,
+
+    /// Rank installed skills against a request
+    Match {
+        /// Text to match (e.g. a prompt)
+        text: String,
+
+        /// Maximum number of skills to show
+        #[arg(long, short = 'n', default_value = "5")]
+        limit: usize,
+
+        /// Output format (default: text for TTY, json for pipes)
+        #[arg(long, short = 'o', value_enum)]
+        format: Option<OutputFormat>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -237,6 +300,14 @@ pub enum HistoryAction {
         #[arg(long)]
         since: Option<String>,
 
+        /// Only entries from this project (id or path)
+        #[arg(long, conflicts_with = "here")]
+        project: Option<String>,
+
+        /// Only entries from the project containing the current directory
+        #[arg(long)]
+        here: bool,
+
         /// Output format (default: text for TTY, json for pipes)
         #[arg(long, short = 'o', value_enum)]
         format: Option<OutputFormat>,
@@ -251,10 +322,102 @@ pub enum HistoryAction {
         /// Number of entries
         #[arg(long, default_value = "5")]
         count: usize,
+
+        /// Only entries from this project (id or path)
+        #[arg(long, conflicts_with = "here")]
+        project: Option<String>,
+
+        /// Only entries from the project containing the current directory
+        #[arg(long)]
+        here: bool,
+    },
+
+    /// Usage statistics: sessions over time, projects, tools, blocks, tags
+    Stats {
+        /// Only activity on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only activity on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Output format (default: text for TTY, json for pipes)
+        #[arg(long, short = 'o', value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Show entry counts and last activity per project
+    Projects {
+        /// Output format (default: text for TTY, json for pipes)
+        #[arg(long, short = 'o', value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Show entries in full by entry id or session id (prefixes work)
+    Show {
+        /// Entry id or session id
+        id: String,
+
+        /// Only entries in this category (e.g. compactions)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Output format (default: text for TTY, json for pipes)
+        #[arg(long, short = 'o', value_enum)]
+        format: Option<OutputFormat>,
     },
 
     /// List available categories
     Categories,
+
+    /// Apply retention policies and compact old entries into monthly archives
+    Prune {
+        /// Only prune this category (`journal` for session journals)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Show what would be removed or compacted without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export history entries to share or back up
+    Export {
+        /// Export format
+        #[arg(long, short = 'f', value_enum, default_value = "jsonl")]
+        format: ExportFormat,
+
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Only entries on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only entries on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only entries with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Write to a file instead of stdout
+        #[arg(long, short = 'O')]
+        output: Option<PathBuf>,
+    },
+
+    /// Import entries from a JSONL or Markdown export
+    Import {
+        /// Export file to import ("-" for stdin)
+        file: PathBuf,
+
+        /// Input format (detected from the file extension if omitted)
+        #[arg(long, short = 'f', value_enum)]
+        format: Option<ExportFormat>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -310,8 +473,16 @@ pub enum RegistryAction {
 
     /// Search for plugins in cached registries
     Search {
-        /// Search query (matches name, description, tags)
-        query: String,
+        /// Search query (matches name, description, tags, keywords, provides)
+        query: Option<String>,
+
+        /// Only plugins that provide this contract (e.g. memory, skill)
+        #[arg(long)]
+        provides: Option<String>,
+
+        /// Refresh cached registries older than this first (e.g. 12h, 7d)
+        #[arg(long)]
+        max_age: Option<String>,
 
         /// Output format (default: text for TTY, json for pipes)
         #[arg(long, short = 'o', value_enum)]
@@ -327,4 +498,87 @@ pub enum RegistryAction {
         #[arg(long, short = 'o', value_enum)]
         format: Option<OutputFormat>,
     },
+
+    /// Generate an ed25519 key pair for signing registries
+    Keygen {
+        /// Where to write the secret key
+        #[arg(long, short = 'O', default_value = "registry.key")]
+        output: PathBuf,
+
+        /// Overwrite an existing key file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Sign a registry file, writing <file>.sig next to it
+    Sign {
+        /// Registry file to sign
+        file: PathBuf,
+
+        /// Secret key file from `paii registry keygen`
+        #[arg(long, short = 'k')]
+        key: PathBuf,
+    },
+
+    /// Check a registry file's signature against [trust.keys]
+    Verify {
+        /// Registry file (its signature is read from <file>.sig)
+        file: PathBuf,
+    },
+
+    /// Create a registry file
+    Init {
+        /// Registry file to create
+        #[arg(default_value = "plugins.toml")]
+        file: PathBuf,
+
+        /// Registry name
+        #[arg(long, short = 'n')]
+        name: String,
+
+        /// Registry description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Registry maintainer
+        #[arg(long)]
+        maintainer: Option<String>,
+
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Add or update a plugin's registry entry from its plugin.toml
+    Publish {
+        /// Plugin directory
+        plugin: PathBuf,
+
+        /// Registry file to write
+        #[arg(long, short = 'r')]
+        registry: PathBuf,
+
+        /// Repository to list as the source (default: the plugin's
+        /// repository, else its git remote)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Branch, tag or commit holding this version
+        #[arg(long = "ref")]
+        git_ref: Option<String>,
+
+        /// Record the content hash of the plugin's files
+        #[arg(long)]
+        hash: bool,
+    },
+
+    /// Check that every entry resolves to a plugin with its name and version
+    Lint {
+        /// Registry file
+        file: PathBuf,
+
+        /// Resolve entry paths in this checkout instead of cloning sources
+        #[arg(long)]
+        local: Option<PathBuf>,
+    },
 }
@@ -0,0 +1,494 @@
+//! Converge installed plugins on the `[plugins]` table in paii.toml
+
+use colored::*;
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::plugin;
+use crate::config::{Config, WantedPlugin};
+use crate::plugin::loader::load_plugin;
+use crate::plugin::lock::{LockedPlugin, Lockfile};
+use crate::plugin::source::{GitSource, Source};
+use crate::plugin::version;
+
+/// What `apply` does to one plugin
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    Install,
+    /// Reinstall, with the reason
+    Update(String),
+    Remove,
+    Keep,
+}
+
+#[derive(Debug)]
+struct Step {
+    name: String,
+    change: Change,
+    /// Installed version, if any
+    installed: Option<String>,
+}
+
+/// An installed plugin as found on disk
+#[derive(Debug)]
+struct Installed {
+    version: String,
+    locked: Option<LockedPlugin>,
+}
+
+pub fn run(dry_run: bool, config: &Config) -> Result<()> {
+    if config.plugins.is_empty() {
+        println!("{} No [plugins] declared in paii.toml; nothing to apply", "⚠".yellow());
+        return Ok(());
+    }
+
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let installed = installed(&plugins_dir, &plugin::lockfile(config)?);
+    let steps = plan(&config.plugins, &installed)?;
+
+    println!(
+        "{} Plan for {} declared plugin(s){}",
+        "→".blue(),
+        config.plugins.len(),
+        if dry_run {
+            " (dry run)".dimmed().to_string()
+        } else {
+            String::new()
+        }
+    );
+    for step in &steps {
+        print_step(step, config.plugins.get(&step.name));
+    }
+    println!();
+    println!("  {}", summary(&steps).dimmed());
+
+    if dry_run {
+        check_overrides(config, &plugins_dir);
+        println!("{} Dry run: nothing changed", "✓".green());
+        return Ok(());
+    }
+
+    // Removals first, so their skills and agents don't collide with installs
+    let mut failed = 0;
+    for step in steps.iter().filter(|s| s.change == Change::Remove) {
+        if let Err(e) = plugin::remove(&step.name, false, config) {
+            println!("  {} {}: {:#}", "✗".red(), step.name, e);
+            failed += 1;
+        }
+    }
+    for step in &steps {
+        let force = match step.change {
+            Change::Install => false,
+            Change::Update(_) => true,
+            Change::Remove | Change::Keep => continue,
+        };
+        let want = &config.plugins[&step.name];
+        let result = match want.source {
+            Some(ref source) => plugin::install_declared(
+                &step.name,
+                &Config::expand_path(Path::new(source)).display().to_string(),
+                want.dev,
+                force,
+                config,
+            ),
+            None => plugin::install(&registry_spec(&step.name, want), false, force, config),
+        };
+        if let Err(e) = result {
+            println!("  {} {}: {:#}", "✗".red(), step.name, e);
+            failed += 1;
+        }
+    }
+
+    check_overrides(config, &plugins_dir);
+
+    if failed > 0 {
+        eyre::bail!("{} plugin(s) failed to apply", failed);
+    }
+    println!("{} Plugins match paii.toml", "✓".green());
+    Ok(())
+}
+
+/// Plugins in the plugins directory, keyed by directory name
+fn installed(plugins_dir: &Path, lock: &Lockfile) -> BTreeMap<String, Installed> {
+    let Ok(entries) = fs::read_dir(plugins_dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join("plugin.toml").exists())
+        .map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let version = load_plugin(e.path())
+                .map(|p| p.manifest.plugin.version)
+                .unwrap_or_else(|_| "?".to_string());
+            let locked = lock.get(&name).cloned();
+            (name, Installed { version, locked })
+        })
+        .collect()
+}
+
+/// Diff the declared plugins against the installed ones.
+///
+/// Constraints and local sources are validated up front, so a bad entry
+/// fails the whole plan before anything changes.
+fn plan(wanted: &BTreeMap<String, WantedPlugin>, installed: &BTreeMap<String, Installed>) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+
+    for (name, want) in wanted {
+        let req = want
+            .version
+            .as_deref()
+            .map(version::parse_req)
+            .transpose()
+            .wrap_err(format!("[plugins.{}]", name))?;
+        let source = want
+            .source
+            .as_deref()
+            .map(|s| declared_source(name, s, req.as_ref()))
+            .transpose()?;
+
+        let Some(current) = installed.get(name) else {
+            steps.push(Step {
+                name: name.clone(),
+                change: Change::Install,
+                installed: None,
+            });
+            continue;
+        };
+
+        let unsatisfied = || {
+            req.as_ref().and_then(|req| {
+                let satisfied = version::parse_version(&current.version).is_ok_and(|v| req.matches(&v));
+                (!satisfied).then(|| format!("v{} does not satisfy {}", current.version, req))
+            })
+        };
+        let locked = current.locked.as_ref();
+        let reason = match (&source, locked) {
+            (_, None) => Some("not in the lockfile".to_string()),
+            (Some(Declared::Git(git)), Some(locked)) => {
+                if locked.registry.is_some()
+                    || locked.source.as_deref() != Some(git.url.as_str())
+                    || locked.path != git.subdir
+                    || locked.git_ref != git.git_ref
+                {
+                    Some(format!("source is now {}", git))
+                } else {
+                    unsatisfied()
+                }
+            }
+            (Some(Declared::Local(path, source_version)), Some(locked)) => {
+                let path = path.display().to_string();
+                if locked.source.as_deref() != Some(path.as_str()) || locked.registry.is_some() {
+                    Some(format!("source is now {}", path))
+                } else if locked.dev != want.dev {
+                    Some(
+                        if want.dev {
+                            "switch to dev mode"
+                        } else {
+                            "switch to a copy"
+                        }
+                        .to_string(),
+                    )
+                } else if *source_version != current.version {
+                    Some(format!("source is v{}", source_version))
+                } else {
+                    None
+                }
+            }
+            (None, Some(locked)) => match (&want.registry, &locked.registry) {
+                (_, None) => Some("installed from a local path".to_string()),
+                (Some(wanted), Some(from)) if wanted != from => {
+                    Some(format!("installed from {}, wanted {}", from, wanted))
+                }
+                _ => unsatisfied(),
+            },
+        };
+
+        steps.push(Step {
+            name: name.clone(),
+            change: reason.map_or(Change::Keep, Change::Update),
+            installed: Some(current.version.clone()),
+        });
+    }
+
+    for (name, current) in installed {
+        if !wanted.contains_key(name) {
+            steps.push(Step {
+                name: name.clone(),
+                change: Change::Remove,
+                installed: Some(current.version.clone()),
+            });
+        }
+    }
+
+    Ok(steps)
+}
+
+/// A declared `source`
+enum Declared {
+    /// Canonical path and version of a local plugin
+    Local(PathBuf, String),
+    Git(GitSource),
+}
+
+fn declared_source(name: &str, source: &str, req: Option<&semver::VersionReq>) -> Result<Declared> {
+    let expanded = Config::expand_path(Path::new(source)).display().to_string();
+    let path = match Source::parse(&expanded) {
+        Some(Source::Git(git)) => return Ok(Declared::Git(git)),
+        Some(Source::Path(path)) => path,
+        _ => eyre::bail!(
+            "[plugins.{}] source {} is neither a plugin directory nor a git repository",
+            name,
+            source
+        ),
+    };
+
+    let plugin = load_plugin(&path).wrap_err(format!("[plugins.{}] source {}", name, source))?;
+    if plugin.manifest.plugin.name != name {
+        eyre::bail!(
+            "[plugins.{}] source {} is plugin '{}'",
+            name,
+            source,
+            plugin.manifest.plugin.name
+        );
+    }
+    let source_version = plugin.manifest.plugin.version;
+    if let Some(req) = req
+        && !version::parse_version(&source_version).is_ok_and(|v| req.matches(&v))
+    {
+        eyre::bail!(
+            "[plugins.{}] source {} is v{}, which does not satisfy {}",
+            name,
+            source,
+            source_version,
+            req
+        );
+    }
+    Ok(Declared::Local(fs::canonicalize(&path).unwrap_or(path), source_version))
+}
+
+/// Install spec for a registry plugin: `[registry:]name[@version]`
+fn registry_spec(name: &str, want: &WantedPlugin) -> String {
+    let mut spec = match want.registry {
+        Some(ref registry) => format!("{}:{}", registry, name),
+        None => name.to_string(),
+    };
+    if let Some(ref req) = want.version {
+        spec = format!("{}@{}", spec, req);
+    }
+    spec
+}
+
+fn print_step(step: &Step, want: Option<&WantedPlugin>) {
+    let installed = step
+        .installed
+        .as_deref()
+        .map(|v| format!(" v{}", v))
+        .unwrap_or_default();
+    let target = match want {
+        Some(WantedPlugin {
+            source: Some(source), ..
+        }) => source.clone(),
+        Some(want) => registry_spec(&step.name, want),
+        None => String::new(),
+    };
+    match step.change {
+        Change::Install => println!("  {} {} ({})", "+".green(), step.name.green(), target.dimmed()),
+        Change::Update(ref reason) => println!(
+            "  {} {}{} → {} ({})",
+            "~".yellow(),
+            step.name.yellow(),
+            installed,
+            target.dimmed(),
+            reason
+        ),
+        Change::Remove => println!("  {} {}{} (not declared)", "-".red(), step.name.red(), installed),
+        Change::Keep => println!("  {} {}{}", "=".dimmed(), step.name, installed.dimmed()),
+    }
+}
+
+fn summary(steps: &[Step]) -> String {
+    let count = |f: fn(&Change) -> bool| steps.iter().filter(|s| f(&s.change)).count();
+    format!(
+        "{} to install, {} to update, {} to remove, {} unchanged",
+        count(|c| *c == Change::Install),
+        count(|c| matches!(c, Change::Update(_))),
+        count(|c| *c == Change::Remove),
+        count(|c| *c == Change::Keep),
+    )
+}
+
+/// Warn about config overrides the installed plugin doesn't declare
+fn check_overrides(config: &Config, plugins_dir: &Path) {
+    for (name, want) in &config.plugins {
+        if want.config.is_empty() {
+            continue;
+        }
+        let Ok(plugin) = load_plugin(plugins_dir.join(name)) else {
+            continue;
+        };
+        for key in want.config.keys() {
+            if !plugin.manifest.config.contains_key(key) {
+                println!(
+                    "  {} [plugins.{}.config] sets '{}', which {} does not declare",
+                    "⚠".yellow(),
+                    name,
+                    key,
+                    name
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn present(version: &str, locked: Option<LockedPlugin>) -> Installed {
+        Installed {
+            version: version.to_string(),
+            locked,
+        }
+    }
+
+    fn from_registry(registry: &str, version: &str) -> LockedPlugin {
+        LockedPlugin {
+            version: version.to_string(),
+            registry: Some(registry.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn change<'a>(steps: &'a [Step], name: &str) -> &'a Change {
+        &steps.iter().find(|s| s.name == name).unwrap().change
+    }
+
+    #[test]
+    fn test_plan() {
+        let config: Config = toml::from_str(
+            r#"
+[plugins]
+new = "^1"
+current = "^1"
+stale = "^1.2"
+
+[plugins.moved]
+registry = "work"
+"#,
+        )
+        .unwrap();
+
+        let mut on_disk = BTreeMap::new();
+        on_disk.insert(
+            "current".to_string(),
+            present("1.4.0", Some(from_registry("core", "1.4.0"))),
+        );
+        on_disk.insert(
+            "stale".to_string(),
+            present("1.0.0", Some(from_registry("core", "1.0.0"))),
+        );
+        on_disk.insert(
+            "moved".to_string(),
+            present("0.1.0", Some(from_registry("core", "0.1.0"))),
+        );
+        on_disk.insert("extra".to_string(), present("0.1.0", None));
+
+        let steps = plan(&config.plugins, &on_disk).unwrap();
+        assert_eq!(*change(&steps, "new"), Change::Install);
+        assert_eq!(*change(&steps, "current"), Change::Keep);
+        assert_eq!(
+            *change(&steps, "stale"),
+            Change::Update("v1.0.0 does not satisfy ^1.2".to_string())
+        );
+        assert_eq!(
+            *change(&steps, "moved"),
+            Change::Update("installed from core, wanted work".to_string())
+        );
+        assert_eq!(*change(&steps, "extra"), Change::Remove);
+        assert_eq!(summary(&steps), "1 to install, 2 to update, 1 to remove, 1 unchanged");
+
+        assert_eq!(registry_spec("moved", &config.plugins["moved"]), "work:moved");
+        assert_eq!(registry_spec("stale", &config.plugins["stale"]), "stale@^1.2");
+    }
+
+    #[test]
+    fn test_plan_local_source() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("mine");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(
+            source.join("plugin.toml"),
+            "[plugin]\nname = \"mine\"\nversion = \"0.2.0\"\ndescription = \"test\"\nlanguage = \"python\"\n",
+        )
+        .unwrap();
+
+        let mut wanted = BTreeMap::new();
+        wanted.insert(
+            "mine".to_string(),
+            WantedPlugin {
+                source: Some(source.display().to_string()),
+                dev: true,
+                ..Default::default()
+            },
+        );
+
+        let linked = LockedPlugin::local(&source, true);
+        let mut on_disk = BTreeMap::new();
+        on_disk.insert("mine".to_string(), present("0.2.0", Some(linked)));
+        assert_eq!(*change(&plan(&wanted, &on_disk).unwrap(), "mine"), Change::Keep);
+
+        on_disk.insert(
+            "mine".to_string(),
+            present("0.2.0", Some(LockedPlugin::local(&source, false))),
+        );
+        assert_eq!(
+            *change(&plan(&wanted, &on_disk).unwrap(), "mine"),
+            Change::Update("switch to dev mode".to_string())
+        );
+
+        // A source that can't satisfy the constraint fails the plan
+        wanted.get_mut("mine").unwrap().version = Some("^1".to_string());
+        assert!(plan(&wanted, &on_disk).is_err());
+
+        // So does a source whose plugin.toml names another plugin
+        let mut renamed = wanted.remove("mine").unwrap();
+        renamed.version = None;
+        wanted.insert("yours".to_string(), renamed);
+        let err = plan(&wanted, &BTreeMap::new()).unwrap_err();
+        assert!(err.to_string().contains("is plugin 'mine'"));
+    }
+
+    #[test]
+    fn test_plan_git_source() {
+        let mut wanted = BTreeMap::new();
+        wanted.insert(
+            "remote".to_string(),
+            WantedPlugin {
+                source: Some("https://example.com/plugins.git:remote#v1".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let mut locked = LockedPlugin {
+            version: "1.0.0".to_string(),
+            source: Some("https://example.com/plugins.git".to_string()),
+            path: Some("remote".to_string()),
+            git_ref: Some("v1".to_string()),
+            commit: Some("0123abcd".to_string()),
+            ..Default::default()
+        };
+        let mut on_disk = BTreeMap::new();
+        on_disk.insert("remote".to_string(), present("1.0.0", Some(locked.clone())));
+        assert_eq!(*change(&plan(&wanted, &on_disk).unwrap(), "remote"), Change::Keep);
+
+        locked.git_ref = Some("v0".to_string());
+        on_disk.insert("remote".to_string(), present("1.0.0", Some(locked)));
+        assert_eq!(
+            *change(&plan(&wanted, &on_disk).unwrap(), "remote"),
+            Change::Update("source is now https://example.com/plugins.git:remote#v1".to_string())
+        );
+    }
+}
@@ -3,7 +3,7 @@ use eyre::{Context, Result};
 use std::fs;
 
 use crate::cli::{ConfigAction, OutputFormat};
-use crate::config::Config;
+use crate::config::{ClaudeScope, Config};
 
 pub fn run(action: ConfigAction, config: &Config) -> Result<()> {
     match action {
@@ -33,6 +33,7 @@ fn show(format: OutputFormat, config: &Config) -> Result<()> {
             println!("  plugins: {}", config.paths.plugins.display());
             println!("  history: {}", config.paths.history.display());
             println!("  registries: {}", config.paths.registries.display());
+            println!("  lockfile: {}", config.paths.lockfile.display());
             println!();
 
             println!("{}:", "defaults".cyan());
@@ -41,14 +42,65 @@ fn show(format: OutputFormat, config: &Config) -> Result<()> {
             println!();
 
             println!("{}:", "registries".cyan());
-            for (name, url) in &config.registries {
-                println!("  {}: {}", name, url.dimmed());
+            for name in config.registry_order() {
+                println!("  {}: {}", name, config.registries[&name].dimmed());
+            }
+            if !config.registry_priority.is_empty() {
+                println!("  priority: [{}]", config.registry_priority.join(", "));
             }
             println!();
 
             println!("{}:", "hooks".cyan());
             println!("  security_enabled: {}", config.hooks.security_enabled);
             println!("  history_enabled: {}", config.hooks.history_enabled);
+            println!("  context_enabled: {}", config.hooks.context_enabled);
+            println!("  compaction_enabled: {}", config.hooks.compaction_enabled);
+            println!("  learning_enabled: {}", config.hooks.learning_enabled);
+            println!("  notification_enabled: {}", config.hooks.notification_enabled);
+            println!("  capture_prompts: {}", config.hooks.capture_prompts);
+            println!("  capture_tools: {}", config.hooks.capture_tools);
+            println!("  suggest_skills: {}", config.hooks.suggest_skills);
+            println!();
+
+            println!("{}:", "context".cyan());
+            if let Some(tokens) = config.context.max_tokens {
+                println!("  max_tokens: {}", tokens);
+            }
+            if let Some(chars) = config.context.max_chars {
+                println!("  max_chars: {}", chars);
+            }
+            println!("  categories: [{}]", config.context.categories.join(", "));
+            println!("  per_category: {}", config.context.per_category);
+            println!("  include_last_session: {}", config.context.include_last_session);
+            println!();
+
+            println!("{}:", "claude".cyan());
+            println!("  dir: {}", config.claude.dir.display());
+            println!("  scope: {}", config.claude.scope.as_str());
+
+            if !config.history.retention.is_empty() {
+                println!();
+                println!("{}:", "history.retention".cyan());
+                let mut names: Vec<&String> = config.history.retention.keys().collect();
+                names.sort();
+                for name in names {
+                    let policy = &config.history.retention[name];
+                    let mut parts = Vec::new();
+                    if let Some(days) = policy.max_age_days {
+                        parts.push(format!("max_age_days={}", days));
+                    }
+                    if let Some(count) = policy.max_count {
+                        parts.push(format!("max_count={}", count));
+                    }
+                    if !policy.keep_tagged.is_empty() {
+                        parts.push(format!("keep_tagged=[{}]", policy.keep_tagged.join(", ")));
+                    }
+                    if let Some(days) = policy.compact_after_days {
+                        parts.push(format!("compact_after_days={}", days));
+                    }
+                    println!("  {}: {}", name, parts.join(" ").dimmed());
+                }
+            }
         }
     }
 
@@ -62,10 +114,25 @@ fn get(key: &str, config: &Config) -> Result<()> {
         "paths.plugins" => Some(config.paths.plugins.display().to_string()),
         "paths.history" => Some(config.paths.history.display().to_string()),
         "paths.registries" => Some(config.paths.registries.display().to_string()),
+        "paths.lockfile" => Some(config.paths.lockfile.display().to_string()),
         "defaults.language" => Some(config.defaults.language.clone()),
         "defaults.log_level" => Some(config.defaults.log_level.clone()),
         "hooks.security_enabled" => Some(config.hooks.security_enabled.to_string()),
         "hooks.history_enabled" => Some(config.hooks.history_enabled.to_string()),
+        "hooks.context_enabled" => Some(config.hooks.context_enabled.to_string()),
+        "hooks.compaction_enabled" => Some(config.hooks.compaction_enabled.to_string()),
+        "hooks.learning_enabled" => Some(config.hooks.learning_enabled.to_string()),
+        "hooks.notification_enabled" => Some(config.hooks.notification_enabled.to_string()),
+        "hooks.capture_prompts" => Some(config.hooks.capture_prompts.to_string()),
+        "hooks.capture_tools" => Some(config.hooks.capture_tools.to_string()),
+        "hooks.suggest_skills" => Some(config.hooks.suggest_skills.to_string()),
+        "context.max_tokens" => config.context.max_tokens.map(|v| v.to_string()),
+        "context.max_chars" => config.context.max_chars.map(|v| v.to_string()),
+        "context.categories" => Some(config.context.categories.join(",")),
+        "context.per_category" => Some(config.context.per_category.to_string()),
+        "context.include_last_session" => Some(config.context.include_last_session.to_string()),
+        "claude.dir" => Some(config.claude.dir.display().to_string()),
+        "claude.scope" => Some(config.claude.scope.as_str().to_string()),
         _ => None,
     };
 
@@ -92,6 +159,7 @@ fn set(key: &str, value: &str, config: &Config) -> Result<()> {
         "paths.plugins" => new_config.paths.plugins = value.into(),
         "paths.history" => new_config.paths.history = value.into(),
         "paths.registries" => new_config.paths.registries = value.into(),
+        "paths.lockfile" => new_config.paths.lockfile = value.into(),
         "defaults.language" => new_config.defaults.language = value.to_string(),
         "defaults.log_level" => new_config.defaults.log_level = value.to_string(),
         "hooks.security_enabled" => {
@@ -102,6 +170,61 @@ fn set(key: &str, value: &str, config: &Config) -> Result<()> {
             new_config.hooks.history_enabled =
                 value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
         }
+        "hooks.context_enabled" => {
+            new_config.hooks.context_enabled =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.compaction_enabled" => {
+            new_config.hooks.compaction_enabled =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.learning_enabled" => {
+            new_config.hooks.learning_enabled =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.notification_enabled" => {
+            new_config.hooks.notification_enabled =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.capture_prompts" => {
+            new_config.hooks.capture_prompts =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.capture_tools" => {
+            new_config.hooks.capture_tools = value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "hooks.suggest_skills" => {
+            new_config.hooks.suggest_skills = value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "context.max_tokens" => {
+            new_config.context.max_tokens = Some(value.parse().context("Invalid number")?);
+        }
+        "context.max_chars" => {
+            new_config.context.max_chars = Some(value.parse().context("Invalid number")?);
+        }
+        "context.categories" => {
+            new_config.context.categories = value
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        "context.per_category" => {
+            new_config.context.per_category = value.parse().context("Invalid number")?;
+        }
+        "context.include_last_session" => {
+            new_config.context.include_last_session =
+                value.parse().context("Invalid boolean value (use 'true' or 'false')")?;
+        }
+        "claude.dir" => new_config.claude.dir = value.into(),
+        "claude.scope" => {
+            new_config.claude.scope = match value {
+                "user" => ClaudeScope::User,
+                "project" => ClaudeScope::Project,
+                _ => eyre::bail!("Invalid scope '{}' (use 'user' or 'project')", value),
+            };
+        }
         _ => {
             eyre::bail!("Unknown config key: {}", key);
         }
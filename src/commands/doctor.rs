@@ -4,8 +4,13 @@ use colored::*;
 use eyre::Result;
 use std::fs;
 use std::process::Command;
+use std::time::Duration;
 
 use crate::config::Config;
+use crate::plugin::mcp;
+
+/// How long an MCP server gets to answer `initialize`
+const MCP_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub fn run(config: &Config) -> Result<()> {
     println!("{}", "PAII Doctor".bold());
@@ -123,6 +128,9 @@ pub fn run(config: &Config) -> Result<()> {
 
     println!();
 
+    // Check MCP servers bundled with installed plugins
+    issues += check_mcp_servers(&plugins_dir);
+
     // Check hooks configuration
     println!("{}", "Hooks:".bold());
     println!(
@@ -141,6 +149,30 @@ pub fn run(config: &Config) -> Result<()> {
             "disabled".yellow()
         }
     );
+    println!(
+        "  Learning: {}",
+        if config.hooks.learning_enabled {
+            "enabled".green()
+        } else {
+            "disabled".yellow()
+        }
+    );
+    println!(
+        "  Compact:  {}",
+        if config.hooks.compaction_enabled {
+            "enabled".green()
+        } else {
+            "disabled".yellow()
+        }
+    );
+    println!(
+        "  Context:  {}",
+        if config.hooks.context_enabled {
+            "enabled".green()
+        } else {
+            "disabled".yellow()
+        }
+    );
 
     // Check Claude Code hooks file
     let claude_hooks = std::env::current_dir().ok().map(|d| d.join(".claude/settings.json"));
@@ -166,6 +198,53 @@ pub fn run(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Start every installed plugin's stdio MCP servers and make sure they
+/// answer `initialize`. Returns the number of failures.
+fn check_mcp_servers(plugins_dir: &std::path::Path) -> usize {
+    let mut plugins: Vec<std::path::PathBuf> = fs::read_dir(plugins_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.join(mcp::MCP_FILE).exists())
+                .collect()
+        })
+        .unwrap_or_default();
+    if plugins.is_empty() {
+        return 0;
+    }
+    plugins.sort();
+
+    println!("{}", "MCP servers:".bold());
+    let mut issues = 0;
+    for plugin_dir in plugins {
+        let plugin = plugin_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let servers = match mcp::load(&plugin_dir) {
+            Ok(servers) => servers,
+            Err(e) => {
+                println!("  {} {}: {:#}", "✗".red(), plugin, e);
+                issues += 1;
+                continue;
+            }
+        };
+
+        for (name, server) in servers {
+            if !server.is_stdio() {
+                println!("  {} {} ({}, remote, not probed)", "-".dimmed(), name, plugin.dimmed());
+                continue;
+            }
+            match mcp::probe(&server, &plugin_dir, MCP_PROBE_TIMEOUT) {
+                Ok(info) => println!("  {} {} ({}) {}", "✓".green(), name, plugin.dimmed(), info.dimmed()),
+                Err(e) => {
+                    println!("  {} {} ({}): {:#}", "✗".red(), name, plugin.dimmed(), e);
+                    issues += 1;
+                }
+            }
+        }
+    }
+    println!();
+    issues
+}
+
 fn count_plugins(dir: &std::path::Path) -> usize {
     fs::read_dir(dir)
         .map(|entries| {
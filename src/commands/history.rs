@@ -2,10 +2,17 @@ use chrono::NaiveDate;
 use colored::*;
 use eyre::{Context, Result};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
 
-use crate::cli::{HistoryAction, OutputFormat};
+use crate::cli::{ExportFormat, HistoryAction, OutputFormat};
 use crate::config::Config;
-use crate::history::HistoryStore;
+use crate::contract::memory::{self, MemoryProvider, MemoryResult};
+use crate::history::journal::{self, Journal};
+use crate::history::project::{self, Project};
+use crate::history::{HistoryFilter, HistoryStore, export, retention, stats};
 
 pub fn run(action: HistoryAction, config: &Config) -> Result<()> {
     match action {
@@ -14,17 +21,58 @@ pub fn run(action: HistoryAction, config: &Config) -> Result<()> {
             category,
             limit,
             since,
+            project,
+            here,
             format,
         } => query_history(
             &query,
             category.as_deref(),
             limit,
             since.as_deref(),
+            project_filter(project.as_deref(), here)?.as_deref(),
             OutputFormat::resolve(format),
             config,
         ),
-        HistoryAction::Recent { category, count } => recent(category.as_deref(), count, config),
+        HistoryAction::Recent {
+            category,
+            count,
+            project,
+            here,
+        } => recent(
+            category.as_deref(),
+            count,
+            project_filter(project.as_deref(), here)?.as_deref(),
+            config,
+        ),
+        HistoryAction::Show { id, category, format } => {
+            show(&id, category.as_deref(), OutputFormat::resolve(format), config)
+        }
         HistoryAction::Categories => categories(config),
+        HistoryAction::Projects { format } => projects(OutputFormat::resolve(format), config),
+        HistoryAction::Stats { since, until, format } => stats(
+            parse_date(since.as_deref())?,
+            parse_date(until.as_deref())?,
+            OutputFormat::resolve(format),
+            config,
+        ),
+        HistoryAction::Prune { category, dry_run } => prune(category.as_deref(), dry_run, config),
+        HistoryAction::Export {
+            format,
+            category,
+            since,
+            until,
+            tags,
+            output,
+        } => {
+            let filter = HistoryFilter {
+                category,
+                since: parse_date(since.as_deref())?,
+                until: parse_date(until.as_deref())?,
+                tags,
+            };
+            export(format, &filter, output.as_deref(), config)
+        }
+        HistoryAction::Import { file, format } => import(&file, format, config),
     }
 }
 
@@ -37,37 +85,64 @@ struct HistoryEntryOutput {
     tags: Vec<String>,
 }
 
+impl From<&MemoryResult> for HistoryEntryOutput {
+    fn from(r: &MemoryResult) -> Self {
+        Self {
+            id: r.meta_str("id").unwrap_or(&r.path).to_string(),
+            category: r.category.clone(),
+            title: r.title(),
+            created_at: r.timestamp.clone(),
+            tags: r.tags(),
+        }
+    }
+}
+
+/// Resolve `--project`/`--here` to a project id
+fn project_filter(project: Option<&str>, here: bool) -> Result<Option<String>> {
+    if here {
+        let cwd = std::env::current_dir().context("Failed to read current directory")?;
+        return Ok(Some(Project::detect(&cwd).id));
+    }
+    Ok(project.map(project::resolve_arg))
+}
+
+/// Keep only entries from `project`, up to `limit`
+fn in_project(entries: Vec<MemoryResult>, project: &str, limit: usize) -> Vec<MemoryResult> {
+    let mut cache = HashMap::new();
+    entries
+        .into_iter()
+        .filter(|e| project::entry_project(e, &mut cache).as_deref() == Some(project))
+        .take(limit)
+        .collect()
+}
+
 fn query_history(
     query: &str,
     category: Option<&str>,
     limit: usize,
     since: Option<&str>,
+    project: Option<&str>,
     format: OutputFormat,
     config: &Config,
 ) -> Result<()> {
-    let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
+    let provider = memory::resolve(config);
 
     // Parse since date if provided
-    let since_date = since
-        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
-        .transpose()
-        .context("Invalid date format (use YYYY-MM-DD)")?;
+    let since_date = parse_date(since)?;
 
-    let entries = store.query(query, category, since_date, limit)?;
+    // Project scoping happens after the search, so search everything first
+    let entries = match project {
+        Some(project) => in_project(
+            provider.query_since(category.unwrap_or(""), query, since_date, usize::MAX)?,
+            project,
+            limit,
+        ),
+        None => provider.query_since(category.unwrap_or(""), query, since_date, limit)?,
+    };
 
     match format {
         OutputFormat::Json | OutputFormat::Yaml => {
-            let output: Vec<HistoryEntryOutput> = entries
-                .iter()
-                .map(|e| HistoryEntryOutput {
-                    id: e.id.clone(),
-                    category: e.category.clone(),
-                    title: e.title.clone(),
-                    created_at: e.created_at.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
-                    tags: e.tags.clone(),
-                })
-                .collect();
+            let output: Vec<HistoryEntryOutput> = entries.iter().map(HistoryEntryOutput::from).collect();
             match format {
                 OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
                 OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
@@ -75,12 +150,16 @@ fn query_history(
             }
         }
         OutputFormat::Text => {
-            println!(
-                "{} Found {} entries matching '{}':",
+            print!(
+                "{} Found {} entries matching '{}'",
                 "🔍".blue(),
                 entries.len(),
                 query.cyan()
             );
+            match project {
+                Some(project) => println!(" in {}:", project.cyan()),
+                None => println!(":"),
+            }
             println!();
 
             if entries.is_empty() {
@@ -96,13 +175,14 @@ fn query_history(
     Ok(())
 }
 
-fn recent(category: Option<&str>, count: usize, config: &Config) -> Result<()> {
-    let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
+fn recent(category: Option<&str>, count: usize, project: Option<&str>, config: &Config) -> Result<()> {
+    let provider = memory::resolve(config);
+    let entries = recent_entries(provider.as_ref(), category.unwrap_or(""), count, project)?;
 
-    let entries = store.recent(category, count)?;
-
-    println!("{} Recent history entries:", "📋".blue());
+    match project {
+        Some(project) => println!("{} Recent history entries in {}:", "📋".blue(), project.cyan()),
+        None => println!("{} Recent history entries:", "📋".blue()),
+    }
     println!();
 
     if entries.is_empty() {
@@ -116,14 +196,70 @@ fn recent(category: Option<&str>, count: usize, config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// The `count` newest entries, optionally scoped to a project
+fn recent_entries(
+    provider: &dyn MemoryProvider,
+    category: &str,
+    count: usize,
+    project: Option<&str>,
+) -> Result<Vec<MemoryResult>> {
+    match project {
+        // Scoping happens after the search, so it has to reach archived months
+        Some(project) => Ok(in_project(
+            provider.query_since(category, "", None, usize::MAX)?,
+            project,
+            count,
+        )),
+        None => provider.get_recent(category, count),
+    }
+}
+
+fn show(id: &str, category: Option<&str>, format: OutputFormat, config: &Config) -> Result<()> {
+    let provider = memory::resolve(config);
+
+    // Search archives too; oldest first, so a session reads in order
+    let mut entries: Vec<MemoryResult> = provider
+        .query_since(category.unwrap_or(""), "", None, usize::MAX)?
+        .into_iter()
+        .filter(|e| {
+            e.meta_str("id").is_some_and(|v| v.starts_with(id))
+                || e.meta_str("session_id").is_some_and(|v| v.starts_with(id))
+        })
+        .collect();
+    entries.reverse();
+
+    if entries.is_empty() {
+        eyre::bail!("No history entries for '{}'", id);
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&entries)?),
+        OutputFormat::Text => {
+            for entry in &entries {
+                print_entry_summary(entry);
+                if let Some(session) = entry.meta_str("session_id") {
+                    println!("    session: {}", session.dimmed());
+                }
+                println!();
+                for line in entry.content.lines() {
+                    println!("    {}", line);
+                }
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn categories(config: &Config) -> Result<()> {
     println!("{}", "History categories:".bold());
     println!();
 
-    let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
+    let provider = memory::resolve(config);
 
-    let cats = store.categories()?;
+    let cats = provider.list_categories();
 
     if cats.is_empty() {
         println!("  {}", "(no history yet)".dimmed());
@@ -131,17 +267,392 @@ fn categories(config: &Config) -> Result<()> {
     }
 
     for cat in cats {
-        let count = store.count(&cat)?;
+        let count = provider.count(&cat)?;
         println!("  {:15} ({} entries)", cat.cyan(), count);
     }
 
     Ok(())
 }
 
-fn print_entry_summary(entry: &crate::history::HistoryEntry) {
-    let date = entry.created_at.format("%Y-%m-%d %H:%M").to_string();
-    println!("  {} {} {}", entry.category.cyan(), date.dimmed(), entry.title.bold());
-    if !entry.tags.is_empty() {
-        println!("    tags: {}", entry.tags.join(", ").dimmed());
+#[derive(Serialize)]
+struct ProjectStats {
+    project: String,
+    entries: usize,
+    sessions: usize,
+    last_activity: String,
+    categories: BTreeMap<String, usize>,
+}
+
+fn projects(format: OutputFormat, config: &Config) -> Result<()> {
+    let provider = memory::resolve(config);
+    let entries = provider.get_recent("", usize::MAX)?;
+
+    let mut cache = HashMap::new();
+    let mut stats: BTreeMap<String, ProjectStats> = BTreeMap::new();
+    let mut unscoped = 0;
+
+    for entry in &entries {
+        let Some(id) = project::entry_project(entry, &mut cache) else {
+            unscoped += 1;
+            continue;
+        };
+        let s = stats.entry(id.clone()).or_insert_with(|| ProjectStats {
+            project: id,
+            entries: 0,
+            sessions: 0,
+            last_activity: String::new(),
+            categories: BTreeMap::new(),
+        });
+        s.entries += 1;
+        if entry.category == "sessions" {
+            s.sessions += 1;
+        }
+        if entry.timestamp > s.last_activity {
+            s.last_activity = entry.timestamp.clone();
+        }
+        *s.categories.entry(entry.category.clone()).or_default() += 1;
+    }
+
+    // Most recently active first
+    let mut stats: Vec<ProjectStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&stats)?),
+        OutputFormat::Text => {
+            println!("{}", "History by project:".bold());
+            println!();
+
+            if stats.is_empty() {
+                println!("  {}", "(no project-scoped history yet)".dimmed());
+            }
+            for s in &stats {
+                let last = chrono::DateTime::parse_from_rfc3339(&s.last_activity)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|_| s.last_activity.clone());
+                println!(
+                    "  {} ({} entries, {} sessions, last {})",
+                    s.project.cyan(),
+                    s.entries,
+                    s.sessions,
+                    last.dimmed()
+                );
+                let breakdown: Vec<String> = s.categories.iter().map(|(c, n)| format!("{}: {}", c, n)).collect();
+                println!("    {}", breakdown.join(", ").dimmed());
+            }
+            if unscoped > 0 {
+                println!();
+                println!("  {} entries without a project", unscoped.to_string().dimmed());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn stats(since: Option<NaiveDate>, until: Option<NaiveDate>, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let provider = memory::resolve(config);
+
+    let mut entries = provider.query_since("", "", since, usize::MAX)?;
+    if let Some(until) = until {
+        entries.retain(|e| e.date().is_some_and(|d| d <= until));
+    }
+    let journals = Journal::new(&history_dir).all()?;
+    let stats = stats::compute(&entries, &journals, since, until);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&stats)?),
+        OutputFormat::Text => print_stats(&stats),
+    }
+
+    Ok(())
+}
+
+fn print_stats(stats: &stats::Stats) {
+    let range = match (stats.since, stats.until) {
+        (None, None) => "all time".to_string(),
+        (since, until) => format!(
+            "{} – {}",
+            since.map(|d| d.to_string()).unwrap_or_else(|| "…".to_string()),
+            until.map(|d| d.to_string()).unwrap_or_else(|| "today".to_string())
+        ),
+    };
+    println!("{} ({})", "History statistics".bold(), range.dimmed());
+    println!();
+
+    println!("  {:22} {}", "Entries:".dimmed(), stats.entries);
+    println!("  {:22} {}", "Sessions:".dimmed(), stats.sessions);
+    match stats.avg_session_minutes {
+        Some(minutes) => println!("  {:22} {:.1} min", "Avg session length:".dimmed(), minutes),
+        None => println!("  {:22} {}", "Avg session length:".dimmed(), "n/a".dimmed()),
+    }
+    println!("  {:22} {}", "Security blocks:".dimmed(), stats.blocks.total);
+    println!();
+
+    print_counts(
+        "Sessions per week",
+        stats.sessions_per_week.iter().map(|(k, v)| (k.clone(), *v)),
+    );
+    print_counts(
+        "Sessions per day",
+        stats.sessions_per_day.iter().map(|(k, v)| (k.clone(), *v)),
+    );
+    print_counts(
+        "Busiest projects",
+        stats
+            .projects
+            .iter()
+            .map(|p| (format!("{} ({} entries)", p.project, p.entries), p.sessions)),
+    );
+    print_counts(
+        "Most-used tools",
+        stats.tools.iter().map(|t| {
+            let label = if t.failures > 0 {
+                format!("{} ({} failed)", t.tool, t.failures)
+            } else {
+                t.tool.clone()
+            };
+            (label, t.calls)
+        }),
+    );
+    print_counts(
+        "Blocks by reason",
+        stats.blocks.by_reason.iter().map(|(k, v)| (k.clone(), *v)),
+    );
+    print_counts("Tags", stats.tags.iter().map(|t| (t.tag.clone(), t.count)));
+}
+
+/// A titled two-column table with a proportional bar
+fn print_counts(title: &str, rows: impl Iterator<Item = (String, usize)>) {
+    let rows: Vec<(String, usize)> = rows.collect();
+    println!("{}:", title.cyan());
+    if rows.is_empty() {
+        println!("  {}", "(none)".dimmed());
+        println!();
+        return;
+    }
+
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    let max = rows.iter().map(|(_, n)| *n).max().unwrap_or(1).max(1);
+    for (label, count) in &rows {
+        let bar = "█".repeat((count * 30).div_ceil(max));
+        println!("  {:width$}  {:>5}  {}", label, count, bar.blue(), width = width);
+    }
+    println!();
+}
+
+fn prune(category: Option<&str>, dry_run: bool, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let store = HistoryStore::new(history_dir.clone());
+
+    // Journals have a default policy, so they are pruned even when no
+    // category has one
+    let prune_journals = category.is_none_or(|c| c == journal::RETENTION_KEY);
+    let mut policies: Vec<(&String, &crate::config::RetentionPolicy)> = match category {
+        Some(journal::RETENTION_KEY) => Vec::new(),
+        Some(c) => {
+            let (name, policy) = config
+                .history
+                .retention
+                .get_key_value(c)
+                .ok_or_else(|| eyre::eyre!("No retention policy configured for category '{}'", c))?;
+            vec![(name, policy)]
+        }
+        None => config
+            .history
+            .retention
+            .iter()
+            .filter(|(name, _)| name.as_str() != journal::RETENTION_KEY)
+            .collect(),
+    };
+    policies.sort_by_key(|(name, _)| name.as_str());
+
+    if policies.is_empty() && category.is_none() {
+        println!("{}", "No retention policies configured.".dimmed());
+        println!();
+        println!(
+            "Add a {} section to paii.toml, e.g.:",
+            "[history.retention.<category>]".cyan()
+        );
+        println!("  [history.retention.events]");
+        println!("  max_age_days = 30");
+        println!();
+    }
+
+    let now = chrono::Local::now();
+
+    for (name, policy) in policies {
+        let plan = retention::plan(&store, name, policy, now)?;
+
+        println!("{} {}", "→".blue(), name.cyan());
+        for item in &plan.remove {
+            let date = item.entry.created_at.format("%Y-%m-%d").to_string();
+            println!("  {} {} {}", "-".red(), date.dimmed(), item.entry.title);
+        }
+        println!(
+            "  {}{} removed, {} compacted, {} kept",
+            if dry_run { "(dry run) " } else { "" },
+            plan.remove.len(),
+            plan.compact.len(),
+            plan.kept
+        );
+
+        if !dry_run && !plan.is_empty() {
+            retention::apply(&store, &plan)?;
+        }
+    }
+
+    if prune_journals {
+        let journals = Journal::new(&history_dir);
+        let expired = journals.expired(&journal::retention(&config.history.retention), now.into())?;
+        println!("{} {}", "→".blue(), "session journals".cyan());
+        for path in &expired {
+            let session = path.file_stem().unwrap_or_default().to_string_lossy();
+            println!("  {} {}", "-".red(), session);
+        }
+        println!("  {}{} removed", if dry_run { "(dry run) " } else { "" }, expired.len());
+        if !dry_run {
+            for path in &expired {
+                fs::remove_file(path).context(format!("Failed to remove {}", path.display()))?;
+            }
+        }
+    }
+
+    if dry_run {
+        println!();
+        println!("{}", "Dry run: nothing was changed.".dimmed());
+    }
+
+    Ok(())
+}
+
+fn export(format: ExportFormat, filter: &HistoryFilter, output: Option<&Path>, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let store = HistoryStore::new(history_dir);
+
+    let entries = store.entries(filter)?;
+
+    let rendered = match format {
+        ExportFormat::Jsonl => export::to_jsonl(&entries)?,
+        ExportFormat::Markdown => export::to_markdown_bundle(&entries),
+        ExportFormat::Html => export::to_html(&entries),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).context(format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{} Exported {} entries to {}",
+                "✓".green(),
+                entries.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+fn import(file: &Path, format: Option<ExportFormat>, config: &Config) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut buffer = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buffer)
+            .context("Failed to read export from stdin")?;
+        buffer
+    } else {
+        fs::read_to_string(file).context(format!("Failed to read {}", file.display()))?
+    };
+
+    let format = match format {
+        Some(f) => f,
+        None => match file.extension().and_then(|e| e.to_str()) {
+            Some("md") | Some("markdown") => ExportFormat::Markdown,
+            Some("html") | Some("htm") => ExportFormat::Html,
+            _ => ExportFormat::Jsonl,
+        },
+    };
+
+    let entries = match format {
+        ExportFormat::Jsonl => export::from_jsonl(&content)?,
+        ExportFormat::Markdown => export::from_markdown_bundle(&content)?,
+        ExportFormat::Html => eyre::bail!("HTML reports can't be imported; export as jsonl or markdown instead"),
+    };
+
+    let history_dir = Config::expand_path(&config.paths.history);
+    let store = HistoryStore::new(history_dir);
+    let summary = export::import(&store, entries)?;
+
+    println!(
+        "{} Imported {} entries ({} skipped as duplicates)",
+        "✓".green(),
+        summary.imported,
+        summary.skipped
+    );
+    if summary.rejected > 0 {
+        println!(
+            "{} Rejected {} entries whose category or id isn't a plain name",
+            "⚠".yellow(),
+            summary.rejected
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_date(date: Option<&str>) -> Result<Option<NaiveDate>> {
+    date.map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .transpose()
+        .context("Invalid date format (use YYYY-MM-DD)")
+}
+
+fn print_entry_summary(entry: &MemoryResult) {
+    let date = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| entry.timestamp.clone());
+    println!("  {} {} {}", entry.category.cyan(), date.dimmed(), entry.title().bold());
+    let tags = entry.tags();
+    if !tags.is_empty() {
+        println!("    tags: {}", tags.join(", ").dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoryEntry, archive};
+
+    #[test]
+    fn test_show_finds_archived_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!("[paths]\nhistory = \"{}\"\n", tmp.path().display())).unwrap();
+
+        let entry = HistoryEntry::new("sessions", "Old session", "Archived work");
+        let month = archive::archive_path(&tmp.path().join("sessions"), "2020-01");
+        archive::write_archive(&month, std::slice::from_ref(&entry)).unwrap();
+
+        show(&entry.id, None, OutputFormat::Json, &config).unwrap();
+        assert!(show("missing", None, OutputFormat::Json, &config).is_err());
+    }
+
+    #[test]
+    fn test_project_listing_includes_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!("[paths]\nhistory = \"{}\"\n", tmp.path().display())).unwrap();
+        let provider = memory::resolve(&config);
+
+        let mut entry = HistoryEntry::new("sessions", "Old session", "Archived work");
+        entry.metadata.insert("project".to_string(), "paii".to_string());
+        let month = archive::archive_path(&tmp.path().join("sessions"), "2020-01");
+        archive::write_archive(&month, std::slice::from_ref(&entry)).unwrap();
+
+        let found = recent_entries(provider.as_ref(), "", 10, Some("paii")).unwrap();
+        assert_eq!(found.len(), 1);
+        let other = recent_entries(provider.as_ref(), "", 10, Some("other")).unwrap();
+        assert!(other.is_empty());
     }
 }
@@ -2,13 +2,30 @@
 
 use colored::*;
 use eyre::{Context, Result};
+use serde_json::json;
+use std::collections::HashMap;
 use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
 
 use crate::cli::HookAction;
+use crate::commands::skill;
 use crate::config::Config;
+use crate::contract::memory::{LazyMemoryProvider, MemoryProvider};
+use crate::history::journal::{self, Journal};
+use crate::history::project::{PROJECT_KEY, Project};
+use crate::history::stats;
+use crate::hook::compaction::CompactionHandler;
+use crate::hook::context::SessionContextHandler;
+use crate::hook::dispatch;
 use crate::hook::history::HistoryHandler;
+use crate::hook::learning::LearningHandler;
+use crate::hook::notification::NotificationHandler;
+use crate::hook::notify::{self, NotifyHandler};
 use crate::hook::security::SecurityValidator;
+use crate::hook::skills::SkillSuggestHandler;
 use crate::hook::{HookEvent, HookHandler, HookResult};
+use crate::notify::{BLOCK_EVENT, Notifier};
 
 pub fn run(action: HookAction, config: &Config) -> Result<()> {
     match action {
@@ -45,40 +62,101 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
     log::info!("Dispatching hook event: {:?}", hook_event);
     log::debug!("Payload: {}", payload);
 
-    // Build handlers list
-    let security_enabled = config.hooks.security_enabled;
-    let history_enabled = config.hooks.history_enabled;
-    let history_path = Config::expand_path(&config.paths.history);
-
-    let handlers: Vec<Box<dyn HookHandler>> = vec![
-        Box::new(SecurityValidator::new(security_enabled)),
-        Box::new(HistoryHandler::new(history_enabled, history_path)),
-    ];
+    let memory = Arc::new(LazyMemoryProvider::new(config));
+    let notifier = Arc::new(Notifier::new(&config.notifications));
+    let handlers = build_handlers(config, memory.clone(), notifier.clone());
 
     // Run all handlers for this event
-    for handler in &handlers {
-        if handler.handles(hook_event) {
-            let result = handler.handle(hook_event, &payload);
-
-            match &result {
-                HookResult::Block { message } => {
-                    // Print block message to stderr (Claude Code reads this)
-                    eprintln!("{}", message);
-                    std::process::exit(result.exit_code());
-                }
-                HookResult::Error { message } => {
-                    log::error!("Hook error: {}", message);
-                    // Continue - errors don't block
-                }
-                HookResult::Allow => {
-                    // Continue to next handler
-                }
+    let result = dispatch::dispatch(hook_event, &payload, &handlers);
+
+    match &result {
+        HookResult::Block { message } => {
+            if config.hooks.history_enabled {
+                record_block(memory.as_ref(), hook_event, &payload, message);
+            }
+            notifier.notify(BLOCK_EVENT, &notify::block_notification(&payload, message));
+            // Print block message to stderr (Claude Code reads this)
+            eprintln!("{}", message);
+        }
+        _ => {
+            if let Some(output) = result.output(hook_event) {
+                println!("{}", output);
             }
         }
     }
 
-    // All handlers passed
-    std::process::exit(0);
+    std::process::exit(result.exit_code());
+}
+
+/// Record a blocked action in the security history category
+fn record_block(memory: &dyn MemoryProvider, event: HookEvent, payload: &serde_json::Value, message: &str) {
+    let reason = message.trim_start_matches("🚨 BLOCKED:").trim();
+    let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let input = payload.get("tool_input").cloned().unwrap_or_default();
+
+    let mut metadata = HashMap::from([
+        ("title".to_string(), json!(format!("Blocked: {}", reason))),
+        ("tags".to_string(), json!([stats::BLOCK_TAG, tool.to_lowercase()])),
+        ("reason".to_string(), json!(reason)),
+        ("event".to_string(), json!(event)),
+        ("tool".to_string(), json!(tool)),
+    ]);
+    for key in ["session_id", "cwd"] {
+        if let Some(value) = payload.get(key).and_then(|v| v.as_str()) {
+            metadata.insert(key.to_string(), json!(value));
+        }
+    }
+    if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        metadata.insert(PROJECT_KEY.to_string(), json!(Project::detect(Path::new(cwd)).id));
+    }
+
+    let content = format!("{} `{}`", tool, journal::summarize_input(tool, &input));
+    if let Err(e) = memory.capture(stats::SECURITY_CATEGORY, &content, metadata) {
+        log::error!("Failed to record block: {}", e);
+    }
+}
+
+/// Build the built-in handlers, in dispatch order
+fn build_handlers(
+    config: &Config,
+    memory: Arc<dyn MemoryProvider>,
+    notifier: Arc<Notifier>,
+) -> Vec<Box<dyn HookHandler>> {
+    let history_dir = Config::expand_path(&config.paths.history);
+
+    vec![
+        Box::new(SecurityValidator::new(config.hooks.security_enabled)),
+        Box::new(
+            HistoryHandler::new(config.hooks.history_enabled, memory.clone())
+                .with_journal(
+                    Journal::new(&history_dir),
+                    config.hooks.capture_prompts,
+                    config.hooks.capture_tools,
+                )
+                .with_journal_retention(journal::retention(&config.history.retention)),
+        ),
+        Box::new(
+            LearningHandler::new(config.hooks.learning_enabled, memory.clone(), &history_dir)
+                .with_journaled_tools(config.hooks.history_enabled && config.hooks.capture_tools),
+        ),
+        Box::new(NotificationHandler::new(
+            config.hooks.notification_enabled,
+            memory.clone(),
+        )),
+        Box::new(CompactionHandler::new(config.hooks.compaction_enabled, memory.clone())),
+        Box::new(SessionContextHandler::new(
+            config.hooks.context_enabled,
+            memory,
+            config.context.clone(),
+        )),
+        Box::new(SkillSuggestHandler::new(
+            config.hooks.suggest_skills,
+            skill::scope_dirs(config),
+            Config::expand_path(&config.paths.plugins),
+            Config::expand_path(&config.paths.registries),
+        )),
+        Box::new(NotifyHandler::new(notifier)),
+    ]
 }
 
 fn list(event_filter: Option<&str>, _config: &Config) -> Result<()> {
@@ -94,3 +172,47 @@ fn list(event_filter: Option<&str>, _config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::provider::FileMemoryProvider;
+
+    #[test]
+    fn test_default_config_does_not_journal_tools() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!("[paths]\nhistory = \"{}\"\n", tmp.path().display())).unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(tmp.path().to_path_buf()));
+        let handlers = build_handlers(&config, memory, Arc::new(Notifier::new(&config.notifications)));
+
+        for exit_code in [0, 1] {
+            let payload = json!({
+                "session_id": "s1",
+                "tool_name": "Bash",
+                "tool_input": { "command": "cargo build" },
+                "tool_response": { "exit_code": exit_code }
+            });
+            dispatch::dispatch(HookEvent::PostToolUse, &payload, &handlers);
+        }
+        assert!(!tmp.path().join(journal::JOURNAL_DIR).exists());
+    }
+
+    #[test]
+    fn test_memory_resolved_only_when_used() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!(
+            "[paths]\nhistory = \"{0}/history\"\nplugins = \"{0}/plugins\"\n",
+            tmp.path().display()
+        ))
+        .unwrap();
+        let memory = Arc::new(LazyMemoryProvider::new(&config));
+        let handlers = build_handlers(&config, memory.clone(), Arc::new(Notifier::new(&config.notifications)));
+
+        let payload = json!({"session_id": "s1", "tool_name": "Bash", "tool_input": {"command": "ls"}});
+        dispatch::dispatch(HookEvent::PreToolUse, &payload, &handlers);
+        assert!(!memory.is_resolved());
+
+        dispatch::dispatch(HookEvent::Notification, &json!({"session_id": "s1"}), &handlers);
+        assert!(memory.is_resolved());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod apply;
 pub mod completions;
 pub mod config;
 pub mod doctor;
@@ -7,4 +8,5 @@ pub mod init;
 pub mod plugin;
 pub mod registry;
 pub mod run;
+pub mod skill;
 pub mod status;
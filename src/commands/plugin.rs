@@ -1,48 +1,31 @@
 use colored::*;
 use eyre::{Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
 use crate::cli::{OutputFormat, PluginAction};
-use crate::config::Config;
+use crate::commands::{registry, skill};
+use crate::config::{ClaudeScope, Config};
+use crate::plugin::agent;
+use crate::plugin::exec;
 use crate::plugin::loader::load_plugin;
-
-/// Registry plugin entry for lookup
-#[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
-struct RegistryPlugin {
-    name: String,
-    #[serde(default)]
-    version: Option<String>,
-    #[serde(default)]
-    description: Option<String>,
-    #[serde(default)]
-    source: Option<String>,
-    #[serde(default)]
-    path: Option<String>,
-}
-
-/// Parsed registry file
-#[derive(Debug, Deserialize)]
-struct RegistryFile {
-    #[allow(dead_code)]
-    registry: RegistryMeta,
-    #[serde(default)]
-    plugins: Vec<RegistryPlugin>,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct RegistryMeta {
-    name: String,
-}
+use crate::plugin::lock::{self, LockedPlugin, Lockfile};
+use crate::plugin::mcp::{self, McpConfig};
+use crate::plugin::skill::Skill;
+use crate::plugin::source::{GitSource, Source};
+use crate::plugin::version;
 
 pub fn run(action: PluginAction, config: &Config) -> Result<()> {
     match action {
         PluginAction::List { format } => list(OutputFormat::resolve(format), config),
-        PluginAction::Install { source, dev, force } => install(&source, dev, force, config),
+        PluginAction::Install {
+            source,
+            dev,
+            force,
+            max_age,
+        } => install(&source, dev, force, &registry::with_max_age(config, max_age)),
         PluginAction::Remove { name, force } => remove(&name, force, config),
         PluginAction::Update { name } => update(&name, config),
         PluginAction::Info { name } => info(&name, config),
@@ -53,6 +36,7 @@ pub fn run(action: PluginAction, config: &Config) -> Result<()> {
             path,
         } => new(&name, &language, &r#type, path.as_ref(), config),
         PluginAction::Verify { name } => verify(&name, config),
+        PluginAction::Sync { force } => sync(force, config),
     }
 }
 
@@ -142,7 +126,17 @@ fn list(format: OutputFormat, config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn install(source: &str, dev: bool, force: bool, config: &Config) -> Result<()> {
+pub fn install(source: &str, dev: bool, force: bool, config: &Config) -> Result<()> {
+    install_named(source, None, dev, force, config)
+}
+
+/// Install the plugin paii.toml declares as `[plugins.<name>]`, refusing a
+/// source whose plugin.toml names another plugin
+pub fn install_declared(name: &str, source: &str, dev: bool, force: bool, config: &Config) -> Result<()> {
+    install_named(source, Some(name), dev, force, config)
+}
+
+fn install_named(source: &str, expected: Option<&str>, dev: bool, force: bool, config: &Config) -> Result<()> {
     println!(
         "{} Installing plugin: {} {}{}",
         "→".blue(),
@@ -151,30 +145,56 @@ fn install(source: &str, dev: bool, force: bool, config: &Config) -> Result<()>
         if force { "(force) ".dimmed().to_string() } else { String::new() },
     );
 
-    let source_path = Path::new(source);
-
-    // Check if source is a local path or a plugin name from registry
-    if source_path.exists() {
-        // Local path installation
-        install_from_path(source_path, dev, force, config)
-    } else if !source.contains('/') && !source.contains('\\') {
-        // Looks like a plugin name - search registry
-        install_from_registry(source, force, config)
-    } else {
-        eyre::bail!(
-            "Source not found: {}. Use a local path or a plugin name from registry.\n\
+    match Source::parse(source) {
+        Some(Source::Path(path)) => {
+            if let Some(name) = expected {
+                check_name(&path, source, name)?;
+            }
+            install_from_path(&path, dev, force, LockedPlugin::local(&path, dev), config)
+        }
+        Some(Source::Git(git)) => {
+            if dev {
+                eyre::bail!("--dev links a local plugin directory; {} is a git source", source);
+            }
+            install_from_git(&git, expected, force, config)
+        }
+        Some(Source::Registry(spec)) => install_from_registry(&spec, force, config),
+        None => eyre::bail!(
+            "Source not found: {}. Use a local path, a git URL or owner/repo, or a plugin name from registry.\n\
              Hint: Run 'paii registry search {}' to find available plugins.",
             source,
             source
-        );
+        ),
     }
 }
 
 /// Install a plugin from a local path
-fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config) -> Result<()> {
+fn install_from_path(
+    source_path: &Path,
+    dev: bool,
+    force: bool,
+    mut locked: LockedPlugin,
+    config: &Config,
+) -> Result<()> {
     // Load and validate the plugin
     let plugin = load_plugin(source_path).context("Failed to load plugin from source")?;
     let plugin_name = &plugin.manifest.plugin.name;
+    version::parse_version(&plugin.manifest.plugin.version)?;
+    plugin
+        .manifest
+        .paii
+        .check()
+        .wrap_err(format!("Cannot install {}", plugin_name))?;
+
+    // Validate the skill and check for collisions before touching anything
+    let plugin_skill = Skill::load(source_path)?;
+    if let Some(ref s) = plugin_skill {
+        skill::check_collision(s, plugin_name, force, config)?;
+    }
+    let agents = agent::discover(source_path, &plugin.manifest)?;
+    check_agent_collisions(&agents, plugin_name, force, config)?;
+    let servers = mcp::load(source_path)?;
+    check_mcp_collisions(&servers, plugin_name, force, config)?;
 
     // Determine destination
     let plugins_dir = Config::expand_path(&config.paths.plugins);
@@ -195,6 +215,12 @@ fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config
         }
     }
 
+    // Drop what a previous install registered with Claude Code; the skill,
+    // agents and MCP servers this version still ships are registered below
+    skill::unsync_plugin(plugin_name, config)?;
+    unsync_agents(plugin_name, config)?;
+    unregister_mcp(plugin_name, config)?;
+
     // Create plugins directory if needed
     fs::create_dir_all(&plugins_dir).context("Failed to create plugins directory")?;
 
@@ -221,6 +247,29 @@ fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config
         println!("  {} Installed to {}", "✓".green(), dest_path.display());
     }
 
+    if plugin_skill.is_some() {
+        skill::sync_plugin(&dest_path, plugin_name, dev, config)?;
+    }
+    if !agents.is_empty() {
+        sync_agents(&dest_path, plugin_name, dev, config)?;
+    }
+    if !servers.is_empty() {
+        // Server commands resolve against the install's own virtualenv or
+        // binaries, which a copy leaves behind
+        if !dev {
+            exec::build(&dest_path, &plugin.manifest.build)?;
+        }
+        register_mcp(&dest_path, plugin_name, config)?;
+    }
+
+    locked.version = plugin.manifest.plugin.version.clone();
+    locked.hash = if dev {
+        None
+    } else {
+        Some(lock::content_hash(&dest_path)?)
+    };
+    lockfile(config)?.record(plugin_name, locked)?;
+
     println!(
         "  {} {} v{}",
         "✓".green(),
@@ -231,9 +280,10 @@ fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config
     Ok(())
 }
 
-/// Install a plugin from the registry by name
-fn install_from_registry(name: &str, force: bool, config: &Config) -> Result<()> {
-    println!("  {} Searching registries for '{}'...", "→".blue(), name);
+/// Install a plugin from the registry by `name` or `registry:name`
+fn install_from_registry(spec: &str, force: bool, config: &Config) -> Result<()> {
+    println!("  {} Searching registries for '{}'...", "→".blue(), spec);
+    registry::refresh_stale(config)?;
 
     // Search cached registries for the plugin
     let registries_dir = Config::expand_path(&config.paths.registries);
@@ -242,44 +292,11 @@ fn install_from_registry(name: &str, force: bool, config: &Config) -> Result<()>
         eyre::bail!("No cached registries. Run 'paii registry update' first.");
     }
 
-    let mut found_plugin: Option<(String, RegistryPlugin)> = None;
-
-    for entry in fs::read_dir(&registries_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().is_some_and(|e| e == "toml") {
-            let registry_name = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let content = fs::read_to_string(&path)?;
-            let registry: RegistryFile =
-                toml::from_str(&content).context(format!("Failed to parse registry: {}", registry_name))?;
-
-            for plugin in registry.plugins {
-                if plugin.name == name {
-                    found_plugin = Some((registry_name, plugin));
-                    break;
-                }
-            }
-
-            if found_plugin.is_some() {
-                break;
-            }
-        }
-    }
-
-    let (registry_name, plugin) = found_plugin.ok_or_else(|| {
-        eyre::eyre!(
-            "Plugin '{}' not found in any registry.\n\
-             Hint: Run 'paii registry search {}' to find similar plugins.",
-            name,
-            name
-        )
-    })?;
+    let registries = registry::cached(config);
+    let resolved = crate::plugin::registry::resolve(&registries, spec)?;
+    let (registry_name, plugin) = (resolved.registry, resolved.entry);
+    let name = plugin.name.as_str();
+    registry::verify_cached(registry_name, config).wrap_err("Refusing to install from an unverified registry")?;
 
     let version = plugin.version.as_deref().unwrap_or("unknown");
     println!(
@@ -289,46 +306,377 @@ fn install_from_registry(name: &str, force: bool, config: &Config) -> Result<()>
         version,
         registry_name.dimmed()
     );
+    if !resolved.shadowed.is_empty() {
+        println!(
+            "  {} '{}' is also in {}; using '{}' (higher priority). Install {}:{} to pick another.",
+            "⚠".yellow(),
+            name,
+            resolved.shadowed.join(", "),
+            registry_name,
+            resolved.shadowed[0],
+            name
+        );
+    }
 
-    // Get source and path
+    // Get source
     let source_url = plugin
         .source
         .as_ref()
         .ok_or_else(|| eyre::eyre!("Plugin '{}' has no source URL in registry", name))?;
-    let plugin_path = plugin.path.as_deref().unwrap_or("");
 
-    // Clone the repository to a temp directory
+    println!("  {} Cloning {}...", "→".blue(), source_url.dimmed());
+    let git_ref = plugin.commit.as_deref().or(plugin.git_ref.as_deref());
+    let (_temp_dir, plugin_source_path, commit) = clone_plugin(source_url, git_ref, plugin.path.as_deref())?;
+    if let Some(ref pinned) = plugin.commit
+        && !commit.starts_with(pinned.as_str())
+    {
+        eyre::bail!("{} is pinned to commit {} but the clone is at {}", name, pinned, commit);
+    }
+
+    check_name(&plugin_source_path, source_url, name)?;
+    if let Some(ref expected) = plugin.version {
+        let found = load_plugin(&plugin_source_path)?.manifest.plugin.version;
+        if found != *expected {
+            eyre::bail!(
+                "version mismatch for {}: registry '{}' lists v{}, source has v{}",
+                name,
+                registry_name,
+                expected,
+                found
+            );
+        }
+    }
+
+    if let Some(ref expected) = plugin.hash {
+        let actual = lock::content_hash(&plugin_source_path)?;
+        if actual != *expected {
+            eyre::bail!(
+                "content hash mismatch for {}: registry '{}' has {}, source has {}",
+                name,
+                registry_name,
+                expected,
+                actual
+            );
+        }
+        println!("  {} Content hash verified", "✓".green());
+    }
+
+    // Now install from the cloned path (not dev mode for registry installs)
+    let locked = LockedPlugin {
+        registry: Some(registry_name.to_string()),
+        source: Some(source_url.clone()),
+        path: plugin.path.clone(),
+        commit: Some(commit),
+        version_req: resolved.req.as_ref().map(|r| r.to_string()),
+        ..Default::default()
+    };
+    install_from_path(&plugin_source_path, false, force, locked, config)?;
+
+    // Temp dir is automatically cleaned up when it goes out of scope
+
+    Ok(())
+}
+
+/// Install a plugin straight from a git repository
+fn install_from_git(git: &GitSource, expected: Option<&str>, force: bool, config: &Config) -> Result<()> {
+    println!("  {} Cloning {}...", "→".blue(), git.to_string().dimmed());
+    let (_temp_dir, plugin_source_path, commit) =
+        clone_plugin(&git.url, git.git_ref.as_deref(), git.subdir.as_deref())?;
+    if let Some(name) = expected {
+        check_name(&plugin_source_path, &git.to_string(), name)?;
+    }
+
+    let locked = LockedPlugin {
+        source: Some(git.url.clone()),
+        path: git.subdir.clone(),
+        git_ref: git.git_ref.clone(),
+        commit: Some(commit),
+        ..Default::default()
+    };
+    install_from_path(&plugin_source_path, false, force, locked, config)
+}
+
+/// Fail unless the plugin at `path`, fetched from `source`, is named `name`
+fn check_name(path: &Path, source: &str, name: &str) -> Result<()> {
+    let plugin = load_plugin(path).context("Failed to load plugin from source")?;
+    if plugin.manifest.plugin.name != name {
+        eyre::bail!("{} is plugin '{}', not '{}'", source, plugin.manifest.plugin.name, name);
+    }
+    Ok(())
+}
+
+/// Clone a repository at `git_ref` into a temp directory, returning the
+/// directory, the plugin within it and the cloned commit
+fn clone_plugin(
+    url: &str,
+    git_ref: Option<&str>,
+    subdir: Option<&str>,
+) -> Result<(tempfile::TempDir, std::path::PathBuf, String)> {
     let temp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
     let clone_path = temp_dir.path().join("repo");
+    let commit = clone_repo(url, git_ref, &clone_path)?;
 
-    println!("  {} Cloning {}...", "→".blue(), source_url.dimmed());
+    // Find the plugin within the cloned repo
+    let plugin_path = clone_path.join(subdir.unwrap_or(""));
+    if !plugin_path.join("plugin.toml").exists() {
+        eyre::bail!("No plugin.toml at '{}' in repository {}", subdir.unwrap_or("."), url);
+    }
+    Ok((temp_dir, plugin_path, commit))
+}
+
+/// Refuse a URL or ref git would take for an option
+pub fn check_git_args(url: &str, git_ref: Option<&str>) -> Result<()> {
+    if url.starts_with('-') {
+        eyre::bail!("Invalid repository URL: {}", url);
+    }
+    if let Some(git_ref) = git_ref.filter(|r| r.starts_with('-')) {
+        eyre::bail!("Invalid git ref: {}", git_ref);
+    }
+    Ok(())
+}
+
+/// Clone `url` into `dest`, checking out `git_ref` if given, and return
+/// the commit checked out
+pub fn clone_repo(url: &str, git_ref: Option<&str>, dest: &Path) -> Result<String> {
+    check_git_args(url, git_ref)?;
 
-    let status = Command::new("git")
-        .args(["clone", "--depth", "1", source_url, clone_path.to_str().unwrap()])
+    // A ref needs full history to check out; otherwise a shallow clone will do
+    let mut clone = Command::new("git");
+    clone.arg("clone");
+    if git_ref.is_none() {
+        clone.args(["--depth", "1"]);
+    }
+    let status = clone
+        .arg("--")
+        .arg(url)
+        .arg(dest)
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::piped())
         .status()
         .context("Failed to run git clone")?;
 
     if !status.success() {
-        eyre::bail!("Git clone failed for: {}", source_url);
+        eyre::bail!("Git clone failed for: {}", url);
     }
 
-    // Find the plugin within the cloned repo
-    let plugin_source_path = if plugin_path.is_empty() { clone_path } else { clone_path.join(plugin_path) };
+    if let Some(git_ref) = git_ref {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dest)
+            .args(["checkout", "--quiet", git_ref])
+            .stderr(std::process::Stdio::piped())
+            .status()
+            .context("Failed to run git checkout")?;
+        if !status.success() {
+            eyre::bail!("Ref '{}' not found in {}", git_ref, url);
+        }
+    }
 
-    if !plugin_source_path.exists() {
-        eyre::bail!("Plugin path '{}' not found in repository {}", plugin_path, source_url);
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dest)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .context("Failed to run git rev-parse")?;
+    if !output.status.success() {
+        eyre::bail!("Could not read the cloned commit of {}", url);
     }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
 
-    // Now install from the cloned path (not dev mode for registry installs)
-    install_from_path(&plugin_source_path, false, force, config)?;
+/// Install the plugins recorded in paii.lock at their locked commits
+fn sync(force: bool, config: &Config) -> Result<()> {
+    let lock = lockfile(config)?;
+    println!("{} Syncing plugins from {}", "→".blue(), lock.path().display());
 
-    // Temp dir is automatically cleaned up when it goes out of scope
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let mut failed = 0;
+    for (name, locked) in lock.iter() {
+        let dest = plugins_dir.join(name);
+        if !force && matches_lock(&dest, locked) {
+            println!("  {} {} v{} (up to date)", "✓".green(), name, locked.version);
+            continue;
+        }
+        if let Err(e) = install_locked(name, locked, config) {
+            println!("  {} {}: {:#}", "✗".red(), name, e);
+            failed += 1;
+        }
+    }
+
+    // Plugins installed by hand but never recorded
+    if let Ok(entries) = fs::read_dir(&plugins_dir) {
+        let mut extra: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("plugin.toml").exists())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| lock.get(n).is_none())
+            .collect();
+        extra.sort();
+        for name in extra {
+            println!("  {} {} is installed but not in the lockfile", "⚠".yellow(), name);
+        }
+    }
+
+    if failed > 0 {
+        eyre::bail!("{} plugin(s) failed to sync", failed);
+    }
+    Ok(())
+}
+
+/// Whether an installed plugin is the locked version with the locked files
+fn matches_lock(dest: &Path, locked: &LockedPlugin) -> bool {
+    let Ok(installed) = load_plugin(dest) else {
+        return false;
+    };
+    installed.manifest.plugin.version == locked.version
+        && match locked.hash {
+            Some(ref hash) if !locked.dev => lock::content_hash(dest).is_ok_and(|h| h == *hash),
+            _ => true,
+        }
+}
+
+/// Reinstall one plugin exactly as locked, refusing files that don't
+/// match the locked hash
+fn install_locked(name: &str, locked: &LockedPlugin, config: &Config) -> Result<()> {
+    let source = locked
+        .source
+        .as_deref()
+        .ok_or_else(|| eyre::eyre!("no source recorded in the lockfile"))?;
+
+    let temp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
+    let source_path = match locked.commit {
+        Some(ref commit) => {
+            let clone_path = temp_dir.path().join("repo");
+            println!(
+                "  {} Cloning {} at {}...",
+                "→".blue(),
+                source.dimmed(),
+                &commit[..commit.len().min(12)]
+            );
+            clone_repo(source, Some(commit), &clone_path)?;
+            clone_path.join(locked.path.as_deref().unwrap_or(""))
+        }
+        None => Path::new(source).to_path_buf(),
+    };
+    if !source_path.join("plugin.toml").exists() {
+        eyre::bail!("no plugin at {}", source_path.display());
+    }
+
+    if let Some(ref expected) = locked.hash
+        && !locked.dev
+    {
+        let actual = lock::content_hash(&source_path)?;
+        if actual != *expected {
+            eyre::bail!(
+                "content hash mismatch for {}: lockfile has {}, source has {}",
+                name,
+                expected,
+                actual
+            );
+        }
+    }
+
+    install_from_path(&source_path, locked.dev, true, locked.clone(), config)
+}
+
+/// The configured lockfile
+pub fn lockfile(config: &Config) -> Result<Lockfile> {
+    Lockfile::load(&Config::expand_path(&config.paths.lockfile))
+}
+
+/// Agents directory for a scope
+fn agents_dir(config: &Config, scope: ClaudeScope) -> std::path::PathBuf {
+    config.claude.scope_dir(scope).join("agents")
+}
 
+/// Fail if installing `agents` would overwrite agents paii doesn't own.
+/// With `force` the collision is reported and the agents replaced.
+fn check_agent_collisions(agents: &[agent::Agent], plugin: &str, force: bool, config: &Config) -> Result<()> {
+    match agent::check_collisions(agents, plugin, &agents_dir(config, config.claude.scope)) {
+        Err(e) if force => {
+            println!("  {} {} (replacing)", "⚠".yellow(), e);
+            Ok(())
+        }
+        Err(e) => Err(e.wrap_err("Use --force to replace it")),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Sync the agents of an installed plugin into the configured scope
+fn sync_agents(plugin_dir: &Path, plugin: &str, link: bool, config: &Config) -> Result<()> {
+    let installed = load_plugin(plugin_dir)?;
+    let agents = agent::discover(plugin_dir, &installed.manifest)?;
+    let dir = agents_dir(config, config.claude.scope);
+
+    for target in agent::sync(&agents, plugin, &dir, link)? {
+        println!("  {} Agent → {}", "✓".green(), target.display());
+    }
     Ok(())
 }
 
+/// Remove a plugin's agents from every scope
+fn unsync_agents(plugin: &str, config: &Config) -> Result<Vec<String>> {
+    let mut removed = agent::unsync(plugin, &agents_dir(config, ClaudeScope::User))?;
+    let project = agents_dir(config, ClaudeScope::Project);
+    if project != agents_dir(config, ClaudeScope::User) {
+        removed.extend(agent::unsync(plugin, &project)?);
+    }
+    Ok(removed)
+}
+
+/// Claude Code MCP config for a scope
+fn mcp_config(config: &Config, scope: ClaudeScope) -> McpConfig {
+    McpConfig::new(
+        config.claude.mcp_config_path(scope),
+        config.claude.scope_dir(scope).join(mcp::OWNERS_FILE),
+    )
+}
+
+/// Fail if installing `servers` would replace MCP servers paii doesn't own.
+/// With `force` the collision is reported and the servers replaced.
+fn check_mcp_collisions(
+    servers: &std::collections::BTreeMap<String, mcp::McpServer>,
+    plugin: &str,
+    force: bool,
+    config: &Config,
+) -> Result<()> {
+    match mcp_config(config, config.claude.scope).check_collisions(servers, plugin) {
+        Err(e) if force => {
+            println!("  {} {} (replacing)", "⚠".yellow(), e);
+            Ok(())
+        }
+        Err(e) => Err(e.wrap_err("Use --force to replace it")),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Register the MCP servers of an installed plugin in the configured scope
+fn register_mcp(plugin_dir: &Path, plugin: &str, config: &Config) -> Result<()> {
+    let servers = mcp::load(plugin_dir)?;
+    let target = mcp_config(config, config.claude.scope);
+
+    for name in target.register(&servers, plugin)? {
+        println!(
+            "  {} MCP server {} → {}",
+            "✓".green(),
+            name.cyan(),
+            target.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Remove a plugin's MCP servers from every scope
+fn unregister_mcp(plugin: &str, config: &Config) -> Result<Vec<String>> {
+    let user = mcp_config(config, ClaudeScope::User);
+    let project = mcp_config(config, ClaudeScope::Project);
+    let mut removed = user.unregister(plugin)?;
+    if project.path != user.path {
+        removed.extend(project.unregister(plugin)?);
+    }
+    Ok(removed)
+}
+
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
 
@@ -338,10 +686,8 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
         let dst_path = dst.join(entry.file_name());
 
         if src_path.is_dir() {
-            // Skip target directories and hidden directories
-            let name = entry.file_name();
-            let name_str = name.to_string_lossy();
-            if name_str == "target" || name_str.starts_with('.') {
+            // Skip build output, caches and hidden directories
+            if lock::is_ignored_dir(&entry.file_name().to_string_lossy()) {
                 continue;
             }
             copy_dir_recursive(&src_path, &dst_path)?;
@@ -353,7 +699,7 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
-fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
+pub fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
     println!(
         "{} Removing plugin: {} {}",
         "→".blue(),
@@ -368,6 +714,16 @@ fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
         eyre::bail!("Plugin not found: {}", name);
     }
 
+    for removed in skill::unsync_plugin(name, config)? {
+        println!("  {} Removed skill: {}", "✓".green(), removed);
+    }
+    for removed in unsync_agents(name, config)? {
+        println!("  {} Removed agent: {}", "✓".green(), removed);
+    }
+    for removed in unregister_mcp(name, config)? {
+        println!("  {} Removed MCP server: {}", "✓".green(), removed);
+    }
+
     // Check if it's a symlink
     let is_symlink = plugin_path.symlink_metadata()?.file_type().is_symlink();
 
@@ -377,6 +733,8 @@ fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
         fs::remove_dir_all(&plugin_path).context("Failed to remove plugin directory")?;
     }
 
+    lockfile(config)?.forget(name)?;
+
     println!("  {} Removed plugin: {}", "✓".green(), name);
 
     Ok(())
@@ -405,35 +763,27 @@ fn update(name: &str, config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    // Look up in registry to get source
-    let registries_dir = Config::expand_path(&config.paths.registries);
-    let mut found_plugin: Option<RegistryPlugin> = None;
-
-    if registries_dir.exists() {
-        for entry in fs::read_dir(&registries_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().is_some_and(|e| e == "toml") {
-                let content = fs::read_to_string(&path)?;
-                if let Ok(registry) = toml::from_str::<RegistryFile>(&content) {
-                    for p in registry.plugins {
-                        if p.name == name {
-                            found_plugin = Some(p);
-                            break;
-                        }
-                    }
-                }
-                if found_plugin.is_some() {
-                    break;
-                }
-            }
-        }
+    // Look up the plugin in the registry it was installed from, keeping the
+    // version constraint it was installed with
+    let origin = lockfile(config)?.get(name).cloned().unwrap_or_default();
+    if origin.registry.is_none()
+        && origin.commit.is_some()
+        && let Some(ref url) = origin.source
+    {
+        return update_from_git(name, url, origin.clone(), config);
     }
-
-    let registry_plugin = match found_plugin {
-        Some(p) => p,
-        None => {
+    let mut spec = match origin.registry {
+        Some(ref registry) => format!("{}:{}", registry, name),
+        None => name.to_string(),
+    };
+    if let Some(ref req) = origin.version_req {
+        spec = format!("{}@{}", spec, req);
+    }
+    let registries = registry::cached(config);
+    let registry_plugin = match crate::plugin::registry::resolve(&registries, &spec) {
+        Ok(resolved) => resolved.entry,
+        Err(e) if origin.version_req.is_some() => return Err(e),
+        Err(_) => {
             println!("  {} Plugin not found in registry, cannot update", "⚠".yellow());
             println!("    Try reinstalling from source");
             return Ok(());
@@ -441,21 +791,58 @@ fn update(name: &str, config: &Config) -> Result<()> {
     };
 
     let new_version = registry_plugin.version.as_deref().unwrap_or("unknown");
-    if new_version == current_version {
-        println!("  {} Already at latest version ({})", "✓".green(), new_version);
+    let newer = match (registry_plugin.semver(), version::parse_version(current_version)) {
+        (Some(new), Ok(current)) => new > current,
+        _ => new_version != current_version,
+    };
+    if !newer {
+        println!(
+            "  {} Already at latest compatible version ({})",
+            "✓".green(),
+            current_version
+        );
         return Ok(());
     }
 
     println!("  New version available: {}", new_version.green());
 
     // Reinstall from registry (force)
-    install_from_registry(name, true, config)?;
+    install_from_registry(&spec, true, config)?;
 
     println!("  {} Updated {} to v{}", "✓".green(), name, new_version);
 
     Ok(())
 }
 
+/// Re-fetch a plugin installed straight from git, following its recorded ref
+fn update_from_git(name: &str, url: &str, locked: LockedPlugin, config: &Config) -> Result<()> {
+    let source = GitSource {
+        url: url.to_string(),
+        git_ref: locked.git_ref.clone(),
+        subdir: locked.path.clone(),
+    };
+    println!("  {} Fetching {}...", "→".blue(), source.to_string().dimmed());
+    let (_temp_dir, plugin_source_path, commit) = clone_plugin(url, locked.git_ref.as_deref(), locked.path.as_deref())?;
+
+    if locked.commit.as_deref() == Some(commit.as_str()) {
+        println!(
+            "  {} Already at latest commit ({})",
+            "✓".green(),
+            &commit[..commit.len().min(12)]
+        );
+        return Ok(());
+    }
+
+    println!("  New commit: {}", commit[..commit.len().min(12)].green());
+    let locked = LockedPlugin {
+        commit: Some(commit),
+        ..locked
+    };
+    install_from_path(&plugin_source_path, false, true, locked, config)?;
+    println!("  {} Updated {}", "✓".green(), name);
+    Ok(())
+}
+
 fn info(name: &str, config: &Config) -> Result<()> {
     let plugin = find_plugin(name, config)?;
 
@@ -482,6 +869,22 @@ fn info(name: &str, config: &Config) -> Result<()> {
         println!("  {} {}", "Repository:".dimmed(), repo);
     }
 
+    if let Some(origin) = lockfile(config)?.get(&plugin.manifest.plugin.name) {
+        if let Some(ref registry) = origin.registry {
+            println!("  {} {}", "Registry:".dimmed(), registry);
+        }
+        if let Some(ref source) = origin.source {
+            let path = origin.path.as_deref().map(|p| format!(" ({})", p)).unwrap_or_default();
+            println!("  {} {}{}", "Source:".dimmed(), source, path.dimmed());
+        }
+        if let Some(ref git_ref) = origin.git_ref {
+            println!("  {} {}", "Ref:".dimmed(), git_ref);
+        }
+        if let Some(ref commit) = origin.commit {
+            println!("  {} {}", "Commit:".dimmed(), commit);
+        }
+    }
+
     // Show provides/consumes
     if !plugin.manifest.provides.is_empty() {
         println!();
@@ -500,11 +903,78 @@ fn info(name: &str, config: &Config) -> Result<()> {
         }
     }
 
+    match agent::discover(&plugin.path, &plugin.manifest) {
+        Ok(agents) if !agents.is_empty() => {
+            println!();
+            println!("  {}:", "Agents".cyan());
+            for a in &agents {
+                let tools = a.frontmatter.tool_list();
+                println!(
+                    "    - {} {}{}",
+                    a.name().green(),
+                    format!("[{}]", a.frontmatter.model.as_deref().unwrap_or("default")).dimmed(),
+                    if tools.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" tools: {}", tools.join(", ")).dimmed().to_string()
+                    }
+                );
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!();
+            println!("  {} Agents: {:#}", "✗".red(), e);
+        }
+    }
+
+    match mcp::load(&plugin.path) {
+        Ok(servers) if !servers.is_empty() => {
+            println!();
+            println!("  {}:", "MCP servers".cyan());
+            for (name, server) in &servers {
+                let target = match server.command {
+                    Some(ref command) => std::iter::once(command.as_str())
+                        .chain(server.args.iter().map(String::as_str))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    None => server
+                        .extra
+                        .get("url")
+                        .and_then(|u| u.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                };
+                println!("    - {} {}", name.green(), target.dimmed());
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!();
+            println!("  {} MCP servers: {:#}", "✗".red(), e);
+        }
+    }
+
+    let events = plugin.manifest.hooks.events();
+    if !events.is_empty() {
+        println!();
+        println!("  {}:", "Hooks".cyan());
+        for event in events {
+            println!("    - {:?}", event);
+        }
+    }
+
     Ok(())
 }
 
-/// Find a plugin by name in the plugins directory
+/// Find a plugin by name in the plugins directory, with its paii.toml overrides
 pub fn find_plugin(name: &str, config: &Config) -> Result<crate::plugin::Plugin> {
+    let mut plugin = find_installed(name, config)?;
+    plugin.config = config.plugin_overrides(&plugin.manifest.plugin.name);
+    Ok(plugin)
+}
+
+fn find_installed(name: &str, config: &Config) -> Result<crate::plugin::Plugin> {
     let plugins_dir = Config::expand_path(&config.paths.plugins);
 
     // Try exact path first
@@ -644,6 +1114,11 @@ post_tool_use = false
 stop = false
 session_start = false
 session_end = false
+subagent_stop = false
+notification = false
+user_prompt_submit = false
+permission_request = false
+pre_compact = false
 
 # Build configuration
 [build]
@@ -765,7 +1240,12 @@ dev-dependencies = []
 
 fn generate_skill_md(name: &str) -> String {
     format!(
-        r#"# {name}
+        r#"---
+name: {name}
+description: Actions for {name}. Use when the user asks about {name} or wants to perform {name} actions.
+---
+
+# {name}
 
 ## USE WHEN
 
@@ -873,8 +1353,116 @@ fn verify(name: &str, config: &Config) -> Result<()> {
         println!("  {} SKILL.md found", "✓".green());
     }
 
+    // The hash a registry entry pins with `hash = "..."`
+    println!("  {} Content hash: {}", "ℹ".blue(), lock::content_hash(&plugin.path)?);
+
     println!();
     println!("  {} Plugin verification complete", "✓".green());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_args_are_not_options() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("repo");
+        let err = clone_repo("--upload-pack=touch pwned", None, &dest).unwrap_err();
+        assert!(err.to_string().contains("Invalid repository URL"));
+        let err = clone_repo("https://github.com/owner/repo.git", Some("--orphan"), &dest).unwrap_err();
+        assert!(err.to_string().contains("Invalid git ref"));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_reinstall_drops_removed_integrations() {
+        let tmp = tempfile::tempdir().unwrap();
+        let claude = tmp.path().join("home/.claude");
+        let config: Config = toml::from_str(&format!(
+            "[paths]\nplugins = \"{0}/plugins\"\nlockfile = \"{0}/paii.lock\"\n[claude]\ndir = \"{1}\"\n",
+            tmp.path().display(),
+            claude.display()
+        ))
+        .unwrap();
+
+        let source = tmp.path().join("src");
+        fs::create_dir_all(source.join("agents")).unwrap();
+        fs::write(
+            source.join("plugin.toml"),
+            "[plugin]\nname = \"triage\"\nversion = \"0.1.0\"\ndescription = \"d\"\n",
+        )
+        .unwrap();
+        fs::write(
+            source.join("agents/triager.md"),
+            "---\nname: triager\ndescription: Triages\n---\n\nYou triage.\n",
+        )
+        .unwrap();
+        fs::write(
+            source.join(mcp::MCP_FILE),
+            r#"{"mcpServers": {"triage": {"command": "triage-mcp"}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            source.join("SKILL.md"),
+            "---\nname: triage\ndescription: Triage incidents\n---\n\nTriage.\n",
+        )
+        .unwrap();
+
+        let path = source.to_str().unwrap();
+        install(path, false, false, &config).unwrap();
+        let agent_file = claude.join("agents/triager.md");
+        let claude_json = tmp.path().join("home/.claude.json");
+        let skill_dir = claude.join("skills/triage");
+        assert!(agent_file.exists());
+        assert!(skill_dir.exists());
+        assert!(fs::read_to_string(&claude_json).unwrap().contains("triage-mcp"));
+
+        // The next version ships no skill, agents or MCP servers
+        fs::remove_dir_all(source.join("agents")).unwrap();
+        fs::remove_file(source.join(mcp::MCP_FILE)).unwrap();
+        fs::remove_file(source.join("SKILL.md")).unwrap();
+        install(path, false, true, &config).unwrap();
+        assert!(!agent_file.exists());
+        assert!(!skill_dir.exists());
+        assert!(!fs::read_to_string(&claude_json).unwrap().contains("triage-mcp"));
+    }
+
+    #[test]
+    fn test_copied_install_builds_mcp_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!(
+            "[paths]\nplugins = \"{0}/plugins\"\nlockfile = \"{0}/paii.lock\"\n[claude]\ndir = \"{0}/home/.claude\"\n",
+            tmp.path().display()
+        ))
+        .unwrap();
+
+        // Build output in the source is never copied; the install builds its own
+        let source = tmp.path().join("src");
+        fs::create_dir_all(source.join("target/release")).unwrap();
+        fs::write(source.join("target/release/lookup"), "").unwrap();
+        fs::write(
+            source.join("plugin.toml"),
+            "[plugin]\nname = \"lookup\"\nversion = \"0.1.0\"\ndescription = \"d\"\n\n\
+             [build]\ntype = \"custom\"\nbuild_command = \"mkdir -p target/release && touch target/release/lookup\"\n",
+        )
+        .unwrap();
+        fs::write(
+            source.join(mcp::MCP_FILE),
+            r#"{"mcpServers": {"lookup": {"command": "lookup"}}}"#,
+        )
+        .unwrap();
+
+        install(source.to_str().unwrap(), false, false, &config).unwrap();
+        let binary = tmp.path().join("plugins/lookup/target/release/lookup");
+        assert!(binary.exists());
+        let claude_json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(tmp.path().join("home/.claude.json")).unwrap()).unwrap();
+        assert_eq!(
+            claude_json["mcpServers"]["lookup"]["command"],
+            binary.display().to_string().as_str()
+        );
+    }
+}
@@ -1,48 +1,23 @@
+use chrono::Utc;
 use colored::*;
 use eyre::{Context, Result};
-use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use crate::cli::{OutputFormat, RegistryAction};
+use crate::commands::plugin::{check_git_args, clone_repo};
 use crate::config::Config;
-
-/// Parsed registry file
-#[derive(Debug, Deserialize)]
-struct RegistryFile {
-    #[allow(dead_code)]
-    registry: RegistryMeta,
-    #[serde(default)]
-    plugins: Vec<PluginEntry>,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct RegistryMeta {
-    name: String,
-    #[serde(default)]
-    description: Option<String>,
-    #[serde(default)]
-    version: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct PluginEntry {
-    name: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    version: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    source: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    path: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    language: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    r#type: Option<String>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    tags: Vec<String>,
-}
+use crate::plugin::agent;
+use crate::plugin::lock;
+use crate::plugin::manifest::PluginManifest;
+use crate::plugin::mcp;
+use crate::plugin::publish::{self, Published};
+use crate::plugin::registry::{self, CacheMeta, CachedRegistry, Registry, RegistryEntry, RegistryMeta};
+use crate::plugin::signing;
+use crate::plugin::skill::Skill;
+use crate::plugin::source::GitSource;
 
 pub fn run(action: RegistryAction, config: &Config) -> Result<()> {
     match action {
@@ -50,13 +25,50 @@ pub fn run(action: RegistryAction, config: &Config) -> Result<()> {
         RegistryAction::Add { name, url } => add(&name, &url, config),
         RegistryAction::Remove { name } => remove(&name, config),
         RegistryAction::Update { name } => update(name.as_deref(), config),
-        RegistryAction::Search { query, format } => search(&query, OutputFormat::resolve(format), config),
+        RegistryAction::Search {
+            query,
+            provides,
+            max_age,
+            format,
+        } => search(
+            query.as_deref(),
+            provides.as_deref(),
+            OutputFormat::resolve(format),
+            &with_max_age(config, max_age),
+        ),
         RegistryAction::Show { name, format } => show(&name, OutputFormat::resolve(format), config),
+        RegistryAction::Keygen { output, force } => keygen(&output, force),
+        RegistryAction::Sign { file, key } => sign(&file, &key),
+        RegistryAction::Verify { file } => verify(&file, config),
+        RegistryAction::Init {
+            file,
+            name,
+            description,
+            maintainer,
+            force,
+        } => init(
+            &file,
+            RegistryMeta {
+                name,
+                description,
+                maintainer,
+                ..Default::default()
+            },
+            force,
+        ),
+        RegistryAction::Publish {
+            plugin,
+            registry,
+            source,
+            git_ref,
+            hash,
+        } => publish_plugin(&plugin, &registry, source, git_ref, hash),
+        RegistryAction::Lint { file, local } => lint(&file, local.as_deref()),
     }
 }
 
 fn list(config: &Config) -> Result<()> {
-    println!("{}", "Configured registries:".bold());
+    println!("{}", "Configured registries (highest priority first):".bold());
     println!();
 
     if config.registries.is_empty() {
@@ -66,7 +78,8 @@ fn list(config: &Config) -> Result<()> {
 
     let registries_dir = Config::expand_path(&config.paths.registries);
 
-    for (name, url) in &config.registries {
+    for (rank, name) in config.registry_order().iter().enumerate() {
+        let url = &config.registries[name];
         // Check if we have a cached listing
         let cache_file = registries_dir.join(format!("{}.toml", name));
         let status = if cache_file.exists() {
@@ -74,7 +87,17 @@ fn list(config: &Config) -> Result<()> {
         } else {
             "○".dimmed().to_string()
         };
-        println!("  {} {}: {}", status, name.cyan(), url.dimmed());
+        println!(
+            "  {} {}. {}: {}{}",
+            status,
+            rank + 1,
+            name.cyan(),
+            url.dimmed(),
+            cache_age(&cache_file)
+                .map(|a| format!(" (fetched {})", a))
+                .unwrap_or_default()
+                .dimmed()
+        );
     }
 
     Ok(())
@@ -115,6 +138,7 @@ fn remove(name: &str, config: &Config) -> Result<()> {
     // Update config
     let mut new_config = config.clone();
     new_config.registries.remove(name);
+    new_config.registry_priority.retain(|n| n != name);
 
     // Save config
     let config_path = Config::paii_dir().join("paii.toml");
@@ -127,6 +151,9 @@ fn remove(name: &str, config: &Config) -> Result<()> {
     if cache_file.exists() {
         fs::remove_file(&cache_file).ok();
     }
+    fs::remove_file(signature_path(&cache_file)).ok();
+    fs::remove_file(registry::sidecar(&cache_file, "meta")).ok();
+    fs::remove_dir_all(registries_dir.join(format!("{}.repo", name))).ok();
 
     println!("  {} Removed registry: {}", "✓".green(), name);
 
@@ -153,111 +180,634 @@ fn update(name: Option<&str>, config: &Config) -> Result<()> {
         return Ok(());
     }
 
+    let mut failed = 0;
     for (reg_name, url) in &registries_to_update {
         println!("{} Updating registry: {}", "→".blue(), reg_name.cyan());
 
-        // For now, we can't actually fetch URLs without an HTTP client
-        // Just create a placeholder or check if it's a local file
-        if url.starts_with("file://") || url.starts_with('/') || url.starts_with('.') {
-            // Local file registry
-            let path = url.trim_start_matches("file://");
-            if std::path::Path::new(path).exists() {
-                let content = fs::read_to_string(path).context("Failed to read local registry")?;
-                let cache_file = registries_dir.join(format!("{}.toml", reg_name));
-                fs::write(&cache_file, content).context("Failed to cache registry")?;
-                println!("  {} Cached from local file", "✓".green());
+        match refresh(reg_name, url, config) {
+            Ok(Refreshed::Fetched { done, signed_by }) => {
+                if let Some(key) = signed_by {
+                    println!("  {} Signature verified (key '{}')", "✓".green(), key);
+                }
+                println!("  {} {}", "✓".green(), done);
+            }
+            Ok(Refreshed::NotModified) => println!("  {} Not modified; cache is current", "✓".green()),
+            Err(e) => {
+                println!("  {} {:#}", "✗".red(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eyre::bail!("{} registry(s) failed to update", failed);
+    }
+    Ok(())
+}
+
+/// What refreshing a cached registry did
+enum Refreshed {
+    Fetched {
+        done: String,
+        signed_by: Option<String>,
+    },
+    /// The server answered 304, or a git registry is at the cached commit;
+    /// only the metadata was touched
+    NotModified,
+}
+
+/// The file a local registry URL names: `file://` URLs and absolute, relative
+/// or `~` paths, with `~` and environment variables expanded
+fn local_path(url: &str) -> Option<PathBuf> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return Some(Config::expand_path(Path::new(path)));
+    }
+    if url.contains("://") || url.starts_with("git+") {
+        return None;
+    }
+    let path = Config::expand_path(Path::new(url));
+    (path.is_absolute() || url.starts_with('.')).then_some(path)
+}
+
+/// Fetch one registry into the cache.
+///
+/// Remote registries are requested conditionally using the cached ETag and
+/// Last-Modified; `git+` registries are fetched into a checkout kept next to
+/// the cache. On any failure, including a body that isn't a registry or a
+/// bad signature, the previous cache is left as it was.
+fn refresh(name: &str, url: &str, config: &Config) -> Result<Refreshed> {
+    let registries_dir = Config::expand_path(&config.paths.registries);
+    fs::create_dir_all(&registries_dir).context("Failed to create registries directory")?;
+    let cache_file = registries_dir.join(format!("{}.toml", name));
+    let previous = CacheMeta::load(&cache_file).filter(|m| m.url == url && cache_file.exists());
+
+    let mut meta = CacheMeta {
+        url: url.to_string(),
+        fetched_at: Some(Utc::now()),
+        ..Default::default()
+    };
+    let (content, signature, done) = if let Some(spec) = url.strip_prefix("git+") {
+        let (content, signature, commit) = fetch_git_registry(name, spec, &registries_dir)?;
+        if previous.as_ref().and_then(|p| p.commit.as_deref()) == Some(commit.as_str()) {
+            meta.commit = Some(commit);
+            meta.save(&cache_file)?;
+            return Ok(Refreshed::NotModified);
+        }
+        let done = format!("Cached from commit {}", &commit[..commit.len().min(12)]);
+        meta.commit = Some(commit);
+        (content, signature, done)
+    } else if let Some(path) = local_path(url) {
+        // Local file registry
+        if !path.exists() {
+            eyre::bail!("Local file not found: {}", path.display());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read local registry")?;
+        let signature = fs::read_to_string(format!("{}.{}", path.display(), signing::SIGNATURE_EXT)).ok();
+        (content, signature, "Cached from local file".to_string())
+    } else if url.starts_with("http://") || url.starts_with("https://") {
+        // Remote URL - fetch with ureq
+        match fetch_remote_registry(url, previous.as_ref()).context("Failed to fetch")? {
+            Fetched::NotModified => {
+                let previous = previous.unwrap_or_default();
+                meta.etag = previous.etag;
+                meta.last_modified = previous.last_modified;
+                meta.save(&cache_file)?;
+                return Ok(Refreshed::NotModified);
+            }
+            Fetched::Body {
+                content,
+                etag,
+                last_modified,
+            } => {
+                meta.etag = etag;
+                meta.last_modified = last_modified;
+                let signature = match fetch_remote_registry(&format!("{}.{}", url, signing::SIGNATURE_EXT), None) {
+                    Ok(Fetched::Body { content, .. }) => Some(content),
+                    _ => None,
+                };
+                (content, signature, "Fetched and cached".to_string())
+            }
+        }
+    } else {
+        eyre::bail!("Unknown URL scheme: {}", url);
+    };
+
+    Registry::parse_fetched(&content, url)?;
+    let signed_by = check_signature(name, content.as_bytes(), signature.as_deref(), config).wrap_err("Rejected")?;
+
+    fs::write(&cache_file, &content).context("Failed to cache registry")?;
+    let signature_file = signature_path(&cache_file);
+    match signature {
+        Some(ref signature) => fs::write(&signature_file, signature).context("Failed to cache signature")?,
+        None => {
+            fs::remove_file(&signature_file).ok();
+        }
+    }
+    meta.save(&cache_file)?;
+
+    Ok(Refreshed::Fetched { done, signed_by })
+}
+
+/// Clone or fetch a `git+` registry's repository into `<name>.repo/` next
+/// to the cache and check out the requested ref, returning the registry
+/// file, its signature and the commit
+fn fetch_git_registry(name: &str, spec: &str, registries_dir: &Path) -> Result<(String, Option<String>, String)> {
+    let source = GitSource::parse(spec).ok_or_else(|| eyre::eyre!("Not a git repository: {}", spec))?;
+    let file = source.subdir.as_deref().ok_or_else(|| {
+        eyre::eyre!(
+            "git+ registry URLs name the registry file after ':', e.g. git+https://github.com/owner/repo.git:registry/plugins.toml"
+        )
+    })?;
+    check_git_args(&source.url, source.git_ref.as_deref())?;
+
+    let checkout = registries_dir.join(format!("{}.repo", name));
+    if checkout.exists() && git(&checkout, &["remote", "get-url", "origin"]).ok().as_deref() != Some(&source.url) {
+        // The registry now points at another repository
+        fs::remove_dir_all(&checkout).context(format!("Failed to remove {}", checkout.display()))?;
+    }
+    if checkout.exists() {
+        git(&checkout, &["fetch", "--quiet", "--tags", "--force", "origin"])?;
+    } else {
+        let dest = checkout.to_string_lossy();
+        git(
+            registries_dir,
+            &["clone", "--quiet", "--no-checkout", "--", &source.url, &dest],
+        )?;
+    }
+
+    // A branch is followed on the remote; a tag or commit is used as is
+    let target = match source.git_ref {
+        Some(ref git_ref) => {
+            let branch = format!("origin/{}", git_ref);
+            if git(
+                &checkout,
+                &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", branch)],
+            )
+            .is_ok()
+            {
+                branch
             } else {
-                println!("  {} Local file not found: {}", "✗".red(), path);
+                git_ref.clone()
             }
-        } else if url.starts_with("http://") || url.starts_with("https://") {
-            // Remote URL - fetch with ureq
-            match fetch_remote_registry(url) {
-                Ok(content) => {
-                    let cache_file = registries_dir.join(format!("{}.toml", reg_name));
-                    fs::write(&cache_file, &content).context("Failed to cache registry")?;
-                    println!("  {} Fetched and cached", "✓".green());
-                }
-                Err(e) => {
-                    println!("  {} Failed to fetch: {}", "✗".red(), e);
+        }
+        None => "origin/HEAD".to_string(),
+    };
+    git(&checkout, &["checkout", "--quiet", "--force", "--detach", &target])
+        .wrap_err(format!("Ref '{}' not found in {}", target, source.url))?;
+    let commit = git(&checkout, &["rev-parse", "HEAD"])?;
+
+    let path = checkout.join(file);
+    let content = fs::read_to_string(&path).context(format!("No {} in {}", file, source))?;
+    let signature = fs::read_to_string(format!("{}.{}", path.display(), signing::SIGNATURE_EXT)).ok();
+    Ok((content, signature, commit))
+}
+
+/// Run git in `dir`, returning its trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        eyre::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `config` with `registry_max_age` overridden by a `--max-age` flag
+pub fn with_max_age(config: &Config, max_age: Option<String>) -> Config {
+    let mut config = config.clone();
+    if max_age.is_some() {
+        config.registry_max_age = max_age;
+    }
+    config
+}
+
+/// Refresh cached registries older than `registry_max_age`.
+///
+/// A registry that can't be refreshed (offline, say) keeps its stale cache,
+/// with a warning. Progress goes to stderr so piped output stays clean.
+pub fn refresh_stale(config: &Config) -> Result<()> {
+    let Some(ref max_age) = config.registry_max_age else {
+        return Ok(());
+    };
+    let max_age = registry::parse_max_age(max_age)?;
+    let registries_dir = Config::expand_path(&config.paths.registries);
+    let now = Utc::now();
+
+    for name in config.registry_order() {
+        let url = &config.registries[&name];
+        let cache_file = registries_dir.join(format!("{}.toml", name));
+        let meta = CacheMeta::load(&cache_file).filter(|m| m.url == *url);
+        let cached = cache_file.exists();
+        let age = match meta.as_ref().and_then(|m| m.age(now)) {
+            Some(age) if cached && age <= max_age => continue,
+            Some(age) if cached => format!("cached {}", registry::format_age(age)),
+            _ if cached => "cache of unknown age".to_string(),
+            _ => "not cached".to_string(),
+        };
+
+        eprintln!("{} Refreshing registry '{}' ({})", "→".blue(), name, age);
+        if let Err(e) = refresh(&name, url, config) {
+            let fallback = if cached {
+                "using the stale cache"
+            } else {
+                "it has no cache"
+            };
+            eprintln!(
+                "{} Couldn't refresh registry '{}': {:#}; {}",
+                "⚠".yellow(),
+                name,
+                e,
+                fallback
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check a registry's detached signature against `[trust.keys]`.
+///
+/// Returns the name of the key that signed it, or `None` for an unsigned
+/// registry (or a signed one with no keys to check it against) that isn't
+/// required to be signed.
+pub fn check_signature(name: &str, content: &[u8], signature: Option<&str>, config: &Config) -> Result<Option<String>> {
+    let required = config.trust.requires_signature(name);
+    match signature {
+        Some(signature) if !config.trust.keys.is_empty() => signing::verify(content, signature, &config.trust.keys)
+            .map(Some)
+            .wrap_err(format!("Registry '{}'", name)),
+        Some(_) if required => eyre::bail!(
+            "Registry '{}' requires a signature but no [trust.keys] are configured",
+            name
+        ),
+        None if required => eyre::bail!("Registry '{}' must be signed but has no signature", name),
+        _ => Ok(None),
+    }
+}
+
+/// Re-check a cached registry's signature before installing from it
+pub fn verify_cached(name: &str, config: &Config) -> Result<()> {
+    let cache_file = Config::expand_path(&config.paths.registries).join(format!("{}.toml", name));
+    let content = fs::read(&cache_file).context(format!("Failed to read {}", cache_file.display()))?;
+    let signature = fs::read_to_string(signature_path(&cache_file)).ok();
+    check_signature(name, &content, signature.as_deref(), config)?;
+    Ok(())
+}
+
+/// How long ago a cached registry was fetched, e.g. `3h ago`
+fn cache_age(cache_file: &Path) -> Option<String> {
+    let age = CacheMeta::load(cache_file)?.age(Utc::now())?;
+    Some(registry::format_age(age))
+}
+
+/// `<file>.sig` next to a registry file
+fn signature_path(registry_file: &Path) -> PathBuf {
+    registry::sidecar(registry_file, signing::SIGNATURE_EXT)
+}
+
+fn keygen(output: &Path, force: bool) -> Result<()> {
+    if output.exists() && !force {
+        eyre::bail!("{} already exists. Use --force to overwrite.", output.display());
+    }
+    let (secret, public) = signing::generate()?;
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, format!("{}\n", secret)).context(format!("Failed to write {}", output.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(output, fs::Permissions::from_mode(0o600))?;
+    }
+
+    println!("{} Wrote secret key to {}", "✓".green(), output.display());
+    println!();
+    println!("Trust it by adding the public key to paii.toml:");
+    println!();
+    println!("  [trust.keys]");
+    println!("  mykey = \"{}\"", public);
+    Ok(())
+}
+
+fn sign(file: &Path, key: &Path) -> Result<()> {
+    let secret = fs::read_to_string(key).context(format!("Failed to read {}", key.display()))?;
+    let content = fs::read(file).context(format!("Failed to read {}", file.display()))?;
+    Registry::parse(&String::from_utf8_lossy(&content))
+        .wrap_err(format!("{} is not a valid registry", file.display()))?;
+
+    let signature_file = signature_path(file);
+    fs::write(&signature_file, format!("{}\n", signing::sign(&content, &secret)?))
+        .context(format!("Failed to write {}", signature_file.display()))?;
+    println!(
+        "{} Signed {} → {}",
+        "✓".green(),
+        file.display(),
+        signature_file.display()
+    );
+    println!("  Public key: {}", signing::public_key(&secret)?.dimmed());
+    Ok(())
+}
+
+fn verify(file: &Path, config: &Config) -> Result<()> {
+    let content = fs::read(file).context(format!("Failed to read {}", file.display()))?;
+    let signature_file = signature_path(file);
+    let signature =
+        fs::read_to_string(&signature_file).context(format!("No signature at {}", signature_file.display()))?;
+    if config.trust.keys.is_empty() {
+        eyre::bail!("No [trust.keys] configured in paii.toml");
+    }
+    let key = signing::verify(&content, &signature, &config.trust.keys).wrap_err(format!("{}", file.display()))?;
+    println!("{} {} is signed by '{}'", "✓".green(), file.display(), key);
+    Ok(())
+}
+
+fn init(file: &Path, meta: RegistryMeta, force: bool) -> Result<()> {
+    if file.exists() && !force {
+        eyre::bail!("{} already exists. Use --force to overwrite.", file.display());
+    }
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, publish::init(&meta)?).context(format!("Failed to write {}", file.display()))?;
+    println!("{} Created registry '{}' at {}", "✓".green(), meta.name, file.display());
+    println!();
+    println!(
+        "Add plugins with: {}",
+        format!("paii registry publish <plugin> --registry {}", file.display()).cyan()
+    );
+    Ok(())
+}
+
+fn publish_plugin(
+    plugin: &Path,
+    file: &Path,
+    source: Option<String>,
+    git_ref: Option<String>,
+    hash: bool,
+) -> Result<()> {
+    let manifest = PluginManifest::load(plugin.join("plugin.toml"))
+        .wrap_err(format!("No valid plugin.toml in {}", plugin.display()))?;
+    let content = fs::read_to_string(file).context(format!(
+        "Failed to read {} (create it with 'paii registry init')",
+        file.display()
+    ))?;
+
+    // Within a git repository, the path is relative to its top level
+    let plugin_dir = fs::canonicalize(plugin).context(format!("Failed to resolve {}", plugin.display()))?;
+    let toplevel = git(&plugin_dir, &["rev-parse", "--show-toplevel"])
+        .ok()
+        .map(PathBuf::from);
+    let path = toplevel
+        .as_ref()
+        .and_then(|top| plugin_dir.strip_prefix(fs::canonicalize(top).ok()?).ok())
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .filter(|p| !p.is_empty());
+    let source = source
+        .or_else(|| manifest.plugin.repository.clone())
+        .or_else(|| git(&plugin_dir, &["remote", "get-url", "origin"]).ok())
+        .ok_or_else(|| eyre::eyre!("Can't tell where {} is published; pass --source", manifest.plugin.name))?;
+
+    let mut entry = publish::entry(&manifest, source, path);
+    entry.git_ref = git_ref;
+    if hash {
+        entry.hash = Some(lock::content_hash(&plugin_dir)?);
+    }
+    let (content, published) = publish::publish(&content, &entry)?;
+    Registry::parse(&content).wrap_err("Generated registry is invalid")?;
+    fs::write(file, content).context(format!("Failed to write {}", file.display()))?;
+
+    let label = format!("{} {}", entry.name, manifest.plugin.version);
+    match published {
+        Published::Added => println!("{} Added {} to {}", "✓".green(), label, file.display()),
+        Published::Updated => println!("{} Updated {} in {}", "✓".green(), label, file.display()),
+        Published::Replaced(previous) => println!(
+            "{} Replaced {} {} with {} in {}",
+            "✓".green(),
+            entry.name,
+            previous,
+            manifest.plugin.version,
+            file.display()
+        ),
+    }
+    if let Some(ref hash) = entry.hash {
+        println!("  Hash: {}", hash.dimmed());
+    }
+    Ok(())
+}
+
+/// Repositories cloned while linting, by source and ref
+type Clones = HashMap<(String, Option<String>), std::result::Result<tempfile::TempDir, String>>;
+
+fn lint(file: &Path, local: Option<&Path>) -> Result<()> {
+    let registry = Registry::load(file)?;
+    println!(
+        "{} Linting {} ({} entries)",
+        "→".blue(),
+        file.display(),
+        registry.plugins.len()
+    );
+
+    let mut clones = Clones::new();
+    let mut seen = HashSet::new();
+    let (mut errors, mut warnings) = (0, 0);
+    for entry in &registry.plugins {
+        let label = format!("{} {}", entry.name, entry.version.as_deref().unwrap_or("?"));
+        let duplicate = !seen.insert((entry.name.clone(), entry.version.clone()));
+        let result = if duplicate {
+            Err(eyre::eyre!("listed more than once"))
+        } else {
+            lint_entry(entry, local, &mut clones)
+        };
+        match result {
+            Ok(problems) if problems.is_empty() => println!("  {} {}", "✓".green(), label),
+            Ok(problems) => {
+                warnings += 1;
+                println!("  {} {}", "⚠".yellow(), label);
+                for problem in problems {
+                    println!("      {}", problem.dimmed());
                 }
             }
-        } else {
-            println!("  {} Unknown URL scheme: {}", "✗".red(), url);
+            Err(e) => {
+                errors += 1;
+                println!("  {} {}: {:#}", "✗".red(), label, e);
+            }
         }
     }
 
+    println!();
+    if errors > 0 {
+        eyre::bail!("{} of {} entries are broken", errors, registry.plugins.len());
+    }
+    if warnings > 0 {
+        println!(
+            "{} {} entries differ from their plugin.toml; regenerate them with 'paii registry publish'",
+            "⚠".yellow(),
+            warnings
+        );
+    } else {
+        println!("{} All entries match their plugins", "✓".green());
+    }
     Ok(())
 }
 
-/// Fetch a registry from a remote URL
-fn fetch_remote_registry(url: &str) -> Result<String> {
+/// Resolve an entry to its plugin and compare them, returning drift
+/// warnings; anything that would break an install is an error
+fn lint_entry(entry: &RegistryEntry, local: Option<&Path>, clones: &mut Clones) -> Result<Vec<String>> {
+    if entry.name.is_empty() {
+        eyre::bail!("no name");
+    }
+    let version = entry.version.as_deref().ok_or_else(|| eyre::eyre!("no version"))?;
+    if entry.semver().is_none() {
+        eyre::bail!("version '{}' is not semver", version);
+    }
+    crate::plugin::version::core_satisfied(entry.core_version.as_deref())?;
+    let source = entry.source.as_deref().ok_or_else(|| eyre::eyre!("no source"))?;
+
+    let dir = match local {
+        Some(root) => root.join(entry.path.as_deref().unwrap_or("")),
+        None => {
+            let git_ref = entry.commit.clone().or_else(|| entry.git_ref.clone());
+            let clone = clones
+                .entry((source.to_string(), git_ref.clone()))
+                .or_insert_with(|| {
+                    let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+                    clone_repo(source, git_ref.as_deref(), &temp.path().join("repo"))
+                        .map_err(|e| format!("{:#}", e))?;
+                    Ok(temp)
+                })
+                .as_ref()
+                .map_err(|e| eyre::eyre!("{}", e))?;
+            clone.path().join("repo").join(entry.path.as_deref().unwrap_or(""))
+        }
+    };
+
+    let manifest = PluginManifest::load(dir.join("plugin.toml")).wrap_err(format!(
+        "no valid plugin.toml at '{}'",
+        entry.path.as_deref().unwrap_or(".")
+    ))?;
+    if manifest.plugin.name != entry.name {
+        eyre::bail!("plugin.toml names the plugin '{}'", manifest.plugin.name);
+    }
+    if manifest.plugin.version != version {
+        eyre::bail!("plugin.toml has version {}", manifest.plugin.version);
+    }
+    // Everything install validates before copying
+    manifest.paii.check()?;
+    Skill::load(&dir)?;
+    agent::discover(&dir, &manifest)?;
+    mcp::load(&dir)?;
+    if let Some(ref expected) = entry.hash {
+        let actual = lock::content_hash(&dir)?;
+        if &actual != expected {
+            eyre::bail!("content hash mismatch: plugin files hash to {}", actual);
+        }
+    }
+
+    let generated = publish::entry(&manifest, source.to_string(), entry.path.clone());
+    Ok(publish::drift(entry, &generated))
+}
+
+/// Response to a registry request
+enum Fetched {
+    NotModified,
+    Body {
+        content: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Fetch a registry from a remote URL, conditionally if `previous` has
+/// validators
+fn fetch_remote_registry(url: &str, previous: Option<&CacheMeta>) -> Result<Fetched> {
     log::info!("Fetching registry from: {}", url);
 
-    let response = ureq::get(url)
-        .header("User-Agent", "paii/0.1.0")
-        .call()
-        .context("HTTP request failed")?;
+    let mut request = ureq::get(url).header("User-Agent", "paii/0.1.0");
+    if let Some(previous) = previous {
+        if let Some(ref etag) = previous.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(ref last_modified) = previous.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+    }
+    let response = request.call().context("HTTP request failed")?;
 
+    if response.status() == 304 {
+        return Ok(Fetched::NotModified);
+    }
     if response.status() != 200 {
         eyre::bail!("HTTP {} error", response.status());
     }
 
-    let body = response
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let etag = header("etag");
+    let last_modified = header("last-modified");
+
+    let content = response
         .into_body()
         .read_to_string()
         .context("Failed to read response body")?;
 
-    Ok(body)
+    Ok(Fetched::Body {
+        content,
+        etag,
+        last_modified,
+    })
+}
+
+/// Load the cached registries in priority order, warning about any that
+/// can't be used
+pub fn cached(config: &Config) -> Vec<CachedRegistry> {
+    let (mut registries, errors) = registry::load_cached(&Config::expand_path(&config.paths.registries));
+    for (name, e) in errors {
+        eprintln!("{} Skipping registry '{}': {:#}", "⚠".yellow(), name, e);
+    }
+    registry::prioritize(&mut registries, &config.registry_order());
+    registries
 }
 
 /// Search result containing plugin and its source registry
 #[derive(Debug)]
 struct SearchResult {
     registry: String,
-    plugin: PluginEntry,
+    plugin: RegistryEntry,
 }
 
-fn search(query: &str, format: OutputFormat, config: &Config) -> Result<()> {
-    let registries_dir = Config::expand_path(&config.paths.registries);
-    let query_lower = query.to_lowercase();
-
-    let mut results: Vec<SearchResult> = Vec::new();
-
-    // Iterate through all cached registry files
-    if registries_dir.exists() {
-        for entry in fs::read_dir(&registries_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().is_some_and(|e| e == "toml") {
-                let registry_name = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                let content = fs::read_to_string(&path).context("Failed to read registry file")?;
-                let registry: RegistryFile = toml::from_str(&content).context("Failed to parse registry file")?;
-
-                // Search plugins
-                for plugin in registry.plugins {
-                    let matches = plugin.name.to_lowercase().contains(&query_lower)
-                        || plugin
-                            .description
-                            .as_ref()
-                            .is_some_and(|d| d.to_lowercase().contains(&query_lower))
-                        || plugin.tags.iter().any(|t| t.to_lowercase().contains(&query_lower));
-
-                    if matches {
-                        results.push(SearchResult {
-                            registry: registry_name.clone(),
-                            plugin,
-                        });
-                    }
-                }
-            }
-        }
+fn search(query: Option<&str>, provides: Option<&str>, format: OutputFormat, config: &Config) -> Result<()> {
+    if query.is_none() && provides.is_none() {
+        eyre::bail!("Give a search query, --provides <contract>, or both");
     }
+    refresh_stale(config)?;
+
+    let results: Vec<SearchResult> = cached(config)
+        .into_iter()
+        .flat_map(|cached| {
+            let name = cached.name;
+            cached
+                .registry
+                .plugins
+                .into_iter()
+                .filter(|p| query.is_none_or(|q| p.matches(q)) && provides.is_none_or(|c| p.provides(c)))
+                .map(move |plugin| SearchResult {
+                    registry: name.clone(),
+                    plugin,
+                })
+        })
+        .collect();
 
     match format {
         OutputFormat::Json => {
@@ -270,8 +820,10 @@ fn search(query: &str, format: OutputFormat, config: &Config) -> Result<()> {
                         "version": r.plugin.version,
                         "description": r.plugin.description,
                         "language": r.plugin.language,
-                        "type": r.plugin.r#type,
+                        "type": r.plugin.kind,
+                        "provides": r.plugin.provides,
                         "tags": r.plugin.tags,
+                        "keywords": r.plugin.keywords,
                         "source": r.plugin.source,
                         "path": r.plugin.path,
                     })
@@ -280,7 +832,7 @@ fn search(query: &str, format: OutputFormat, config: &Config) -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&json_results)?);
         }
         OutputFormat::Yaml => {
-            let plugins: Vec<&PluginEntry> = results.iter().map(|r| &r.plugin).collect();
+            let plugins: Vec<&RegistryEntry> = results.iter().map(|r| &r.plugin).collect();
             println!("{}", serde_yaml::to_string(&plugins)?);
         }
         OutputFormat::Text => {
@@ -308,12 +860,29 @@ fn search(query: &str, format: OutputFormat, config: &Config) -> Result<()> {
                     lang.yellow()
                 );
                 println!("    {}", desc.dimmed());
+                if !result.plugin.provides.is_empty() {
+                    println!("    Provides: {}", result.plugin.provides.join(", ").green());
+                }
                 if !result.plugin.tags.is_empty() {
                     println!("    Tags: {}", result.plugin.tags.join(", ").blue());
                 }
                 println!("    Registry: {}", result.registry.dimmed());
                 println!();
             }
+
+            // How old the listings above are
+            let registries_dir = Config::expand_path(&config.paths.registries);
+            let mut shown: Vec<&str> = results.iter().map(|r| r.registry.as_str()).collect();
+            shown.dedup();
+            let ages: Vec<String> = shown
+                .iter()
+                .filter_map(|name| {
+                    cache_age(&registries_dir.join(format!("{}.toml", name))).map(|age| format!("{} {}", name, age))
+                })
+                .collect();
+            if !ages.is_empty() {
+                println!("{}", format!("Cache fetched: {}", ages.join(", ")).dimmed());
+            }
         }
     }
 
@@ -337,8 +906,7 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
         }
     }
 
-    let content = fs::read_to_string(&cache_file).context("Failed to read registry file")?;
-    let registry: RegistryFile = toml::from_str(&content).context("Failed to parse registry file")?;
+    let registry = Registry::load(&cache_file)?;
 
     match format {
         OutputFormat::Json => {
@@ -370,6 +938,9 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
                         lang.yellow()
                     );
                     println!("    {}", desc.dimmed());
+                    if !plugin.provides.is_empty() {
+                        println!("    Provides: {}", plugin.provides.join(", ").green());
+                    }
                     if !plugin.tags.is_empty() {
                         println!("    Tags: {}", plugin.tags.join(", ").blue());
                     }
@@ -381,3 +952,49 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_path() {
+        let home = Config::expand_path(Path::new("~"));
+        assert_eq!(
+            local_path("~/src/registry/plugins.toml"),
+            Some(home.join("src/registry/plugins.toml"))
+        );
+        assert_eq!(
+            local_path("file:///srv/plugins.toml"),
+            Some(PathBuf::from("/srv/plugins.toml"))
+        );
+        assert_eq!(local_path("./plugins.toml"), Some(PathBuf::from("./plugins.toml")));
+        assert_eq!(local_path("https://example.com/plugins.toml"), None);
+        assert_eq!(local_path("git+https://example.com/registry.git"), None);
+    }
+
+    #[test]
+    fn test_lint_entry_checks_plugin_contents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("notes");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("plugin.toml"),
+            "[plugin]\nname = \"notes\"\nversion = \"0.1.0\"\ndescription = \"d\"\n",
+        )
+        .unwrap();
+        let entry = RegistryEntry {
+            name: "notes".to_string(),
+            version: Some("0.1.0".to_string()),
+            source: Some("https://example.com/plugins.git".to_string()),
+            path: Some("notes".to_string()),
+            ..Default::default()
+        };
+        let mut clones = Clones::new();
+        lint_entry(&entry, Some(tmp.path()), &mut clones).unwrap();
+
+        fs::write(dir.join(mcp::MCP_FILE), r#"{"mcpServers": {"notes": {}}}"#).unwrap();
+        let err = lint_entry(&entry, Some(tmp.path()), &mut clones).unwrap_err();
+        assert!(err.to_string().contains("neither `command` nor `url`"));
+    }
+}
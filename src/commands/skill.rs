@@ -0,0 +1,213 @@
+use colored::*;
+use eyre::Result;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{OutputFormat, SkillAction};
+use crate::config::{ClaudeScope, Config};
+use crate::plugin::PluginManager;
+use crate::plugin::intent::{self, SkillIndex};
+use crate::plugin::skill::{self, InstalledSkill, Skill};
+
+pub fn run(action: SkillAction, config: &Config) -> Result<()> {
+    match action {
+        SkillAction::List { format } => list(OutputFormat::resolve(format), config),
+        SkillAction::Sync => sync(config),
+        SkillAction::Match { text, limit, format } => match_intent(&text, limit, OutputFormat::resolve(format), config),
+    }
+}
+
+/// Skills directory for a scope
+pub fn skills_dir(config: &Config, scope: ClaudeScope) -> PathBuf {
+    config.claude.scope_dir(scope).join("skills")
+}
+
+/// Fail if installing `skill` would overwrite a skill paii doesn't own.
+/// With `force` the collision is reported and the skill replaced.
+pub fn check_collision(skill: &Skill, plugin: &str, force: bool, config: &Config) -> Result<()> {
+    match skill::check_collision(skill, plugin, &skills_dir(config, config.claude.scope)) {
+        Err(e) if force => {
+            println!("  {} {} (replacing)", "⚠".yellow(), e);
+            Ok(())
+        }
+        Err(e) => Err(e.wrap_err("Use --force to replace it")),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Sync the skill of an installed plugin into the configured scope
+pub fn sync_plugin(plugin_dir: &Path, plugin: &str, link: bool, config: &Config) -> Result<()> {
+    let Some(skill) = Skill::load(plugin_dir)? else {
+        return Ok(());
+    };
+
+    let scope = config.claude.scope;
+    let target = skill::sync(&skill, plugin, &skills_dir(config, scope), link)?;
+    println!(
+        "  {} Skill {} → {}",
+        "✓".green(),
+        skill.name().green(),
+        target.display()
+    );
+
+    // Personal skills override project skills of the same name
+    if scope == ClaudeScope::Project {
+        let personal = skills_dir(config, ClaudeScope::User).join(skill.name());
+        if personal.join(skill::SKILL_FILE).exists() && personal != target {
+            println!("  {} Shadowed by personal skill {}", "⚠".yellow(), personal.display());
+        }
+    }
+    Ok(())
+}
+
+/// Remove a plugin's skills from every scope
+pub fn unsync_plugin(plugin: &str, config: &Config) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for dir in scope_dirs(config) {
+        removed.extend(skill::unsync(plugin, &dir.1)?);
+    }
+    Ok(removed)
+}
+
+/// Skills directories Claude reads, highest precedence first
+pub fn scope_dirs(config: &Config) -> Vec<(ClaudeScope, PathBuf)> {
+    let user = skills_dir(config, ClaudeScope::User);
+    let project = skills_dir(config, ClaudeScope::Project);
+
+    let mut dirs = vec![(ClaudeScope::User, user.clone())];
+    if project != user {
+        dirs.push((ClaudeScope::Project, project));
+    }
+    dirs
+}
+
+fn list(format: OutputFormat, config: &Config) -> Result<()> {
+    let mut skills: Vec<InstalledSkill> = Vec::new();
+    for (scope, dir) in scope_dirs(config) {
+        skills.extend(skill::installed(&dir, scope));
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&skills)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&skills)?),
+        OutputFormat::Text => {
+            for (scope, dir) in scope_dirs(config) {
+                println!(
+                    "{} {}",
+                    format!("{} skills:", capitalize(scope.as_str())).bold(),
+                    dir.display().to_string().dimmed()
+                );
+                println!();
+
+                let in_scope: Vec<&InstalledSkill> = skills.iter().filter(|s| s.scope == scope).collect();
+                if in_scope.is_empty() {
+                    println!("  {}", "(none)".dimmed());
+                }
+                for s in in_scope {
+                    let shadowed = s.scope == ClaudeScope::Project
+                        && skills.iter().any(|o| o.scope == ClaudeScope::User && o.name == s.name);
+                    let owner = s.plugin.as_ref().map(|p| format!(" [{}]", p)).unwrap_or_default();
+
+                    match (&s.error, shadowed) {
+                        (Some(e), _) => println!("  {} {} {}", "✗".red(), s.name.red(), e.dimmed()),
+                        (None, true) => println!(
+                            "  {} {}{} {}",
+                            "⚠".yellow(),
+                            s.name.yellow(),
+                            owner.dimmed(),
+                            "(shadowed by personal skill)".dimmed()
+                        ),
+                        (None, false) => println!(
+                            "  {} {}{} {}",
+                            "✓".green(),
+                            s.name.green(),
+                            owner.dimmed(),
+                            format!("- {}", first_sentence(&s.description)).dimmed()
+                        ),
+                    }
+                }
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn sync(config: &Config) -> Result<()> {
+    let scope = config.claude.scope;
+    println!(
+        "{} Syncing plugin skills to {}",
+        "→".blue(),
+        skills_dir(config, scope).display().to_string().cyan()
+    );
+
+    let mut manager = PluginManager::new(Config::expand_path(&config.paths.plugins));
+    manager.discover()?;
+
+    let mut names: Vec<&String> = manager.plugins.keys().collect();
+    names.sort();
+    for name in names {
+        let plugin = &manager.plugins[name];
+        let link = plugin.path.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink());
+
+        let result = Skill::load(&plugin.path).and_then(|skill| match skill {
+            Some(s) => {
+                check_collision(&s, name, false, config).and_then(|_| sync_plugin(&plugin.path, name, link, config))
+            }
+            None => Ok(()),
+        });
+        if let Err(e) = result {
+            println!("  {} {}: {:#}", "✗".red(), name, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn match_intent(text: &str, limit: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    let index = SkillIndex::new(intent::load_docs(
+        &scope_dirs(config),
+        &Config::expand_path(&config.paths.plugins),
+        &Config::expand_path(&config.paths.registries),
+    ));
+    let matches = index.rank(text, limit);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&matches)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&matches)?),
+        OutputFormat::Text => {
+            println!(
+                "{} {}",
+                "Skills matching:".bold(),
+                format!("\"{}\" ({} installed)", text, index.docs().len()).dimmed()
+            );
+            println!();
+
+            if matches.is_empty() {
+                println!("  {}", "(no matches)".dimmed());
+            }
+            for m in &matches {
+                println!(
+                    "  {:>3.0}%  {} {}",
+                    m.confidence * 100.0,
+                    m.name.green(),
+                    format!("- {}", first_sentence(&m.description)).dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn first_sentence(text: &str) -> &str {
+    text.split_once(". ").map(|(first, _)| first).unwrap_or(text)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+        .unwrap_or_default()
+}
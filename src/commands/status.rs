@@ -4,6 +4,8 @@ use serde::Serialize;
 
 use crate::cli::OutputFormat;
 use crate::config::Config;
+use crate::contract::memory;
+use crate::history::HistoryStore;
 
 #[derive(Serialize)]
 struct Status {
@@ -13,11 +15,13 @@ struct Status {
     history_dir: String,
     plugins_count: usize,
     registries_count: usize,
+    memory_provider: String,
 }
 
 pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
     let plugins_dir = Config::expand_path(&config.paths.plugins);
     let history_dir = Config::expand_path(&config.paths.history);
+    let memory_provider = memory::resolve(config).provider_name();
 
     // Count plugins (follow symlinks)
     let plugins_count = if plugins_dir.exists() {
@@ -39,6 +43,7 @@ pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
                 history_dir: history_dir.display().to_string(),
                 plugins_count,
                 registries_count: config.registries.len(),
+                memory_provider,
             };
             match format {
                 OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&status)?),
@@ -53,6 +58,7 @@ pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
             println!("  {:12} {}", "Version:".dimmed(), env!("CARGO_PKG_VERSION"));
             println!("  {:12} {}", "Plugins:".dimmed(), plugins_dir.display());
             println!("  {:12} {}", "History:".dimmed(), history_dir.display());
+            println!("  {:12} {}", "Memory:".dimmed(), memory_provider);
             println!();
 
             // Plugins
@@ -84,18 +90,14 @@ pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
 
             // History
             println!("{}:", "History".cyan());
-            if history_dir.exists() {
-                for entry in std::fs::read_dir(&history_dir)? {
-                    let entry = entry?;
-                    if entry.file_type()?.is_dir() {
-                        let name = entry.file_name();
-                        let count = std::fs::read_dir(entry.path())?.filter_map(|e| e.ok()).count();
-                        println!("  {:15} {} entries", name.to_string_lossy(), count);
-                    }
-                }
-            } else {
+            let store = HistoryStore::new(history_dir.clone());
+            let categories = store.categories()?;
+            if categories.is_empty() {
                 println!("  {}", "(no history yet)".dimmed());
             }
+            for category in categories {
+                println!("  {:15} {} entries", category, store.count(&category)?);
+            }
         }
     }
 
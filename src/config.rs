@@ -2,10 +2,12 @@
 
 use eyre::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::history::project::Project;
+
 /// XDG config dir, honoring `$XDG_CONFIG_HOME` and falling back to `$HOME/.config`.
 fn xdg_config_dir() -> Option<PathBuf> {
     if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
@@ -41,7 +43,21 @@ pub struct Config {
     pub paths: PathsConfig,
     pub defaults: DefaultsConfig,
     pub registries: HashMap<String, String>,
+    /// Registries consulted first when a plugin name is in several; the rest
+    /// follow in name order
+    pub registry_priority: Vec<String>,
+    /// Refresh cached registries older than this (`12h`, `7d`) before search
+    /// and install; `--max-age` overrides it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry_max_age: Option<String>,
     pub hooks: HooksConfig,
+    pub history: HistoryConfig,
+    pub context: ContextConfig,
+    pub notifications: NotificationsConfig,
+    pub claude: ClaudeConfig,
+    pub trust: TrustConfig,
+    /// Plugins `paii apply` keeps installed, keyed by plugin name
+    pub plugins: BTreeMap<String, WantedPlugin>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -56,6 +72,8 @@ pub struct PathsConfig {
     pub plugins: PathBuf,
     pub history: PathBuf,
     pub registries: PathBuf,
+    /// paii.lock recording exactly which plugins are installed
+    pub lockfile: PathBuf,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -70,6 +88,256 @@ pub struct DefaultsConfig {
 pub struct HooksConfig {
     pub security_enabled: bool,
     pub history_enabled: bool,
+    pub context_enabled: bool,
+    /// Snapshot the transcript into compactions/ on PreCompact
+    pub compaction_enabled: bool,
+    /// File failing tool calls under errors/ and promote fixes to learnings/
+    pub learning_enabled: bool,
+    /// Record Notification events (attention requests) under events/
+    pub notification_enabled: bool,
+    /// Journal UserPromptSubmit prompts (opt-in)
+    pub capture_prompts: bool,
+    /// Journal PreToolUse/PostToolUse calls (opt-in)
+    pub capture_tools: bool,
+    /// Suggest relevant installed skills on UserPromptSubmit (opt-in)
+    pub suggest_skills: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Retention policies keyed by history category
+    pub retention: HashMap<String, RetentionPolicy>,
+}
+
+/// Notification channels and which events they receive
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Named delivery channels
+    pub channels: HashMap<String, ChannelConfig>,
+    /// Event name (`stop`, `block`, `notification`, ...) to channel names
+    pub events: HashMap<String, Vec<String>>,
+}
+
+/// A notification channel
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChannelConfig {
+    /// Desktop notification via notify-send
+    Desktop,
+    /// JSON POST to a URL
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Append JSON lines to a file or FIFO
+    File { path: PathBuf },
+}
+
+/// Keys trusted to sign registries; see [`crate::plugin::signing`]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TrustConfig {
+    /// Public keys (`ed25519:<base64>`) by name
+    pub keys: BTreeMap<String, String>,
+    /// Registries that must carry a valid signature (`"*"` for all)
+    pub require_signature: Vec<String>,
+}
+
+impl TrustConfig {
+    pub fn requires_signature(&self, registry: &str) -> bool {
+        self.require_signature.iter().any(|r| r == registry || r == "*")
+    }
+}
+
+/// A plugin declared in `[plugins]`.
+///
+/// Either a version constraint (`otto = "^0.1"`) or a table:
+///
+/// ```toml
+/// [plugins.incident]
+/// registry = "work"
+/// version = "^1.2"
+/// config = { escalation_threshold_minutes = 15 }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(from = "WantedPluginToml")]
+pub struct WantedPlugin {
+    /// Local path to install from instead of a registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Registry to resolve the name in (highest priority if unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Version constraint, e.g. `^1.2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Symlink `source` instead of copying it
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dev: bool,
+    /// Overrides for the plugin's `[config]` settings
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub config: toml::Table,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WantedPluginToml {
+    Version(String),
+    Table {
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        registry: Option<String>,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        dev: bool,
+        #[serde(default)]
+        config: toml::Table,
+    },
+}
+
+impl From<WantedPluginToml> for WantedPlugin {
+    fn from(raw: WantedPluginToml) -> Self {
+        match raw {
+            WantedPluginToml::Version(version) => Self {
+                version: Some(version),
+                ..Default::default()
+            },
+            WantedPluginToml::Table {
+                source,
+                registry,
+                version,
+                dev,
+                config,
+            } => Self {
+                source,
+                registry,
+                version,
+                dev,
+                config,
+            },
+        }
+    }
+}
+
+/// Where plugin-provided skills are installed for Claude Code
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ClaudeConfig {
+    /// Claude Code user directory (personal scope)
+    pub dir: PathBuf,
+    /// Install into the personal or the current project's `.claude/`
+    pub scope: ClaudeScope,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeScope {
+    /// `~/.claude/`, across all projects
+    #[default]
+    User,
+    /// `.claude/` at the root of the current project
+    Project,
+}
+
+impl ClaudeScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaudeScope::User => "user",
+            ClaudeScope::Project => "project",
+        }
+    }
+}
+
+impl Default for ClaudeConfig {
+    fn default() -> Self {
+        Self {
+            dir: dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".claude"),
+            scope: ClaudeScope::User,
+        }
+    }
+}
+
+impl ClaudeConfig {
+    /// The `.claude` directory for a scope
+    pub fn scope_dir(&self, scope: ClaudeScope) -> PathBuf {
+        match scope {
+            ClaudeScope::User => Config::expand_path(&self.dir),
+            ClaudeScope::Project => {
+                let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                Project::detect(&cwd).root.join(".claude")
+            }
+        }
+    }
+
+    /// Claude Code's MCP server config for a scope: `~/.claude.json` next to
+    /// the user `.claude` directory, or `.mcp.json` at the project root
+    pub fn mcp_config_path(&self, scope: ClaudeScope) -> PathBuf {
+        let dir = self.scope_dir(scope);
+        let parent = dir.parent().map(Path::to_path_buf).unwrap_or_default();
+        match scope {
+            ClaudeScope::User => parent.join(".claude.json"),
+            ClaudeScope::Project => parent.join(".mcp.json"),
+        }
+    }
+}
+
+/// Retention policy for a single history category
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Remove entries older than this many days
+    pub max_age_days: Option<u32>,
+    /// Keep at most this many entries (newest first); protected entries don't count
+    pub max_count: Option<usize>,
+    /// Entries carrying any of these tags are never removed
+    pub keep_tagged: Vec<String>,
+    /// Compact entries older than this many days into monthly archives
+    pub compact_after_days: Option<u32>,
+}
+
+/// What the SessionStart hook feeds back to Claude
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ContextConfig {
+    /// Budget in tokens (approximated as 4 characters each)
+    pub max_tokens: Option<usize>,
+    /// Budget in characters; the tighter of the two budgets applies
+    pub max_chars: Option<usize>,
+    /// History categories to digest, in priority order
+    pub categories: Vec<String>,
+    /// Entries to include per category
+    pub per_category: usize,
+    /// Include the last session summary from the same project
+    pub include_last_session: bool,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: Some(1000),
+            max_chars: None,
+            categories: vec!["learnings".to_string(), "decisions".to_string(), "todos".to_string()],
+            per_category: 5,
+            include_last_session: true,
+        }
+    }
+}
+
+impl ContextConfig {
+    /// Effective budget in characters
+    pub fn budget_chars(&self) -> usize {
+        let from_tokens = self.max_tokens.map(|t| t * 4);
+        match (from_tokens, self.max_chars) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => usize::MAX,
+        }
+    }
 }
 
 impl Default for Config {
@@ -82,13 +350,22 @@ impl Default for Config {
                 plugins: paii_dir.join("plugins"),
                 history: paii_dir.join("history"),
                 registries: paii_dir.join("registries"),
+                lockfile: paii_dir.join("paii.lock"),
             },
             defaults: DefaultsConfig::default(),
             registries: HashMap::from([(
                 "core".to_string(),
                 "https://raw.githubusercontent.com/scottidler/paii/main/registry/plugins.toml".to_string(),
             )]),
+            registry_priority: Vec::new(),
+            registry_max_age: None,
             hooks: HooksConfig::default(),
+            history: HistoryConfig::default(),
+            context: ContextConfig::default(),
+            notifications: NotificationsConfig::default(),
+            claude: ClaudeConfig::default(),
+            trust: TrustConfig::default(),
+            plugins: BTreeMap::new(),
         }
     }
 }
@@ -109,6 +386,7 @@ impl Default for PathsConfig {
             plugins: paii_dir.join("plugins"),
             history: paii_dir.join("history"),
             registries: paii_dir.join("registries"),
+            lockfile: paii_dir.join("paii.lock"),
         }
     }
 }
@@ -127,6 +405,13 @@ impl Default for HooksConfig {
         Self {
             security_enabled: true,
             history_enabled: true,
+            context_enabled: true,
+            compaction_enabled: true,
+            learning_enabled: true,
+            notification_enabled: true,
+            capture_prompts: false,
+            capture_tools: false,
+            suggest_skills: false,
         }
     }
 }
@@ -210,6 +495,26 @@ impl Config {
             .unwrap_or_else(|_| xdg_config_dir().unwrap_or_else(|| PathBuf::from(".")).join("paii"))
     }
 
+    /// Configured registry names, highest priority first: `registry_priority`
+    /// entries in order, then the remaining registries by name
+    pub fn registry_order(&self) -> Vec<String> {
+        let mut order: Vec<String> = Vec::new();
+        for name in &self.registry_priority {
+            if self.registries.contains_key(name) && !order.contains(name) {
+                order.push(name.clone());
+            }
+        }
+        let mut rest: Vec<&String> = self.registries.keys().filter(|n| !order.contains(n)).collect();
+        rest.sort();
+        order.extend(rest.into_iter().cloned());
+        order
+    }
+
+    /// `[plugins.<name>.config]` overrides for a plugin
+    pub fn plugin_overrides(&self, name: &str) -> toml::Table {
+        self.plugins.get(name).map(|p| p.config.clone()).unwrap_or_default()
+    }
+
     /// Expand a path that may contain ~ or env vars
     pub fn expand_path(path: &Path) -> PathBuf {
         let path_str = path.to_string_lossy();
@@ -233,6 +538,57 @@ mod tests {
         assert!(config.registries.contains_key("core"));
     }
 
+    #[test]
+    fn test_registry_order() {
+        let mut config = Config::default();
+        config
+            .registries
+            .insert("work".to_string(), "/tmp/work.toml".to_string());
+        config
+            .registries
+            .insert("alpha".to_string(), "/tmp/alpha.toml".to_string());
+        assert_eq!(config.registry_order(), vec!["alpha", "core", "work"]);
+
+        config.registry_priority = vec!["work".to_string(), "missing".to_string()];
+        assert_eq!(config.registry_order(), vec!["work", "alpha", "core"]);
+
+        // Survives a round trip through `paii config set`
+        let saved: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(saved.registry_priority, vec!["work", "missing"]);
+    }
+
+    #[test]
+    fn test_wanted_plugins() {
+        let config: Config = toml::from_str(
+            r#"
+[plugins]
+otto = "^0.1"
+
+[plugins.incident]
+registry = "work"
+version = "^1.2"
+config = { escalation_threshold_minutes = 15 }
+
+[plugins.mine]
+source = "~/src/mine"
+dev = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.plugins["otto"].version.as_deref(), Some("^0.1"));
+        assert_eq!(config.plugins["incident"].registry.as_deref(), Some("work"));
+        assert_eq!(
+            config.plugin_overrides("incident")["escalation_threshold_minutes"].as_integer(),
+            Some(15)
+        );
+        assert!(config.plugins["mine"].dev);
+        assert!(config.plugin_overrides("missing").is_empty());
+
+        let saved: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(saved.plugins, config.plugins);
+    }
+
     #[test]
     fn test_default_paii_config() {
         let config = PaiiConfig::default();
@@ -314,6 +670,65 @@ mod tests {
         assert_eq!(parsed.defaults.language, config.defaults.language);
     }
 
+    #[test]
+    fn test_parse_retention_policy() {
+        let toml_str = r#"
+[history.retention.events]
+max_age_days = 30
+keep_tagged = ["pinned"]
+
+[history.retention.sessions]
+max_count = 500
+compact_after_days = 60
+"#;
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse");
+        let events = &config.history.retention["events"];
+        assert_eq!(events.max_age_days, Some(30));
+        assert_eq!(events.max_count, None);
+        assert_eq!(events.keep_tagged, vec!["pinned"]);
+        let sessions = &config.history.retention["sessions"];
+        assert_eq!(sessions.max_count, Some(500));
+        assert_eq!(sessions.compact_after_days, Some(60));
+    }
+
+    #[test]
+    fn test_parse_notifications() {
+        let toml_str = r#"
+[notifications.channels.desktop]
+type = "desktop"
+
+[notifications.channels.team]
+type = "webhook"
+url = "https://example.com/hook"
+
+[notifications.channels.log]
+type = "file"
+path = "~/paii-notify.log"
+
+[notifications.events]
+stop = ["desktop", "log"]
+block = ["team"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let notifications = &config.notifications;
+        assert!(matches!(notifications.channels["desktop"], ChannelConfig::Desktop));
+        assert!(matches!(notifications.channels["team"], ChannelConfig::Webhook { .. }));
+        assert!(matches!(notifications.channels["log"], ChannelConfig::File { .. }));
+        assert_eq!(notifications.events["stop"], vec!["desktop", "log"]);
+    }
+
+    #[test]
+    fn test_context_budget() {
+        let mut context = ContextConfig::default();
+        assert_eq!(context.budget_chars(), 4000);
+        context.max_chars = Some(1500);
+        assert_eq!(context.budget_chars(), 1500);
+        context.max_tokens = None;
+        assert_eq!(context.budget_chars(), 1500);
+        context.max_chars = None;
+        assert_eq!(context.budget_chars(), usize::MAX);
+    }
+
     #[test]
     fn test_load_returns_config() {
         // Just test that load returns something (default or from file)
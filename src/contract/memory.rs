@@ -1,9 +1,21 @@
 //! MemoryProvider contract
 //!
 //! Plugins that provide persistent memory/context storage.
+//!
+//! The built-in provider is `history::provider::FileMemoryProvider`. A plugin
+//! that `provides` MemoryProvider replaces it (see `plugin::memory`).
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::history::provider::FileMemoryProvider;
+use crate::plugin::PluginManager;
+use crate::plugin::memory::PluginMemoryProvider;
+
+use super::ContractType;
 
 /// Result from a memory query
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -15,7 +27,43 @@ pub struct MemoryResult {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+impl MemoryResult {
+    /// Title from metadata, falling back to the first content line
+    pub fn title(&self) -> String {
+        self.metadata
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| self.content.lines().next().unwrap_or("").to_string())
+    }
+
+    /// Tags from metadata
+    pub fn tags(&self) -> Vec<String> {
+        self.metadata
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| tags.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    }
+
+    /// String metadata value
+    pub fn meta_str(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Date part of the timestamp
+    pub fn date(&self) -> Option<NaiveDate> {
+        chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|dt| dt.date_naive())
+    }
+}
+
 /// MemoryProvider contract interface
+///
+/// An empty `category` means "all categories" for `query` and `get_recent`.
+/// `capture` reads the optional `title` (string) and `tags` (string array)
+/// metadata keys; everything else is stored as entry metadata.
 pub trait MemoryProvider: Send + Sync {
     /// Store content in the specified category
     fn capture(
@@ -28,9 +76,143 @@ pub trait MemoryProvider: Send + Sync {
     /// Search stored content
     fn query(&self, category: &str, query: &str, limit: usize) -> eyre::Result<Vec<MemoryResult>>;
 
+    /// Search stored content on or after `since`.
+    ///
+    /// The default filters the results of `query`, so it can return fewer
+    /// than `limit` matches; providers that filter natively should override it.
+    fn query_since(
+        &self,
+        category: &str,
+        query: &str,
+        since: Option<NaiveDate>,
+        limit: usize,
+    ) -> eyre::Result<Vec<MemoryResult>> {
+        let mut results = self.query(category, query, limit)?;
+        if let Some(since) = since {
+            results.retain(|r| r.date().is_some_and(|d| d >= since));
+        }
+        Ok(results)
+    }
+
     /// List available categories
     fn list_categories(&self) -> Vec<String>;
 
     /// Get most recent entries in a category
     fn get_recent(&self, category: &str, count: usize) -> eyre::Result<Vec<MemoryResult>>;
+
+    /// Rewrite an entry returned by `query` or `get_recent` with its changed
+    /// content and metadata
+    fn update(&self, entry: &MemoryResult) -> eyre::Result<()> {
+        eyre::bail!(
+            "{} can't update '{}' entries in place",
+            self.provider_name(),
+            entry.category
+        )
+    }
+
+    /// Number of entries in a category
+    fn count(&self, category: &str) -> eyre::Result<usize> {
+        Ok(self.get_recent(category, usize::MAX)?.len())
+    }
+
+    /// Human-readable provider name (for status output)
+    fn provider_name(&self) -> String {
+        "unknown".to_string()
+    }
+}
+
+/// Resolve the active memory provider.
+///
+/// An installed plugin that provides MemoryProvider takes precedence;
+/// otherwise the file-based history store is used.
+pub fn resolve(config: &Config) -> Box<dyn MemoryProvider> {
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let mut manager = PluginManager::new(plugins_dir);
+
+    match manager.discover().and_then(|_| manager.load_all()) {
+        Ok(()) => {
+            if let Some(plugin) = manager.provider(&ContractType::MemoryProvider) {
+                log::info!("Using memory provider from plugin: {}", plugin.manifest.plugin.name);
+                let mut plugin = plugin.clone();
+                plugin.config = config.plugin_overrides(&plugin.manifest.plugin.name);
+                return Box::new(PluginMemoryProvider::new(&plugin));
+            }
+        }
+        Err(e) => log::warn!("Failed to load plugins, using file memory: {}", e),
+    }
+
+    Box::new(FileMemoryProvider::new(Config::expand_path(&config.paths.history)))
+}
+
+/// A provider that is only resolved when first used.
+///
+/// Resolving means discovering plugins, which most hook events never need;
+/// handlers share one of these and the first to store or read memory pays
+/// for it.
+pub struct LazyMemoryProvider {
+    config: Config,
+    inner: OnceLock<Box<dyn MemoryProvider>>,
+}
+
+impl LazyMemoryProvider {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            inner: OnceLock::new(),
+        }
+    }
+
+    fn provider(&self) -> &dyn MemoryProvider {
+        self.inner.get_or_init(|| resolve(&self.config)).as_ref()
+    }
+
+    /// Whether the provider has been resolved yet
+    pub fn is_resolved(&self) -> bool {
+        self.inner.get().is_some()
+    }
+}
+
+impl MemoryProvider for LazyMemoryProvider {
+    fn capture(
+        &self,
+        category: &str,
+        content: &str,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> eyre::Result<String> {
+        self.provider().capture(category, content, metadata)
+    }
+
+    fn query(&self, category: &str, query: &str, limit: usize) -> eyre::Result<Vec<MemoryResult>> {
+        self.provider().query(category, query, limit)
+    }
+
+    fn query_since(
+        &self,
+        category: &str,
+        query: &str,
+        since: Option<NaiveDate>,
+        limit: usize,
+    ) -> eyre::Result<Vec<MemoryResult>> {
+        self.provider().query_since(category, query, since, limit)
+    }
+
+    fn list_categories(&self) -> Vec<String> {
+        self.provider().list_categories()
+    }
+
+    fn get_recent(&self, category: &str, count: usize) -> eyre::Result<Vec<MemoryResult>> {
+        self.provider().get_recent(category, count)
+    }
+
+    fn update(&self, entry: &MemoryResult) -> eyre::Result<()> {
+        self.provider().update(entry)
+    }
+
+    fn count(&self, category: &str) -> eyre::Result<usize> {
+        self.provider().count(category)
+    }
+
+    fn provider_name(&self) -> String {
+        self.provider().provider_name()
+    }
 }
@@ -7,8 +7,11 @@
 
 use std::collections::HashMap;
 
+use crate::plugin::manifest::ProvideSpec;
+
 pub mod integration;
 pub mod memory;
+pub mod notification;
 pub mod skill;
 
 /// Contract types
@@ -34,6 +37,26 @@ impl ContractType {
             _ => None,
         }
     }
+
+    /// Resolve a `[provides]` manifest entry.
+    ///
+    /// Detailed entries name their contract explicitly; simple entries
+    /// (`skill = "otto"`) are resolved from the key.
+    pub fn from_provide(key: &str, spec: &ProvideSpec) -> Option<Self> {
+        match spec {
+            ProvideSpec::Detailed { contract, service } => Self::from_spec(contract, service.as_deref()),
+            ProvideSpec::Simple(value) => Self::from_spec(value, None).or_else(|| match key {
+                "memory" => Some(Self::MemoryProvider),
+                "hook" | "hooks" => Some(Self::HookHandler),
+                "skill" => Some(Self::SkillProvider),
+                "notification" => Some(Self::NotificationProvider),
+                "integration" => Some(Self::IntegrationProvider { service: value.clone() }),
+                _ => None,
+            }),
+            // Subagents are synced into Claude Code, not a contract
+            ProvideSpec::Agents { .. } => None,
+        }
+    }
 }
 
 /// Contract registry - maps contracts to providers
@@ -71,3 +94,55 @@ impl ContractRegistry {
         self.providers.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_provide_detailed() {
+        let spec = ProvideSpec::Detailed {
+            contract: "MemoryProvider".to_string(),
+            service: None,
+        };
+        assert_eq!(
+            ContractType::from_provide("anything", &spec),
+            Some(ContractType::MemoryProvider)
+        );
+    }
+
+    #[test]
+    fn test_from_provide_simple() {
+        let skill = ProvideSpec::Simple("otto".to_string());
+        assert_eq!(
+            ContractType::from_provide("skill", &skill),
+            Some(ContractType::SkillProvider)
+        );
+
+        let memory = ProvideSpec::Simple("MemoryProvider".to_string());
+        assert_eq!(
+            ContractType::from_provide("store", &memory),
+            Some(ContractType::MemoryProvider)
+        );
+
+        let unknown = ProvideSpec::Simple("x".to_string());
+        assert_eq!(ContractType::from_provide("widgets", &unknown), None);
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicate_provider() {
+        let mut registry = ContractRegistry::new();
+        registry
+            .register(ContractType::MemoryProvider, "a".to_string())
+            .unwrap();
+        assert!(
+            registry
+                .register(ContractType::MemoryProvider, "b".to_string())
+                .is_err()
+        );
+        assert_eq!(
+            registry.get_provider(&ContractType::MemoryProvider),
+            Some(&"a".to_string())
+        );
+    }
+}
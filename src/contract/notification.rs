@@ -0,0 +1,58 @@
+//! NotificationProvider contract
+//!
+//! Providers that deliver notifications to the user (desktop, chat, logs).
+//! Built-in providers live in `notify`.
+
+use serde::{Deserialize, Serialize};
+
+/// How urgently a notification needs attention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        }
+    }
+}
+
+/// A notification to deliver
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub urgency: Urgency,
+    /// Configured channel the notification is sent on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+impl Notification {
+    pub fn new(title: &str, body: &str, urgency: Urgency) -> Self {
+        Self {
+            title: title.to_string(),
+            body: body.to_string(),
+            urgency,
+            channel: None,
+        }
+    }
+}
+
+/// NotificationProvider contract interface
+pub trait NotificationProvider: Send + Sync {
+    /// Provider kind (e.g. "desktop", "webhook")
+    fn provider_name(&self) -> &str;
+
+    /// Deliver a notification
+    fn send(&self, notification: &Notification) -> eyre::Result<()>;
+}
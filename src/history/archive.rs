@@ -0,0 +1,136 @@
+//! Monthly history archives
+//!
+//! Old day directories can be compacted into a single gzip-compressed JSONL
+//! file per month, stored next to the day directories they replace:
+//! ~/.config/paii/history/<category>/<YYYY-MM>.jsonl.gz
+
+use eyre::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::HistoryEntry;
+
+/// File suffix for monthly archives
+pub const ARCHIVE_SUFFIX: &str = ".jsonl.gz";
+
+/// Path of the archive for `month` (YYYY-MM) inside a category directory
+pub fn archive_path(category_dir: &Path, month: &str) -> PathBuf {
+    category_dir.join(format!("{}{}", month, ARCHIVE_SUFFIX))
+}
+
+/// Month (YYYY-MM) an archive file covers, if the path looks like an archive
+pub fn archive_month(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let month = name.strip_suffix(ARCHIVE_SUFFIX)?;
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()?;
+    Some(month.to_string())
+}
+
+/// List archives in a category directory, oldest month first
+pub fn list_archives(category_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !category_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut archives = Vec::new();
+    for entry in fs::read_dir(category_dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && let Some(month) = archive_month(&path)
+        {
+            archives.push((month, path));
+        }
+    }
+    archives.sort();
+    Ok(archives)
+}
+
+/// Read all entries from an archive
+pub fn read_archive(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let file = fs::File::open(path).context(format!("Failed to open archive {}", path.display()))?;
+    let reader = BufReader::new(GzDecoder::new(file));
+
+    let mut entries = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.context(format!("Failed to read archive {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<HistoryEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::warn!("Skipping bad line {} in {}: {}", n + 1, path.display(), e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Write entries to an archive, replacing it atomically.
+/// An empty entry list removes the archive.
+pub fn write_archive(path: &Path, entries: &[HistoryEntry]) -> Result<()> {
+    if entries.is_empty() {
+        if path.exists() {
+            fs::remove_file(path).context(format!("Failed to remove archive {}", path.display()))?;
+        }
+        return Ok(());
+    }
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| eyre::eyre!("Archive path has no parent: {}", path.display()))?;
+    fs::create_dir_all(dir).context("Failed to create archive directory")?;
+
+    let tmp = tempfile::NamedTempFile::new_in(dir).context("Failed to create temp archive")?;
+    {
+        let mut encoder = GzEncoder::new(tmp.as_file(), Compression::default());
+        for entry in entries {
+            serde_json::to_writer(&mut encoder, entry)?;
+            encoder.write_all(b"\n")?;
+        }
+        encoder.finish().context("Failed to compress archive")?;
+    }
+    tmp.persist(path)
+        .map_err(|e| eyre::eyre!("Failed to write archive {}: {}", path.display(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_month() {
+        assert_eq!(
+            archive_month(Path::new("/h/sessions/2025-01.jsonl.gz")),
+            Some("2025-01".to_string())
+        );
+        assert_eq!(archive_month(Path::new("/h/sessions/2025-01-03")), None);
+        assert_eq!(archive_month(Path::new("/h/sessions/notes.jsonl.gz")), None);
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = archive_path(dir.path(), "2025-01");
+        let entries = vec![
+            HistoryEntry::new("sessions", "One", "first").with_tag("a"),
+            HistoryEntry::new("sessions", "Two", "second"),
+        ];
+
+        write_archive(&path, &entries).unwrap();
+        let read = read_archive(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].title, "One");
+        assert_eq!(read[0].tags, vec!["a"]);
+
+        let listed = list_archives(dir.path()).unwrap();
+        assert_eq!(listed, vec![("2025-01".to_string(), path.clone())]);
+
+        write_archive(&path, &[]).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,276 @@
+//! History export and import
+//!
+//! Entries can be exported as JSONL (one serialized `HistoryEntry` per line),
+//! a single Markdown bundle, or a static HTML report. JSONL and Markdown
+//! bundles can be imported back into a `HistoryStore`.
+
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{HistoryEntry, HistoryStore, is_path_segment};
+
+/// Marker separating entries in a Markdown bundle
+const BUNDLE_MARKER: &str = "<!-- paii:entry -->";
+
+/// Serialize entries as JSONL
+pub fn to_jsonl(entries: &[HistoryEntry]) -> Result<String> {
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&serde_json::to_string(entry)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Parse entries from JSONL
+pub fn from_jsonl(content: &str) -> Result<Vec<HistoryEntry>> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| serde_json::from_str(line).context(format!("Invalid entry on line {}", n + 1)))
+        .collect()
+}
+
+/// Concatenate entries into a single Markdown document
+pub fn to_markdown_bundle(entries: &[HistoryEntry]) -> String {
+    let mut out = String::new();
+    out.push_str("# PAII History Export\n\n");
+    out.push_str(&format!(
+        "{} entries, exported {}\n\n",
+        entries.len(),
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+
+    for entry in entries {
+        out.push_str(BUNDLE_MARKER);
+        out.push('\n');
+        out.push_str(&entry.to_markdown());
+        out.push('\n');
+    }
+    out
+}
+
+/// Parse entries from a Markdown bundle
+pub fn from_markdown_bundle(content: &str) -> Result<Vec<HistoryEntry>> {
+    let mut chunks = content.split(BUNDLE_MARKER);
+    // Everything before the first marker is the bundle header
+    chunks.next();
+
+    chunks
+        .map(|chunk| HistoryEntry::from_markdown(chunk.trim_start(), Path::new("unknown.md")))
+        .collect()
+}
+
+/// Render entries as a self-contained HTML report
+pub fn to_html(entries: &[HistoryEntry]) -> String {
+    let mut by_day: BTreeMap<String, Vec<&HistoryEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_day
+            .entry(entry.created_at.format("%Y-%m-%d").to_string())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>PAII History</title>\n<style>\n");
+    html.push_str(HTML_STYLE);
+    html.push_str("</style>\n</head>\n<body>\n");
+    html.push_str("<h1>PAII History</h1>\n");
+    html.push_str(&format!(
+        "<p class=\"meta\">{} entries, exported {}</p>\n",
+        entries.len(),
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+
+    // Table of contents, newest day first
+    html.push_str("<nav>\n");
+    for (day, day_entries) in by_day.iter().rev() {
+        html.push_str(&format!("<h3>{}</h3>\n<ul>\n", day));
+        for entry in day_entries {
+            html.push_str(&format!(
+                "<li><a href=\"#{}\">{}</a> <span class=\"cat\">{}</span></li>\n",
+                escape_html(&entry.id),
+                escape_html(&entry.title),
+                escape_html(&entry.category)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</nav>\n<main>\n");
+
+    for (_, day_entries) in by_day.iter().rev() {
+        for entry in day_entries {
+            html.push_str(&format!("<article id=\"{}\">\n", escape_html(&entry.id)));
+            html.push_str(&format!("<h2>{}</h2>\n", escape_html(&entry.title)));
+            html.push_str(&format!(
+                "<p class=\"meta\"><span class=\"cat\">{}</span> {}",
+                escape_html(&entry.category),
+                entry.created_at.format("%Y-%m-%d %H:%M")
+            ));
+            for tag in &entry.tags {
+                html.push_str(&format!(" <span class=\"tag\">{}</span>", escape_html(tag)));
+            }
+            html.push_str("</p>\n");
+
+            if !entry.metadata.is_empty() {
+                let mut keys: Vec<&String> = entry.metadata.keys().collect();
+                keys.sort();
+                html.push_str("<dl>\n");
+                for key in keys {
+                    html.push_str(&format!(
+                        "<dt>{}</dt><dd>{}</dd>\n",
+                        escape_html(key),
+                        escape_html(&entry.metadata[key])
+                    ));
+                }
+                html.push_str("</dl>\n");
+            }
+
+            html.push_str(&format!("<pre>{}</pre>\n</article>\n", escape_html(&entry.content)));
+        }
+    }
+
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: -apple-system, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+nav { border-bottom: 1px solid #ddd; margin-bottom: 2rem; }
+nav h3 { margin-bottom: 0.2rem; font-size: 0.95rem; }
+nav ul { margin-top: 0; }
+article { border-top: 1px solid #eee; padding-top: 1rem; }
+pre { white-space: pre-wrap; background: #f7f7f7; padding: 0.75rem; border-radius: 4px; }
+.meta { color: #777; font-size: 0.9rem; }
+.cat { color: #06c; font-size: 0.85rem; }
+.tag { background: #eef; border-radius: 3px; padding: 0 0.3rem; font-size: 0.8rem; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0 1rem; font-size: 0.85rem; color: #555; }
+dd { margin: 0; }
+";
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Outcome of an import
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    /// Entries whose category or id isn't a plain name
+    pub rejected: usize,
+}
+
+/// Merge entries into a store, skipping any whose id already exists.
+///
+/// Category and id become path components, so an entry where either is
+/// empty or could escape the history directory is rejected.
+pub fn import(store: &HistoryStore, entries: Vec<HistoryEntry>) -> Result<ImportSummary> {
+    let mut known = store.ids()?;
+    let mut summary = ImportSummary::default();
+
+    for entry in entries {
+        if !is_path_segment(&entry.category) || !is_path_segment(&entry.id) {
+            log::warn!(
+                "Rejecting entry '{}' in category '{}': not a plain name",
+                entry.id,
+                entry.category
+            );
+            summary.rejected += 1;
+            continue;
+        }
+        if !known.insert(entry.id.clone()) {
+            summary.skipped += 1;
+            continue;
+        }
+        store.store(&entry)?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<HistoryEntry> {
+        let mut a = HistoryEntry::new("learnings", "Use <Result>", "Prefer eyre & context")
+            .with_tag("rust")
+            .with_metadata("project", "paii");
+        a.id = "a1".to_string();
+        let mut b = HistoryEntry::new("sessions", "Session b", "Did things");
+        b.id = "b2".to_string();
+        vec![a, b]
+    }
+
+    #[test]
+    fn test_jsonl_roundtrip() {
+        let entries = sample();
+        let parsed = from_jsonl(&to_jsonl(&entries).unwrap()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].id, "a1");
+        assert_eq!(parsed[0].metadata["project"], "paii");
+    }
+
+    #[test]
+    fn test_markdown_bundle_roundtrip() {
+        let entries = sample();
+        let parsed = from_markdown_bundle(&to_markdown_bundle(&entries)).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].id, "a1");
+        assert_eq!(parsed[0].title, "Use <Result>");
+        assert_eq!(parsed[0].content, "Prefer eyre & context");
+        assert_eq!(parsed[0].tags, vec!["rust"]);
+        assert_eq!(parsed[1].category, "sessions");
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let html = to_html(&sample());
+        assert!(html.contains("Use &lt;Result&gt;"));
+        assert!(html.contains("Prefer eyre &amp; context"));
+        assert!(html.contains("href=\"#a1\""));
+    }
+
+    #[test]
+    fn test_import_deduplicates_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+
+        let first = import(&store, sample()).unwrap();
+        assert_eq!(first.imported, 2);
+
+        let second = import(&store, sample()).unwrap();
+        assert_eq!(second.imported, 0);
+        assert_eq!(second.skipped, 2);
+        assert_eq!(store.count("learnings").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_import_rejects_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join("history");
+        let store = HistoryStore::new(history.clone());
+
+        let mut entries = sample();
+        entries[0].category = "../../.ssh".to_string();
+        entries[1].id = "/tmp/evil".to_string();
+        let mut dotdot = HistoryEntry::new("learnings", "Up", "Up");
+        dotdot.id = "..".to_string();
+        entries.push(dotdot);
+
+        let summary = import(&store, entries).unwrap();
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.rejected, 3);
+        assert!(!dir.path().join(".ssh").exists());
+        assert!(!history.exists());
+    }
+}
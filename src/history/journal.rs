@@ -0,0 +1,615 @@
+//! Per-session event journal
+//!
+//! Prompts and tool calls are too frequent for one markdown file each, so
+//! they are appended as JSON lines to `<history>/.journal/<session_id>.jsonl`.
+//! The Stop hook summarizes the journal into the session's history entry.
+//! Journals are removed by the `journal` retention policy (see [`retention`])
+//! at SessionEnd and by `paii history prune`.
+
+use chrono::{DateTime, Local};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::truncate;
+use crate::config::RetentionPolicy;
+
+/// Directory (under the history root) holding session journals
+pub const JOURNAL_DIR: &str = ".journal";
+
+/// Longest summarized tool input or prompt kept in the journal
+const MAX_TEXT: usize = 500;
+
+/// Key of the journals' policy under `[history.retention]`
+pub const RETENTION_KEY: &str = "journal";
+
+/// How long journals are kept when no policy is configured
+pub const DEFAULT_MAX_AGE_DAYS: u32 = 30;
+
+/// Bytes read at a time when scanning a journal backwards
+const READ_CHUNK: u64 = 8192;
+
+/// A journaled event
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// The user submitted a prompt
+    Prompt { prompt: String },
+    /// A tool call is about to run (used to time the call)
+    ToolStart {
+        tool: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_use_id: Option<String>,
+    },
+    /// A tool call finished
+    Tool {
+        tool: String,
+        input: String,
+        success: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_use_id: Option<String>,
+    },
+    /// A subagent finished
+    Subagent {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent_type: Option<String>,
+        outcome: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u64>,
+    },
+}
+
+/// A journal line: timestamp plus event
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct JournalRecord {
+    pub at: DateTime<Local>,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Journal storage rooted at the history directory
+#[derive(Debug, Clone)]
+pub struct Journal {
+    dir: PathBuf,
+}
+
+impl Journal {
+    pub fn new(history_path: &Path) -> Self {
+        Self::in_dir(history_path.join(JOURNAL_DIR))
+    }
+
+    /// Journals kept in `dir` rather than the history's journal directory
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Journal file for a session
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        // Session ids come from Claude Code; keep them from escaping the directory
+        let safe: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.jsonl", safe))
+    }
+
+    /// Append an event to a session's journal
+    pub fn append(&self, session_id: &str, event: JournalEvent) -> Result<()> {
+        fs::create_dir_all(&self.dir).context("Failed to create journal directory")?;
+
+        let record = JournalRecord {
+            at: Local::now(),
+            event,
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let path = self.path(session_id);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context(format!("Failed to open journal {}", path.display()))?;
+        file.write_all(line.as_bytes())
+            .context(format!("Failed to append to journal {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Read a session's journal, skipping malformed lines
+    pub fn read(&self, session_id: &str) -> Result<Vec<JournalRecord>> {
+        let path = self.path(session_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).context(format!("Failed to read journal {}", path.display()))?;
+        Ok(parse(&content))
+    }
+
+    /// Every journal, keyed by session id
+    pub fn all(&self) -> Result<Vec<(String, Vec<JournalRecord>)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut journals = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "jsonl")
+                && let Some(session) = path.file_stem().and_then(|s| s.to_str())
+            {
+                journals.push((session.to_string(), self.read(session)?));
+            }
+        }
+        journals.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(journals)
+    }
+
+    /// When the matching ToolStart was recorded, for timing a finished call.
+    ///
+    /// Matches on `tool_use_id` when Claude Code provides one, otherwise on the
+    /// most recent unfinished start of the same tool. The journal is read
+    /// backwards and only as far as the match, so this stays cheap however
+    /// long the session runs.
+    pub fn started_at(&self, session_id: &str, tool: &str, tool_use_id: Option<&str>) -> Option<DateTime<Local>> {
+        let mut finished = 0usize;
+        self.find_backwards(session_id, |record| match &record.event {
+            JournalEvent::ToolStart {
+                tool: t,
+                tool_use_id: id,
+            } => {
+                if let Some(want) = tool_use_id {
+                    (id.as_deref() == Some(want)).then_some(record.at)
+                } else if t == tool {
+                    if finished == 0 {
+                        return Some(record.at);
+                    }
+                    finished -= 1;
+                    None
+                } else {
+                    None
+                }
+            }
+            JournalEvent::Tool { tool: t, .. } if tool_use_id.is_none() && t == tool => {
+                finished += 1;
+                None
+            }
+            _ => None,
+        })
+    }
+
+    /// Visit a session's records newest first until `visit` returns a value
+    fn find_backwards<T>(&self, session_id: &str, mut visit: impl FnMut(&JournalRecord) -> Option<T>) -> Option<T> {
+        let mut file = File::open(self.path(session_id)).ok()?;
+        let mut pos = file.metadata().ok()?.len();
+        // Bytes after `pos` that start mid-line, carried into the next read
+        let mut carry: Vec<u8> = Vec::new();
+
+        while pos > 0 {
+            let len = READ_CHUNK.min(pos);
+            pos -= len;
+            let mut chunk = vec![0; len as usize];
+            file.seek(SeekFrom::Start(pos)).ok()?;
+            file.read_exact(&mut chunk).ok()?;
+            chunk.extend_from_slice(&carry);
+
+            // Unless this is the start of the file, the first line may be partial
+            let complete = if pos > 0 {
+                match chunk.iter().position(|&b| b == b'\n') {
+                    Some(i) => i + 1,
+                    None => {
+                        carry = chunk;
+                        continue;
+                    }
+                }
+            } else {
+                0
+            };
+            for line in chunk[complete..].split(|&b| b == b'\n').rev() {
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                match serde_json::from_slice::<JournalRecord>(line) {
+                    Ok(record) => {
+                        if let Some(found) = visit(&record) {
+                            return Some(found);
+                        }
+                    }
+                    Err(e) => log::warn!("Skipping malformed journal line: {}", e),
+                }
+            }
+            chunk.truncate(complete);
+            carry = chunk;
+        }
+        None
+    }
+
+    /// Journals a retention policy removes: those last written more than
+    /// `max_age_days` before `now`, and all but the newest `max_count`
+    pub fn expired(&self, policy: &RetentionPolicy, now: SystemTime) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut journals = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "jsonl") {
+                let modified = fs::metadata(&path)?.modified()?;
+                journals.push((modified, path));
+            }
+        }
+        // Newest first, so max_count keeps the most recent journals
+        journals.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+        let cutoff = policy
+            .max_age_days
+            .and_then(|days| now.checked_sub(Duration::from_secs(u64::from(days) * 24 * 60 * 60)));
+        Ok(journals
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (modified, _))| {
+                cutoff.is_some_and(|cutoff| *modified < cutoff) || policy.max_count.is_some_and(|max| *i >= max)
+            })
+            .map(|(_, (_, path))| path)
+            .collect())
+    }
+
+    /// Remove expired journals, returning how many were removed
+    pub fn prune(&self, policy: &RetentionPolicy) -> Result<usize> {
+        let expired = self.expired(policy, SystemTime::now())?;
+        for path in &expired {
+            fs::remove_file(path).context(format!("Failed to remove journal {}", path.display()))?;
+        }
+        Ok(expired.len())
+    }
+}
+
+/// The retention policy for journals: `[history.retention.journal]`, or
+/// [`DEFAULT_MAX_AGE_DAYS`] when none is configured
+pub fn retention(policies: &HashMap<String, RetentionPolicy>) -> RetentionPolicy {
+    policies.get(RETENTION_KEY).cloned().unwrap_or(RetentionPolicy {
+        max_age_days: Some(DEFAULT_MAX_AGE_DAYS),
+        ..Default::default()
+    })
+}
+
+/// Parse journal lines, skipping any that don't decode
+pub fn parse(content: &str) -> Vec<JournalRecord> {
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| match serde_json::from_str(l) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                log::warn!("Skipping malformed journal line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Aggregate view of a session journal
+#[derive(Debug, Default)]
+pub struct JournalSummary {
+    pub prompts: Vec<String>,
+    /// Per tool: (calls, failures)
+    pub tools: BTreeMap<String, (usize, usize)>,
+    pub failures: Vec<(String, String)>,
+    pub total_duration_ms: u64,
+    /// (agent type, outcome, duration)
+    pub subagents: Vec<(String, String, Option<u64>)>,
+}
+
+impl JournalSummary {
+    pub fn from_records(records: &[JournalRecord]) -> Self {
+        let mut summary = Self::default();
+        for record in records {
+            match &record.event {
+                JournalEvent::Prompt { prompt } => summary.prompts.push(prompt.clone()),
+                JournalEvent::Tool {
+                    tool,
+                    input,
+                    success,
+                    duration_ms,
+                    ..
+                } => {
+                    let counts = summary.tools.entry(tool.clone()).or_default();
+                    counts.0 += 1;
+                    if !success {
+                        counts.1 += 1;
+                        summary.failures.push((tool.clone(), input.clone()));
+                    }
+                    summary.total_duration_ms += duration_ms.unwrap_or(0);
+                }
+                JournalEvent::Subagent {
+                    agent_type,
+                    outcome,
+                    duration_ms,
+                } => summary.subagents.push((
+                    agent_type.clone().unwrap_or_else(|| "subagent".to_string()),
+                    outcome.clone(),
+                    *duration_ms,
+                )),
+                JournalEvent::ToolStart { .. } => {}
+            }
+        }
+        summary
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty() && self.tools.is_empty() && self.subagents.is_empty()
+    }
+
+    /// Markdown sections for the session summary
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        if !self.prompts.is_empty() {
+            out.push_str("## Prompts\n\n");
+            for prompt in &self.prompts {
+                let first = prompt.lines().next().unwrap_or("");
+                out.push_str(&format!("- {}\n", truncate(first, 120)));
+            }
+            out.push('\n');
+        }
+
+        if !self.tools.is_empty() {
+            let calls: usize = self.tools.values().map(|(c, _)| c).sum();
+            out.push_str(&format!("## Tools Used\n\nCalls: {}", calls));
+            if self.total_duration_ms > 0 {
+                out.push_str(&format!(", {:.1}s in tools", self.total_duration_ms as f64 / 1000.0));
+            }
+            out.push_str("\n\n");
+            for (tool, (count, failed)) in &self.tools {
+                if *failed > 0 {
+                    out.push_str(&format!("- {} ×{} ({} failed)\n", tool, count, failed));
+                } else {
+                    out.push_str(&format!("- {} ×{}\n", tool, count));
+                }
+            }
+            out.push('\n');
+        }
+
+        if !self.subagents.is_empty() {
+            out.push_str("## Subagents\n\n");
+            for (agent, outcome, duration_ms) in &self.subagents {
+                match duration_ms {
+                    Some(ms) => out.push_str(&format!("- {}: {} ({:.1}s)\n", agent, outcome, *ms as f64 / 1000.0)),
+                    None => out.push_str(&format!("- {}: {}\n", agent, outcome)),
+                }
+            }
+            out.push('\n');
+        }
+
+        if !self.failures.is_empty() {
+            out.push_str("## Failures\n\n");
+            for (tool, input) in &self.failures {
+                out.push_str(&format!("- {}: `{}`\n", tool, truncate(input, 120)));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// One-line summary of a tool's input
+pub fn summarize_input(tool: &str, input: &serde_json::Value) -> String {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str());
+
+    let summary = match tool {
+        "Bash" => field("command").map(str::to_string),
+        "Read" | "Write" | "Edit" | "MultiEdit" | "NotebookEdit" => {
+            field("file_path").or(field("notebook_path")).map(str::to_string)
+        }
+        "Grep" | "Glob" => field("pattern").map(|p| match field("path") {
+            Some(path) => format!("{} in {}", p, path),
+            None => p.to_string(),
+        }),
+        "WebFetch" => field("url").map(str::to_string),
+        "WebSearch" => field("query").map(str::to_string),
+        "Task" => field("description").map(str::to_string),
+        _ => None,
+    };
+
+    let summary = summary.unwrap_or_else(|| match input {
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    });
+    truncate(&summary, MAX_TEXT)
+}
+
+/// Decide whether a tool call succeeded from its `tool_response`
+///
+/// Returns the error text for failures.
+pub fn tool_outcome(response: &serde_json::Value) -> (bool, Option<String>) {
+    let text = |key: &str| {
+        response
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+    };
+
+    if let Some(code) = response
+        .get("exit_code")
+        .or_else(|| response.get("exitCode"))
+        .and_then(|v| v.as_i64())
+        && code != 0
+    {
+        let error = text("stderr").or(text("stdout")).unwrap_or("").to_string();
+        return (
+            false,
+            Some(truncate(&format!("exit {}: {}", code, error.trim()), MAX_TEXT)),
+        );
+    }
+
+    let failed = response.get("is_error").and_then(|v| v.as_bool()) == Some(true)
+        || response.get("success").and_then(|v| v.as_bool()) == Some(false)
+        || response.get("interrupted").and_then(|v| v.as_bool()) == Some(true)
+        || text("error").is_some();
+
+    if failed {
+        let error = text("error").or(text("stderr")).or(text("message")).unwrap_or("failed");
+        return (false, Some(truncate(error.trim(), MAX_TEXT)));
+    }
+
+    // Some tools report failures as a plain string response
+    if let Some(s) = response.as_str()
+        && s.trim_start().starts_with("Error")
+    {
+        return (false, Some(truncate(s.trim(), MAX_TEXT)));
+    }
+
+    (true, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+
+        journal
+            .append(
+                "s1",
+                JournalEvent::Prompt {
+                    prompt: "fix the build".into(),
+                },
+            )
+            .unwrap();
+        journal
+            .append(
+                "s1",
+                JournalEvent::ToolStart {
+                    tool: "Bash".into(),
+                    tool_use_id: None,
+                },
+            )
+            .unwrap();
+        assert!(journal.started_at("s1", "Bash", None).is_some());
+        journal
+            .append(
+                "s1",
+                JournalEvent::Tool {
+                    tool: "Bash".into(),
+                    input: "cargo build".into(),
+                    success: false,
+                    error: Some("exit 101".into()),
+                    duration_ms: Some(1500),
+                    tool_use_id: None,
+                },
+            )
+            .unwrap();
+        // The start is consumed by the finished call
+        assert!(journal.started_at("s1", "Bash", None).is_none());
+
+        let records = journal.read("s1").unwrap();
+        assert_eq!(records.len(), 3);
+
+        let summary = JournalSummary::from_records(&records);
+        assert_eq!(summary.prompts, vec!["fix the build"]);
+        assert_eq!(summary.tools["Bash"], (1, 1));
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("- Bash ×1 (1 failed)"));
+        assert!(markdown.contains("`cargo build`"));
+    }
+
+    #[test]
+    fn test_started_at_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+        let start = |id: &str| JournalEvent::ToolStart {
+            tool: "Bash".into(),
+            tool_use_id: Some(id.into()),
+        };
+
+        journal.append("s1", start("first")).unwrap();
+        for i in 0..300 {
+            journal
+                .append(
+                    "s1",
+                    JournalEvent::Prompt {
+                        prompt: format!("prompt {} with enough text to span several read chunks", i),
+                    },
+                )
+                .unwrap();
+        }
+        journal.append("s1", start("last")).unwrap();
+        assert!(fs::metadata(journal.path("s1")).unwrap().len() > 2 * READ_CHUNK);
+
+        assert!(journal.started_at("s1", "Bash", Some("first")).is_some());
+        assert!(journal.started_at("s1", "Bash", Some("last")).is_some());
+        assert!(journal.started_at("s1", "Bash", Some("missing")).is_none());
+        assert!(journal.started_at("nope", "Bash", None).is_none());
+    }
+
+    #[test]
+    fn test_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path());
+        for session in ["s1", "s2", "s3"] {
+            journal
+                .append(session, JournalEvent::Prompt { prompt: "hi".into() })
+                .unwrap();
+        }
+
+        let by_count = RetentionPolicy {
+            max_count: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(journal.expired(&by_count, SystemTime::now()).unwrap().len(), 1);
+
+        let policy = retention(&HashMap::new());
+        assert_eq!(policy.max_age_days, Some(DEFAULT_MAX_AGE_DAYS));
+        assert!(journal.expired(&policy, SystemTime::now()).unwrap().is_empty());
+        let later = SystemTime::now() + Duration::from_secs(40 * 24 * 60 * 60);
+        assert_eq!(journal.expired(&policy, later).unwrap().len(), 3);
+
+        assert_eq!(journal.prune(&by_count).unwrap(), 1);
+        assert_eq!(journal.all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_session_id_is_sanitized() {
+        let journal = Journal::new(Path::new("/h"));
+        assert_eq!(journal.path("../etc/x"), PathBuf::from("/h/.journal/___etc_x.jsonl"));
+    }
+
+    #[test]
+    fn test_summarize_input() {
+        assert_eq!(summarize_input("Bash", &json!({"command": "ls -la"})), "ls -la");
+        assert_eq!(summarize_input("Edit", &json!({"file_path": "/a.rs"})), "/a.rs");
+        assert_eq!(
+            summarize_input("Grep", &json!({"pattern": "fn", "path": "src"})),
+            "fn in src"
+        );
+        assert_eq!(summarize_input("Other", &json!({"x": 1})), r#"{"x":1}"#);
+    }
+
+    #[test]
+    fn test_tool_outcome() {
+        assert_eq!(tool_outcome(&json!({"stdout": "ok", "exit_code": 0})), (true, None));
+        let (ok, error) = tool_outcome(&json!({"stderr": "boom", "exit_code": 2}));
+        assert!(!ok);
+        assert_eq!(error.as_deref(), Some("exit 2: boom"));
+        assert!(!tool_outcome(&json!({"success": false, "error": "no match"})).0);
+        assert!(!tool_outcome(&json!("Error: file not found")).0);
+        assert!(tool_outcome(&json!({"filePath": "/a.rs"})).0);
+    }
+}
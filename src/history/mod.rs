@@ -2,6 +2,17 @@
 //!
 //! Stores session data, learnings, decisions, and insights as markdown files.
 //! Structure: ~/.config/paii/history/<category>/<date>/<id>.md
+//!
+//! Old days may be compacted into monthly archives (see [`archive`]), which
+//! `count` and `query` read transparently.
+
+pub mod archive;
+pub mod export;
+pub mod journal;
+pub mod project;
+pub mod provider;
+pub mod retention;
+pub mod stats;
 
 use chrono::{DateTime, Local, NaiveDate};
 use eyre::{Context, Result};
@@ -152,11 +163,19 @@ impl HistoryEntry {
             }
         }
 
+        // Drop the heading that to_markdown adds so content round-trips
+        let mut content = body.trim();
+        if let Some(rest) = content.strip_prefix(&format!("# {}", title))
+            && (rest.is_empty() || rest.starts_with('\n'))
+        {
+            content = rest.trim_start();
+        }
+
         Ok(Self {
             id,
             category,
             title,
-            content: body.trim().to_string(),
+            content: content.to_string(),
             tags,
             created_at,
             metadata,
@@ -164,7 +183,31 @@ impl HistoryEntry {
     }
 }
 
+/// Criteria for selecting history entries
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub category: Option<String>,
+    /// Only entries on or after this date
+    pub since: Option<NaiveDate>,
+    /// Only entries on or before this date
+    pub until: Option<NaiveDate>,
+    /// Only entries carrying at least one of these tags
+    pub tags: Vec<String>,
+}
+
+impl HistoryFilter {
+    /// Check whether an entry passes the filter
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        let date = entry.created_at.date_naive();
+        self.category.as_ref().is_none_or(|c| &entry.category == c)
+            && self.since.is_none_or(|d| date >= d)
+            && self.until.is_none_or(|d| date <= d)
+            && (self.tags.is_empty() || entry.tags.iter().any(|t| self.tags.contains(t)))
+    }
+}
+
 /// History storage
+#[derive(Debug, Clone)]
 pub struct HistoryStore {
     base_path: PathBuf,
 }
@@ -175,8 +218,39 @@ impl HistoryStore {
         Self { base_path }
     }
 
+    /// Root directory of the store
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
+    }
+
+    /// Rewrite an existing entry, live or inside its monthly archive
+    pub fn update(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some((path, _)) = self
+            .live_entries(&entry.category)?
+            .into_iter()
+            .find(|(_, e)| e.id == entry.id)
+        {
+            return fs::write(&path, entry.to_markdown()).context(format!("Failed to write {}", path.display()));
+        }
+        for (_, path) in archive::list_archives(&self.base_path.join(&entry.category))? {
+            let mut entries = archive::read_archive(&path)?;
+            if let Some(archived) = entries.iter_mut().find(|e| e.id == entry.id) {
+                *archived = entry.clone();
+                return archive::write_archive(&path, &entries);
+            }
+        }
+        eyre::bail!("No '{}' entry with id '{}'", entry.category, entry.id)
+    }
+
     /// Store an entry
     pub fn store(&self, entry: &HistoryEntry) -> Result<PathBuf> {
+        if !is_path_segment(&entry.category) || !is_path_segment(&entry.id) {
+            eyre::bail!(
+                "Invalid history entry: category '{}' and id '{}' must be plain names",
+                entry.category,
+                entry.id
+            );
+        }
         let date = entry.created_at.format("%Y-%m-%d").to_string();
         let dir = self.base_path.join(&entry.category).join(&date);
         fs::create_dir_all(&dir).context("Failed to create history directory")?;
@@ -199,8 +273,10 @@ impl HistoryStore {
         let mut categories = Vec::new();
         for entry in fs::read_dir(&self.base_path)? {
             let entry = entry?;
+            // Dot directories (e.g. the session journal) are not categories
             if entry.path().is_dir()
                 && let Some(name) = entry.file_name().to_str()
+                && !name.starts_with('.')
             {
                 categories.push(name.to_string());
             }
@@ -228,9 +304,70 @@ impl HistoryStore {
                 }
             }
         }
+
+        for (_, path) in archive::list_archives(&cat_path)? {
+            count += archive::read_archive(&path)?.len();
+        }
         Ok(count)
     }
 
+    /// Load the live (not yet archived) entries of a category with their paths
+    pub fn live_entries(&self, category: &str) -> Result<Vec<(PathBuf, HistoryEntry)>> {
+        let cat_path = self.base_path.join(category);
+        if !cat_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for date_entry in fs::read_dir(&cat_path)? {
+            let date_path = date_entry?.path();
+            if !date_path.is_dir() {
+                continue;
+            }
+            for file_entry in fs::read_dir(&date_path)? {
+                let path = file_entry?.path();
+                if !path.extension().map(|e| e == "md").unwrap_or(false) {
+                    continue;
+                }
+                let content = fs::read_to_string(&path)?;
+                match HistoryEntry::from_markdown(&content, &path) {
+                    Ok(entry) => entries.push((path, entry)),
+                    Err(e) => log::warn!("Skipping unreadable entry {}: {}", path.display(), e),
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Load every entry (live and archived) matching a filter, oldest first
+    pub fn entries(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>> {
+        let categories: Vec<String> = match &filter.category {
+            Some(c) => vec![c.clone()],
+            None => self.categories()?,
+        };
+
+        let mut entries = Vec::new();
+        for cat in categories {
+            entries.extend(self.live_entries(&cat)?.into_iter().map(|(_, e)| e));
+            for (_, path) in archive::list_archives(&self.base_path.join(&cat))? {
+                entries.extend(archive::read_archive(&path)?);
+            }
+        }
+
+        entries.retain(|e| filter.matches(e));
+        entries.sort_by_key(|e| e.created_at);
+        Ok(entries)
+    }
+
+    /// IDs of every stored entry, live and archived
+    pub fn ids(&self) -> Result<std::collections::HashSet<String>> {
+        Ok(self
+            .entries(&HistoryFilter::default())?
+            .into_iter()
+            .map(|e| e.id)
+            .collect())
+    }
+
     /// Get recent entries
     pub fn recent(&self, category: Option<&str>, limit: usize) -> Result<Vec<HistoryEntry>> {
         let mut entries = Vec::new();
@@ -341,6 +478,27 @@ impl HistoryStore {
                     }
                 }
             }
+
+            // Search compacted months
+            let since_month = since.map(|d| d.format("%Y-%m").to_string());
+            for (month, path) in archive::list_archives(&cat_path)? {
+                if since_month.as_ref().is_some_and(|m| &month < m) {
+                    continue;
+                }
+
+                for entry in archive::read_archive(&path)? {
+                    if since.is_some_and(|d| entry.created_at.date_naive() < d) {
+                        continue;
+                    }
+                    if !regex.is_match(&entry.to_markdown()) {
+                        continue;
+                    }
+                    entries.push(entry);
+                    if entries.len() >= limit {
+                        return Ok(entries);
+                    }
+                }
+            }
         }
 
         // Sort by date (newest first)
@@ -349,11 +507,37 @@ impl HistoryStore {
     }
 }
 
-/// Generate a unique ID for an entry
+/// Whether `name` can be used as one component of an entry's path: not
+/// empty, `.` or `..`, and free of separators
+pub fn is_path_segment(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\']) && !Path::new(name).is_absolute()
+}
+
+/// Truncate to at most `max` characters, marking the cut with an ellipsis
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut out: String = s.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Generate a unique ID for an entry.
+///
+/// The id names the entry's file, so two entries must never share one: a
+/// single hook can capture several entries within a millisecond. Ids are
+/// nanosecond timestamps, bumped past the last one issued when the clock
+/// hasn't moved on.
 fn generate_id() -> String {
+    use std::sync::Mutex;
     use std::time::{SystemTime, UNIX_EPOCH};
-    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
-    format!("{:x}", timestamp)
+
+    static LAST: Mutex<u128> = Mutex::new(0);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    *last = now.max(*last + 1);
+    format!("{:x}", *last)
 }
 
 #[cfg(test)]
@@ -373,12 +557,66 @@ mod tests {
         assert!(md.contains("This is a test"));
     }
 
+    #[test]
+    fn test_entry_markdown_roundtrip() {
+        let entry = HistoryEntry::new("sessions", "Test Session", "Line one\n\nLine two").with_tag("test");
+        let parsed = HistoryEntry::from_markdown(&entry.to_markdown(), Path::new("x.md")).unwrap();
+        assert_eq!(parsed.id, entry.id);
+        assert_eq!(parsed.title, "Test Session");
+        assert_eq!(parsed.content, "Line one\n\nLine two");
+        assert_eq!(parsed.tags, vec!["test"]);
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let entry = HistoryEntry::new("sessions", "Test", "body").with_tag("rust");
+        let today = entry.created_at.date_naive();
+
+        assert!(HistoryFilter::default().matches(&entry));
+        assert!(
+            HistoryFilter {
+                category: Some("sessions".to_string()),
+                since: Some(today),
+                until: Some(today),
+                tags: vec!["rust".to_string(), "go".to_string()],
+            }
+            .matches(&entry)
+        );
+        assert!(
+            !HistoryFilter {
+                category: Some("events".to_string()),
+                ..Default::default()
+            }
+            .matches(&entry)
+        );
+        assert!(
+            !HistoryFilter {
+                tags: vec!["go".to_string()],
+                ..Default::default()
+            }
+            .matches(&entry)
+        );
+        assert!(
+            !HistoryFilter {
+                since: today.succ_opt(),
+                ..Default::default()
+            }
+            .matches(&entry)
+        );
+    }
+
     #[test]
     fn test_generate_id() {
-        let id1 = generate_id();
-        std::thread::sleep(std::time::Duration::from_millis(2));
-        let id2 = generate_id();
-        assert!(!id1.is_empty());
-        assert_ne!(id1, id2);
+        let ids: std::collections::HashSet<String> = (0..1000).map(|_| generate_id()).collect();
+        assert_eq!(ids.len(), 1000);
+        assert!(ids.iter().all(|id| !id.is_empty()));
+
+        // Entries captured back to back get their own files
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        let first = store.store(&HistoryEntry::new("errors", "First", "a")).unwrap();
+        let second = store.store(&HistoryEntry::new("errors", "Second", "b")).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(store.count("errors").unwrap(), 2);
     }
 }
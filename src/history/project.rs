@@ -0,0 +1,203 @@
+//! Project identity for history entries
+//!
+//! A project is identified by its git remote slug (`host/owner/repo`, from
+//! `remote.origin.url`), falling back to the git root path for repos without
+//! a remote, and to the directory itself outside git. Hooks record the id as
+//! `project` metadata so entries from any subdirectory of a repo (or any
+//! clone of it) group together.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::contract::memory::MemoryResult;
+
+/// Metadata key holding the project id
+pub const PROJECT_KEY: &str = "project";
+
+/// A normalized project identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    /// Stable identifier: remote slug or root path
+    pub id: String,
+    /// Git root (or the directory itself outside git)
+    pub root: PathBuf,
+}
+
+impl Project {
+    /// Detect the project containing `path`
+    pub fn detect(path: &Path) -> Self {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        match git_root(&path) {
+            Some(root) => {
+                let id = origin_url(&root)
+                    .and_then(|url| remote_slug(&url))
+                    .unwrap_or_else(|| root.display().to_string());
+                Self { id, root }
+            }
+            None => Self {
+                id: path.display().to_string(),
+                root: path,
+            },
+        }
+    }
+}
+
+/// Find the enclosing git work tree root
+fn git_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Read `remote.origin.url` from a work tree's git config
+fn origin_url(root: &Path) -> Option<String> {
+    let dot_git = root.join(".git");
+
+    // Worktrees and submodules have a `.git` file pointing at the real git dir
+    let git_dir = if dot_git.is_file() {
+        let content = fs::read_to_string(&dot_git).ok()?;
+        let target = content.trim().strip_prefix("gitdir:")?.trim();
+        let target = root.join(target);
+        // Linked worktrees keep the shared config in the common dir
+        match fs::read_to_string(target.join("commondir")) {
+            Ok(common) => target.join(common.trim()),
+            Err(_) => target,
+        }
+    } else {
+        dot_git
+    };
+
+    let config = fs::read_to_string(git_dir.join("config")).ok()?;
+    parse_origin_url(&config)
+}
+
+/// Extract the origin url from git config text
+fn parse_origin_url(config: &str) -> Option<String> {
+    let mut in_origin = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+            continue;
+        }
+        if in_origin
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+        {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Normalize a git remote url to `host/owner/repo`
+///
+/// Handles `https://host/owner/repo.git`, `ssh://git@host:22/owner/repo` and
+/// scp-style `git@host:owner/repo.git`. Local paths have no slug.
+pub fn remote_slug(url: &str) -> Option<String> {
+    let url = url.trim();
+
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let rest = rest.rsplit_once('@').map(|(_, r)| r).unwrap_or(rest);
+        let (host, path) = rest.split_once('/')?;
+        (host.split(':').next().unwrap_or(host), path)
+    } else if let Some((user_host, path)) = url.split_once(':')
+        && !user_host.contains('/')
+    {
+        let host = user_host.rsplit_once('@').map(|(_, h)| h).unwrap_or(user_host);
+        (host, path)
+    } else {
+        return None;
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}/{}", host.to_lowercase(), path))
+}
+
+/// Resolve the project a stored entry belongs to.
+///
+/// Uses recorded `project` metadata; older entries that only recorded `cwd`
+/// are resolved from it (memoized in `cache`). Entries with neither are global.
+pub fn entry_project(result: &MemoryResult, cache: &mut HashMap<String, String>) -> Option<String> {
+    if let Some(project) = result.meta_str(PROJECT_KEY) {
+        return Some(project.to_string());
+    }
+
+    let cwd = result.meta_str("cwd").filter(|c| !c.is_empty() && *c != "unknown")?;
+    let id = cache
+        .entry(cwd.to_string())
+        .or_insert_with(|| Project::detect(Path::new(cwd)).id);
+    Some(id.clone())
+}
+
+/// Resolve a `--project` argument: an existing path is detected, anything
+/// else is taken as a project id
+pub fn resolve_arg(arg: &str) -> String {
+    let path = Path::new(arg);
+    if path.exists() {
+        Project::detect(path).id
+    } else {
+        arg.trim_end_matches('/').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_slug() {
+        assert_eq!(
+            remote_slug("https://github.com/scottidler/paii.git").as_deref(),
+            Some("github.com/scottidler/paii")
+        );
+        assert_eq!(
+            remote_slug("git@github.com:scottidler/paii.git").as_deref(),
+            Some("github.com/scottidler/paii")
+        );
+        assert_eq!(
+            remote_slug("ssh://git@GitHub.com:22/scottidler/paii").as_deref(),
+            Some("github.com/scottidler/paii")
+        );
+        assert_eq!(remote_slug("/srv/git/paii.git"), None);
+    }
+
+    #[test]
+    fn test_parse_origin_url() {
+        let config =
+            "[core]\n\tbare = false\n[remote \"upstream\"]\n\turl = a\n[remote \"origin\"]\n\turl = git@host:o/r.git\n";
+        assert_eq!(parse_origin_url(config).as_deref(), Some("git@host:o/r.git"));
+        assert_eq!(parse_origin_url("[core]\n"), None);
+    }
+
+    #[test]
+    fn test_detect_git_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("src/deep")).unwrap();
+
+        // No remote: the git root identifies the project
+        let project = Project::detect(&root.join("src/deep"));
+        assert_eq!(project.root, root);
+        assert_eq!(project.id, root.display().to_string());
+
+        fs::write(
+            root.join(".git/config"),
+            "[remote \"origin\"]\n\turl = https://github.com/acme/widgets.git\n",
+        )
+        .unwrap();
+        let project = Project::detect(&root.join("src"));
+        assert_eq!(project.id, "github.com/acme/widgets");
+    }
+}
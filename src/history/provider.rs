@@ -0,0 +1,197 @@
+//! Built-in MemoryProvider backed by the file history store
+
+use chrono::{DateTime, Local, NaiveDate};
+use eyre::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{HistoryEntry, HistoryStore};
+use crate::contract::memory::{MemoryProvider, MemoryResult};
+
+/// MemoryProvider storing entries as markdown files via `HistoryStore`
+pub struct FileMemoryProvider {
+    store: HistoryStore,
+}
+
+impl FileMemoryProvider {
+    pub fn new(base_path: PathBuf) -> Self {
+        Self {
+            store: HistoryStore::new(base_path),
+        }
+    }
+
+    fn to_result(&self, entry: HistoryEntry) -> MemoryResult {
+        let path = self
+            .store
+            .base_path()
+            .join(&entry.category)
+            .join(entry.created_at.format("%Y-%m-%d").to_string())
+            .join(format!("{}.md", entry.id));
+
+        let mut metadata: HashMap<String, serde_json::Value> = entry
+            .metadata
+            .into_iter()
+            .map(|(k, v)| (k, serde_json::Value::String(v)))
+            .collect();
+        metadata.insert("id".to_string(), entry.id.into());
+        metadata.insert("title".to_string(), entry.title.into());
+        metadata.insert("tags".to_string(), entry.tags.into());
+
+        MemoryResult {
+            path: path.display().to_string(),
+            category: entry.category,
+            timestamp: entry.created_at.to_rfc3339(),
+            content: entry.content,
+            metadata,
+        }
+    }
+}
+
+/// Add `tags` and the remaining metadata, as strings, to an entry
+fn with_metadata(mut entry: HistoryEntry, mut metadata: HashMap<String, serde_json::Value>) -> HistoryEntry {
+    if let Some(serde_json::Value::Array(tags)) = metadata.remove("tags") {
+        for tag in tags.iter().filter_map(|t| t.as_str()) {
+            entry = entry.with_tag(tag);
+        }
+    }
+
+    for (key, value) in metadata {
+        let value = match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        entry = entry.with_metadata(&key, &value);
+    }
+    entry
+}
+
+/// Category argument to store filter: empty means all categories
+fn category_filter(category: &str) -> Option<&str> {
+    if category.is_empty() { None } else { Some(category) }
+}
+
+impl MemoryProvider for FileMemoryProvider {
+    fn capture(
+        &self,
+        category: &str,
+        content: &str,
+        mut metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<String> {
+        let title = match metadata.remove("title") {
+            Some(serde_json::Value::String(t)) => t,
+            _ => content.lines().next().unwrap_or(category).to_string(),
+        };
+
+        let entry = with_metadata(HistoryEntry::new(category, &title, content), metadata);
+
+        let path = self.store.store(&entry)?;
+        Ok(path.display().to_string())
+    }
+
+    fn query(&self, category: &str, query: &str, limit: usize) -> Result<Vec<MemoryResult>> {
+        self.query_since(category, query, None, limit)
+    }
+
+    fn query_since(
+        &self,
+        category: &str,
+        query: &str,
+        since: Option<NaiveDate>,
+        limit: usize,
+    ) -> Result<Vec<MemoryResult>> {
+        let entries = self.store.query(query, category_filter(category), since, limit)?;
+        Ok(entries.into_iter().map(|e| self.to_result(e)).collect())
+    }
+
+    fn list_categories(&self) -> Vec<String> {
+        self.store.categories().unwrap_or_default()
+    }
+
+    fn get_recent(&self, category: &str, count: usize) -> Result<Vec<MemoryResult>> {
+        let entries = self.store.recent(category_filter(category), count)?;
+        Ok(entries.into_iter().map(|e| self.to_result(e)).collect())
+    }
+
+    fn update(&self, result: &MemoryResult) -> Result<()> {
+        let mut metadata = result.metadata.clone();
+        let id = match metadata.remove("id") {
+            Some(serde_json::Value::String(id)) => id,
+            _ => eyre::bail!("Entry {} has no id", result.path),
+        };
+
+        let mut entry = HistoryEntry::new(&result.category, &result.title(), &result.content);
+        entry.id = id;
+        entry.created_at = DateTime::parse_from_rfc3339(&result.timestamp)
+            .context(format!("Invalid timestamp '{}'", result.timestamp))?
+            .with_timezone(&Local);
+        metadata.remove("title");
+
+        self.store.update(&with_metadata(entry, metadata))
+    }
+
+    fn count(&self, category: &str) -> Result<usize> {
+        self.store.count(category)
+    }
+
+    fn provider_name(&self) -> String {
+        format!("file ({})", self.store.base_path().display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_capture_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = FileMemoryProvider::new(dir.path().to_path_buf());
+
+        let metadata = HashMap::from([
+            ("title".to_string(), json!("Use eyre")),
+            ("tags".to_string(), json!(["rust", "errors"])),
+            ("project".to_string(), json!("paii")),
+        ]);
+        let path = provider
+            .capture("learnings", "Wrap errors with context", metadata)
+            .unwrap();
+        assert!(path.ends_with(".md"));
+
+        let results = provider.query("learnings", "context", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title(), "Use eyre");
+        assert_eq!(results[0].tags(), vec!["rust", "errors"]);
+        assert_eq!(results[0].meta_str("project"), Some("paii"));
+        assert_eq!(results[0].content, "Wrap errors with context");
+        assert_eq!(results[0].path, path);
+
+        assert_eq!(provider.list_categories(), vec!["learnings"]);
+        assert_eq!(provider.get_recent("", 5).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = FileMemoryProvider::new(dir.path().to_path_buf());
+        let metadata = HashMap::from([("occurrences".to_string(), json!("1"))]);
+        let path = provider.capture("errors", "cargo build failed", metadata).unwrap();
+
+        let mut entry = provider.get_recent("errors", 1).unwrap().remove(0);
+        entry.content = "cargo build failed again".to_string();
+        entry.metadata.insert("occurrences".to_string(), json!("2"));
+        provider.update(&entry).unwrap();
+
+        let updated = provider.get_recent("errors", 5).unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].path, path);
+        assert_eq!(updated[0].meta_str("id"), entry.meta_str("id"));
+        assert_eq!(updated[0].timestamp, entry.timestamp);
+        assert_eq!(updated[0].title(), "cargo build failed");
+        assert_eq!(updated[0].meta_str("occurrences"), Some("2"));
+        assert_eq!(updated[0].content, "cargo build failed again");
+
+        entry.metadata.insert("id".to_string(), json!("missing"));
+        assert!(provider.update(&entry).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! History retention and compaction
+//!
+//! Applies a per-category [`RetentionPolicy`]: entries past `max_age_days` or
+//! beyond `max_count` are removed (unless protected by `keep_tagged`), and
+//! surviving entries older than `compact_after_days` are moved into monthly
+//! archives.
+
+use chrono::{DateTime, Duration, Local};
+use eyre::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use super::{HistoryEntry, HistoryStore, archive};
+use crate::config::RetentionPolicy;
+
+/// Where an entry currently lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// A markdown file in a day directory
+    File(PathBuf),
+    /// A record inside the monthly archive for YYYY-MM
+    Archive(String),
+}
+
+/// An entry selected by a prune plan
+#[derive(Debug, Clone)]
+pub struct PlannedEntry {
+    pub entry: HistoryEntry,
+    pub location: Location,
+}
+
+/// What pruning a category would do
+#[derive(Debug, Clone, Default)]
+pub struct PrunePlan {
+    pub category: String,
+    /// Entries to delete
+    pub remove: Vec<PlannedEntry>,
+    /// Live entries to move into monthly archives
+    pub compact: Vec<PlannedEntry>,
+    /// Entries left untouched
+    pub kept: usize,
+}
+
+impl PrunePlan {
+    pub fn is_empty(&self) -> bool {
+        self.remove.is_empty() && self.compact.is_empty()
+    }
+}
+
+/// Work out what a policy would do to a category, without changing anything
+pub fn plan(store: &HistoryStore, category: &str, policy: &RetentionPolicy, now: DateTime<Local>) -> Result<PrunePlan> {
+    let cat_path = store.base_path().join(category);
+
+    let mut all: Vec<PlannedEntry> = store
+        .live_entries(category)?
+        .into_iter()
+        .map(|(path, entry)| PlannedEntry {
+            entry,
+            location: Location::File(path),
+        })
+        .collect();
+    for (month, path) in archive::list_archives(&cat_path)? {
+        for entry in archive::read_archive(&path)? {
+            all.push(PlannedEntry {
+                entry,
+                location: Location::Archive(month.clone()),
+            });
+        }
+    }
+
+    // Newest first, so max_count keeps the most recent entries
+    all.sort_by_key(|p| std::cmp::Reverse(p.entry.created_at));
+
+    let age_cutoff = policy.max_age_days.map(|d| now - Duration::days(d.into()));
+    let compact_cutoff = policy.compact_after_days.map(|d| now - Duration::days(d.into()));
+
+    let mut plan = PrunePlan {
+        category: category.to_string(),
+        ..Default::default()
+    };
+    let mut unprotected = 0;
+
+    for item in all {
+        let protected = item.entry.tags.iter().any(|t| policy.keep_tagged.contains(t));
+
+        if !protected {
+            unprotected += 1;
+            let too_old = age_cutoff.is_some_and(|cutoff| item.entry.created_at < cutoff);
+            let over_count = policy.max_count.is_some_and(|max| unprotected > max);
+            if too_old || over_count {
+                plan.remove.push(item);
+                continue;
+            }
+        }
+
+        let compactable = matches!(item.location, Location::File(_))
+            && compact_cutoff.is_some_and(|cutoff| item.entry.created_at < cutoff);
+        if compactable {
+            plan.compact.push(item);
+        } else {
+            plan.kept += 1;
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Carry out a prune plan
+pub fn apply(store: &HistoryStore, plan: &PrunePlan) -> Result<()> {
+    let cat_path = store.base_path().join(&plan.category);
+
+    // Archive records to drop and live entries to add, per month
+    let mut dropped: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    let mut added: BTreeMap<String, Vec<HistoryEntry>> = BTreeMap::new();
+
+    for item in &plan.remove {
+        match &item.location {
+            Location::File(path) => {
+                fs::remove_file(path).context(format!("Failed to remove {}", path.display()))?;
+            }
+            Location::Archive(month) => {
+                dropped.entry(month.clone()).or_default().insert(item.entry.id.clone());
+            }
+        }
+    }
+
+    for item in &plan.compact {
+        let month = item.entry.created_at.format("%Y-%m").to_string();
+        added.entry(month).or_default().push(item.entry.clone());
+    }
+
+    let months: HashSet<&String> = dropped.keys().chain(added.keys()).collect();
+    for month in months {
+        let path = archive::archive_path(&cat_path, month);
+        let mut entries = if path.exists() {
+            archive::read_archive(&path)?
+        } else {
+            Vec::new()
+        };
+
+        if let Some(ids) = dropped.get(month) {
+            entries.retain(|e| !ids.contains(&e.id));
+        }
+        if let Some(new) = added.get(month) {
+            let existing: HashSet<String> = entries.iter().map(|e| e.id.clone()).collect();
+            entries.extend(new.iter().filter(|e| !existing.contains(&e.id)).cloned());
+        }
+        entries.sort_by_key(|e| e.created_at);
+
+        archive::write_archive(&path, &entries)?;
+        log::info!("Wrote archive {} ({} entries)", path.display(), entries.len());
+    }
+
+    // Only delete compacted files once their archive is safely written
+    for item in &plan.compact {
+        if let Location::File(path) = &item.location {
+            fs::remove_file(path).context(format!("Failed to remove {}", path.display()))?;
+        }
+    }
+
+    remove_empty_day_dirs(&cat_path)?;
+    Ok(())
+}
+
+fn remove_empty_day_dirs(cat_path: &std::path::Path) -> Result<()> {
+    if !cat_path.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(cat_path)? {
+        let path = entry?.path();
+        if path.is_dir() && fs::read_dir(&path)?.next().is_none() {
+            fs::remove_dir(&path).ok();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_days_ago(store: &HistoryStore, title: &str, days: i64, tag: Option<&str>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new("events", title, "body");
+        entry.id = format!("{}-{}", title, days);
+        entry.created_at = Local::now() - Duration::days(days);
+        if let Some(tag) = tag {
+            entry = entry.with_tag(tag);
+        }
+        store.store(&entry).unwrap();
+        entry
+    }
+
+    #[test]
+    fn test_plan_max_age_respects_keep_tagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        entry_days_ago(&store, "fresh", 1, None);
+        entry_days_ago(&store, "stale", 40, None);
+        entry_days_ago(&store, "pinned", 40, Some("pinned"));
+
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            keep_tagged: vec!["pinned".to_string()],
+            ..Default::default()
+        };
+        let plan = plan(&store, "events", &policy, Local::now()).unwrap();
+
+        assert_eq!(plan.remove.len(), 1);
+        assert_eq!(plan.remove[0].entry.title, "stale");
+        assert_eq!(plan.kept, 2);
+    }
+
+    #[test]
+    fn test_plan_max_count_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        for days in 0..5 {
+            entry_days_ago(&store, "e", days, None);
+        }
+
+        let policy = RetentionPolicy {
+            max_count: Some(2),
+            ..Default::default()
+        };
+        let plan = plan(&store, "events", &policy, Local::now()).unwrap();
+
+        assert_eq!(plan.kept, 2);
+        let mut removed: Vec<String> = plan.remove.iter().map(|p| p.entry.id.clone()).collect();
+        removed.sort();
+        assert_eq!(removed, vec!["e-2", "e-3", "e-4"]);
+    }
+
+    #[test]
+    fn test_compaction_keeps_entries_queryable() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        entry_days_ago(&store, "recent", 1, None);
+        entry_days_ago(&store, "old", 90, None);
+
+        let policy = RetentionPolicy {
+            compact_after_days: Some(30),
+            ..Default::default()
+        };
+        let plan = plan(&store, "events", &policy, Local::now()).unwrap();
+        assert_eq!(plan.compact.len(), 1);
+        apply(&store, &plan).unwrap();
+
+        assert_eq!(store.live_entries("events").unwrap().len(), 1);
+        assert_eq!(store.count("events").unwrap(), 2);
+        let found = store.query("old", Some("events"), None, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "old-90");
+
+        // A second pass has nothing left to compact
+        let again = super::plan(&store, "events", &policy, Local::now()).unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_prune_removes_from_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        entry_days_ago(&store, "old", 90, None);
+
+        let compact = RetentionPolicy {
+            compact_after_days: Some(30),
+            ..Default::default()
+        };
+        apply(&store, &plan(&store, "events", &compact, Local::now()).unwrap()).unwrap();
+
+        let expire = RetentionPolicy {
+            max_age_days: Some(60),
+            ..Default::default()
+        };
+        let plan = plan(&store, "events", &expire, Local::now()).unwrap();
+        assert!(matches!(plan.remove[0].location, Location::Archive(_)));
+        apply(&store, &plan).unwrap();
+
+        assert_eq!(store.count("events").unwrap(), 0);
+        assert!(archive::list_archives(&dir.path().join("events")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+//! Usage statistics over history entries and session journals
+
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::journal::{JournalEvent, JournalRecord};
+use super::project;
+use crate::contract::memory::MemoryResult;
+
+/// Category holding security hook blocks
+pub const SECURITY_CATEGORY: &str = "security";
+
+/// Tag on entries recording a blocked action
+pub const BLOCK_TAG: &str = "block";
+
+/// How many rows ranked lists keep
+const TOP_N: usize = 10;
+
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub entries: usize,
+    pub sessions: usize,
+    /// Distinct sessions first seen on each day
+    pub sessions_per_day: BTreeMap<String, usize>,
+    /// Distinct sessions first seen in each ISO week (`YYYY-Www`)
+    pub sessions_per_week: BTreeMap<String, usize>,
+    /// Average span between a session's first and last recorded event
+    pub avg_session_minutes: Option<f64>,
+    pub projects: Vec<ProjectCount>,
+    pub tools: Vec<ToolCount>,
+    pub blocks: BlockCounts,
+    pub tags: Vec<TagCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectCount {
+    pub project: String,
+    pub sessions: usize,
+    pub entries: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolCount {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BlockCounts {
+    pub total: usize,
+    pub by_reason: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Compute statistics.
+///
+/// `entries` should already be filtered to the date range; journal records
+/// outside the range are skipped here.
+pub fn compute(
+    entries: &[MemoryResult],
+    journals: &[(String, Vec<JournalRecord>)],
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> Stats {
+    let in_range = |at: &DateTime<Local>| {
+        let date = at.date_naive();
+        since.is_none_or(|d| date >= d) && until.is_none_or(|d| date <= d)
+    };
+
+    // First and last activity per session
+    let mut session_spans: HashMap<String, (DateTime<Local>, DateTime<Local>)> = HashMap::new();
+    let mut record_span = |session: &str, at: DateTime<Local>| {
+        let span = session_spans.entry(session.to_string()).or_insert((at, at));
+        span.0 = span.0.min(at);
+        span.1 = span.1.max(at);
+    };
+
+    let mut project_sessions: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut project_entries: HashMap<String, usize> = HashMap::new();
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    let mut blocks = BlockCounts::default();
+    let mut cwd_projects = HashMap::new();
+
+    for entry in entries {
+        let session = entry.meta_str("session_id").filter(|s| *s != "unknown");
+        if let Some(session) = session
+            && let Ok(at) = DateTime::parse_from_rfc3339(&entry.timestamp)
+        {
+            record_span(session, at.with_timezone(&Local));
+        }
+
+        if let Some(project) = project::entry_project(entry, &mut cwd_projects) {
+            *project_entries.entry(project.clone()).or_default() += 1;
+            if let Some(session) = session {
+                project_sessions.entry(project).or_default().insert(session.to_string());
+            }
+        }
+
+        let entry_tags = entry.tags();
+        if entry.category == SECURITY_CATEGORY && entry_tags.iter().any(|t| t == BLOCK_TAG) {
+            blocks.total += 1;
+            let reason = entry
+                .meta_str("reason")
+                .map(String::from)
+                .unwrap_or_else(|| entry.title());
+            *blocks.by_reason.entry(reason).or_default() += 1;
+        }
+
+        for tag in entry_tags {
+            *tags.entry(tag).or_default() += 1;
+        }
+    }
+
+    let mut tools: HashMap<String, (usize, usize)> = HashMap::new();
+    for (session, records) in journals {
+        for record in records.iter().filter(|r| in_range(&r.at)) {
+            record_span(session, record.at);
+            if let JournalEvent::Tool { tool, success, .. } = &record.event {
+                let counts = tools.entry(tool.clone()).or_default();
+                counts.0 += 1;
+                if !success {
+                    counts.1 += 1;
+                }
+            }
+        }
+    }
+
+    let mut stats = Stats {
+        since,
+        until,
+        entries: entries.len(),
+        sessions: session_spans.len(),
+        blocks,
+        ..Stats::default()
+    };
+
+    let mut total_minutes = 0.0;
+    let mut timed = 0;
+    for (start, end) in session_spans.values() {
+        let day = start.date_naive();
+        *stats.sessions_per_day.entry(day.to_string()).or_default() += 1;
+        let week = day.iso_week();
+        *stats
+            .sessions_per_week
+            .entry(format!("{}-W{:02}", week.year(), week.week()))
+            .or_default() += 1;
+
+        if end > start {
+            total_minutes += (*end - *start).num_seconds() as f64 / 60.0;
+            timed += 1;
+        }
+    }
+    if timed > 0 {
+        stats.avg_session_minutes = Some((total_minutes / timed as f64 * 10.0).round() / 10.0);
+    }
+
+    let mut projects: Vec<ProjectCount> = project_entries
+        .into_iter()
+        .map(|(project, entries)| ProjectCount {
+            sessions: project_sessions.get(&project).map_or(0, |s| s.len()),
+            project,
+            entries,
+        })
+        .collect();
+    projects.sort_by(|a, b| (b.sessions, b.entries, &a.project).cmp(&(a.sessions, a.entries, &b.project)));
+    projects.truncate(TOP_N);
+    stats.projects = projects;
+
+    let mut tools: Vec<ToolCount> = tools
+        .into_iter()
+        .map(|(tool, (calls, failures))| ToolCount { tool, calls, failures })
+        .collect();
+    tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+    tools.truncate(TOP_N);
+    stats.tools = tools;
+
+    let mut tags: Vec<TagCount> = tags.into_iter().map(|(tag, count)| TagCount { tag, count }).collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    tags.truncate(TOP_N * 2);
+    stats.tags = tags;
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn entry(category: &str, session: &str, project: &str, created_at: DateTime<Local>) -> MemoryResult {
+        MemoryResult {
+            path: String::new(),
+            category: category.to_string(),
+            timestamp: created_at.to_rfc3339(),
+            content: "c".to_string(),
+            metadata: HashMap::from([
+                ("title".to_string(), json!("t")),
+                ("session_id".to_string(), json!(session)),
+                (project::PROJECT_KEY.to_string(), json!(project)),
+            ]),
+        }
+    }
+
+    fn with(mut entry: MemoryResult, key: &str, value: serde_json::Value) -> MemoryResult {
+        entry.metadata.insert(key.to_string(), value);
+        entry
+    }
+
+    fn tagged(entry: MemoryResult, tag: &str) -> MemoryResult {
+        with(entry, "tags", json!([tag]))
+    }
+
+    #[test]
+    fn test_compute() {
+        let block = tagged(entry(SECURITY_CATEGORY, "s2", "acme/b", at(4, 9)), BLOCK_TAG);
+        let entries = vec![
+            entry("events", "s1", "acme/a", at(3, 9)),
+            tagged(entry("sessions", "s1", "acme/a", at(3, 10)), "end_turn"),
+            tagged(entry("sessions", "s2", "acme/b", at(4, 9)), "end_turn"),
+            with(block, "reason", json!("Remote code execution")),
+        ];
+        let journals = vec![(
+            "s2".to_string(),
+            vec![JournalRecord {
+                at: at(4, 9),
+                event: JournalEvent::Tool {
+                    tool: "Bash".into(),
+                    input: "ls".into(),
+                    success: false,
+                    error: None,
+                    duration_ms: None,
+                    tool_use_id: None,
+                },
+            }],
+        )];
+
+        let stats = compute(&entries, &journals, None, None);
+        assert_eq!(stats.sessions, 2);
+        assert_eq!(stats.sessions_per_day["2025-03-03"], 1);
+        assert_eq!(stats.sessions_per_week.values().sum::<usize>(), 2);
+        assert_eq!(stats.avg_session_minutes, Some(60.0));
+        assert_eq!(stats.projects[0].entries, 2);
+        assert_eq!(stats.tools[0].tool, "Bash");
+        assert_eq!(stats.tools[0].failures, 1);
+        assert_eq!(stats.blocks.total, 1);
+        assert_eq!(stats.blocks.by_reason["Remote code execution"], 1);
+        assert_eq!(stats.tags[0].tag, "end_turn");
+        assert_eq!(stats.tags[0].count, 2);
+
+        // Journal records outside the range are ignored
+        let stats = compute(&[], &journals, NaiveDate::from_ymd_opt(2025, 3, 5), None);
+        assert!(stats.tools.is_empty());
+    }
+}
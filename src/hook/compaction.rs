@@ -0,0 +1,247 @@
+//! PreCompact hook handler
+//!
+//! Claude Code compacts long sessions by replacing the conversation with a
+//! summary, and the detail is gone. Before that happens, this handler reads
+//! the transcript (`transcript_path` in the payload) and stores a condensed
+//! copy in the `compactions` category, linked to the session by `session_id`.
+//! `paii history show <session_id>` brings it back.
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::contract::memory::MemoryProvider;
+use crate::history::project::{PROJECT_KEY, Project};
+use crate::history::{journal, truncate};
+
+/// Category holding compaction snapshots
+pub const COMPACTIONS_CATEGORY: &str = "compactions";
+
+/// Longest single message kept in a snapshot
+const MAX_MESSAGE_CHARS: usize = 4000;
+
+/// Snapshot size cap; older messages are dropped first
+const MAX_SNAPSHOT_CHARS: usize = 200_000;
+
+/// PreCompact handler - snapshots the transcript before compaction
+pub struct CompactionHandler {
+    enabled: bool,
+    memory: Arc<dyn MemoryProvider>,
+}
+
+impl CompactionHandler {
+    pub fn new(enabled: bool, memory: Arc<dyn MemoryProvider>) -> Self {
+        Self { enabled, memory }
+    }
+
+    fn snapshot(&self, payload: &serde_json::Value) -> eyre::Result<Option<String>> {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let trigger = payload.get("trigger").and_then(|v| v.as_str()).unwrap_or("auto");
+
+        let Some(transcript_path) = payload.get("transcript_path").and_then(|v| v.as_str()) else {
+            log::warn!("PreCompact payload has no transcript_path; nothing to snapshot");
+            return Ok(None);
+        };
+        let transcript_path = shellexpand::tilde(transcript_path).to_string();
+        let transcript = fs::read_to_string(&transcript_path)?;
+
+        let messages = parse_transcript(&transcript);
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        let mut content = format!(
+            "Snapshot of {} messages before {} compaction.\n\n",
+            messages.len(),
+            trigger
+        );
+        if let Some(instructions) = payload
+            .get("custom_instructions")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+        {
+            content.push_str(&format!("**Compaction instructions:** {}\n\n", instructions.trim()));
+        }
+        content.push_str(&render(&messages, MAX_SNAPSHOT_CHARS));
+
+        let mut metadata = HashMap::from([
+            (
+                "title".to_string(),
+                json!(format!(
+                    "Compaction of session {}",
+                    &session_id[..8.min(session_id.len())]
+                )),
+            ),
+            ("tags".to_string(), json!(["compaction", trigger])),
+            ("session_id".to_string(), json!(session_id)),
+            ("transcript_path".to_string(), json!(transcript_path)),
+            ("messages".to_string(), json!(messages.len().to_string())),
+        ]);
+        if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+            metadata.insert("cwd".to_string(), json!(cwd));
+            metadata.insert(PROJECT_KEY.to_string(), json!(Project::detect(Path::new(cwd)).id));
+        }
+
+        self.memory.capture(COMPACTIONS_CATEGORY, &content, metadata).map(Some)
+    }
+}
+
+impl HookHandler for CompactionHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && event == HookEvent::PreCompact
+    }
+
+    fn handle(&self, _event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        match self.snapshot(payload) {
+            Ok(Some(path)) => {
+                log::info!("Saved pre-compaction snapshot to: {}", path);
+                HookResult::Allow
+            }
+            Ok(None) => HookResult::Allow,
+            Err(e) => HookResult::Error {
+                message: format!("Failed to snapshot transcript: {}", e),
+            },
+        }
+    }
+}
+
+/// A condensed transcript message
+#[derive(Debug, PartialEq)]
+struct Message {
+    role: String,
+    text: String,
+}
+
+/// Extract user/assistant text and tool calls from a Claude Code JSONL transcript
+fn parse_transcript(transcript: &str) -> Vec<Message> {
+    transcript
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|record| {
+            let message = record.get("message")?;
+            let role = message
+                .get("role")
+                .or_else(|| record.get("type"))
+                .and_then(|v| v.as_str())?
+                .to_string();
+            if role != "user" && role != "assistant" {
+                return None;
+            }
+
+            let text = message_text(message.get("content")?);
+            (!text.trim().is_empty()).then(|| Message {
+                role,
+                text: truncate(text.trim(), MAX_MESSAGE_CHARS),
+            })
+        })
+        .collect()
+}
+
+/// Flatten message content: text blocks verbatim, tool calls as one-liners.
+/// Tool results are skipped; they are usually large and reproducible.
+fn message_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(|v| v.as_str()) {
+                Some("text") => block.get("text").and_then(|v| v.as_str()).map(str::to_string),
+                Some("tool_use") => {
+                    let name = block.get("name").and_then(|v| v.as_str()).unwrap_or("tool");
+                    let input = block.get("input").cloned().unwrap_or_default();
+                    Some(format!("→ {} `{}`", name, journal::summarize_input(name, &input)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+/// Render messages as markdown, keeping the most recent ones within `budget`
+fn render(messages: &[Message], budget: usize) -> String {
+    let mut sections: Vec<String> = Vec::new();
+    let mut used = 0;
+
+    for message in messages.iter().rev() {
+        let section = format!("### {}\n\n{}\n", message.role, message.text);
+        used += section.len();
+        if used > budget {
+            sections.push(format!(
+                "_{} earlier messages omitted_\n",
+                messages.len() - sections.len()
+            ));
+            break;
+        }
+        sections.push(section);
+    }
+
+    sections.reverse();
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::provider::FileMemoryProvider;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"Why is the build failing?"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me check."},{"type":"tool_use","name":"Bash","input":{"command":"cargo build"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"huge output"}]}}
+{"type":"summary","summary":"ignored"}
+"#;
+
+    #[test]
+    fn test_parse_transcript() {
+        let messages = parse_transcript(TRANSCRIPT);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].text, "Why is the build failing?");
+        assert_eq!(messages[1].text, "Let me check.\n\n→ Bash `cargo build`");
+    }
+
+    #[test]
+    fn test_render_drops_oldest_over_budget() {
+        let messages = vec![
+            Message {
+                role: "user".into(),
+                text: "a".repeat(100),
+            },
+            Message {
+                role: "assistant".into(),
+                text: "recent".into(),
+            },
+        ];
+        let rendered = render(&messages, 50);
+        assert!(rendered.starts_with("_1 earlier messages omitted_"));
+        assert!(rendered.contains("recent"));
+    }
+
+    #[test]
+    fn test_snapshot_linked_to_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let transcript = dir.path().join("t.jsonl");
+        fs::write(&transcript, TRANSCRIPT).unwrap();
+
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().join("history")));
+        let handler = CompactionHandler::new(true, memory.clone());
+        let payload = json!({
+            "session_id": "abc123",
+            "transcript_path": transcript.display().to_string(),
+            "trigger": "manual"
+        });
+        assert!(matches!(
+            handler.handle(HookEvent::PreCompact, &payload),
+            HookResult::Allow
+        ));
+
+        let snapshots = memory.get_recent(COMPACTIONS_CATEGORY, 5).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].meta_str("session_id"), Some("abc123"));
+        assert!(snapshots[0].content.contains("Why is the build failing?"));
+        assert!(snapshots[0].content.contains("manual compaction"));
+    }
+}
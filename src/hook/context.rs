@@ -0,0 +1,248 @@
+//! Session context hook
+//!
+//! On SessionStart, hands Claude a short digest of what PAII remembers about
+//! the current project: the last session summary recorded there, plus recent
+//! entries from the configured history categories. Entries from other
+//! projects are skipped; entries without a project (or `cwd`) are treated as
+//! global and always eligible.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::config::ContextConfig;
+use crate::contract::memory::{MemoryProvider, MemoryResult};
+use crate::history::project::{self, Project};
+use crate::history::truncate;
+
+/// Category holding session summaries
+const SESSIONS_CATEGORY: &str = "sessions";
+
+/// Category whose entries are skipped once tagged `done`
+const TODOS_CATEGORY: &str = "todos";
+
+/// How many recent entries to scan per category before filtering by project
+const SCAN_FACTOR: usize = 10;
+
+/// Longest snippet shown for a single entry
+const SNIPPET_CHARS: usize = 160;
+
+/// Session context handler - injects a memory digest on SessionStart
+pub struct SessionContextHandler {
+    enabled: bool,
+    memory: Arc<dyn MemoryProvider>,
+    config: ContextConfig,
+}
+
+impl SessionContextHandler {
+    pub fn new(enabled: bool, memory: Arc<dyn MemoryProvider>, config: ContextConfig) -> Self {
+        Self {
+            enabled,
+            memory,
+            config,
+        }
+    }
+
+    /// Recent entries in `category` that belong to `project` (or to no project)
+    fn recent_for(&self, category: &str, project: Option<&str>, count: usize) -> Vec<MemoryResult> {
+        let results = match self.memory.get_recent(category, count.saturating_mul(SCAN_FACTOR)) {
+            Ok(results) => results,
+            Err(e) => {
+                log::warn!("Failed to read {} for session context: {}", category, e);
+                return Vec::new();
+            }
+        };
+
+        let mut cache = HashMap::new();
+        results
+            .into_iter()
+            .filter(|r| match (project::entry_project(r, &mut cache), project) {
+                (Some(entry_project), Some(project)) => entry_project == project,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .filter(|r| category != TODOS_CATEGORY || !r.tags().iter().any(|t| t == "done"))
+            .take(count)
+            .collect()
+    }
+
+    /// Build the digest for a session starting in `project`
+    fn digest(&self, project: Option<&str>) -> String {
+        let mut sections = Vec::new();
+
+        if self.config.include_last_session
+            && let Some(project) = project
+            && let Some(last) = self
+                .recent_for(SESSIONS_CATEGORY, Some(project), 1)
+                .into_iter()
+                .find(|r| r.meta_str("cwd").is_some() || r.meta_str(project::PROJECT_KEY).is_some())
+        {
+            let date = last.date().map(|d| d.to_string()).unwrap_or_default();
+            sections.push(format!(
+                "### Last session ({})\n\n{}",
+                date,
+                truncate(last.content.trim(), SNIPPET_CHARS * 4)
+            ));
+        }
+
+        for category in &self.config.categories {
+            let entries = self.recent_for(category, project, self.config.per_category);
+            if entries.is_empty() {
+                continue;
+            }
+
+            let bullets: Vec<String> = entries.iter().map(bullet).collect();
+            sections.push(format!("### {}\n\n{}", capitalize(category), bullets.join("\n")));
+        }
+
+        if sections.is_empty() {
+            return String::new();
+        }
+
+        let digest = format!("## PAII memory\n\n{}", sections.join("\n\n"));
+        truncate(&digest, self.config.budget_chars())
+    }
+}
+
+impl HookHandler for SessionContextHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && event == HookEvent::SessionStart
+    }
+
+    fn handle(&self, _event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let project = payload
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .filter(|c| !c.is_empty())
+            .map(|cwd| Project::detect(Path::new(cwd)).id);
+        let digest = self.digest(project.as_deref());
+
+        if digest.is_empty() {
+            HookResult::Allow
+        } else {
+            log::info!("Injecting {} chars of session context", digest.len());
+            HookResult::Context { context: digest }
+        }
+    }
+}
+
+/// One bullet line: title, plus the first content line when it adds something
+fn bullet(result: &MemoryResult) -> String {
+    let title = result.title();
+    let snippet = result
+        .content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#') && *l != title)
+        .unwrap_or("");
+
+    if snippet.is_empty() {
+        format!("- {}", title)
+    } else {
+        format!("- {} — {}", title, truncate(snippet, SNIPPET_CHARS))
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::provider::FileMemoryProvider;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn capture(memory: &dyn MemoryProvider, category: &str, title: &str, content: &str, cwd: Option<&str>) {
+        let mut metadata = HashMap::from([("title".to_string(), json!(title))]);
+        if let Some(cwd) = cwd {
+            metadata.insert("cwd".to_string(), json!(cwd));
+        }
+        memory.capture(category, content, metadata).unwrap();
+    }
+
+    #[test]
+    fn test_digest_scoped_to_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+
+        capture(
+            memory.as_ref(),
+            "learnings",
+            "Use eyre",
+            "Wrap errors with context",
+            Some("/repo"),
+        );
+        capture(
+            memory.as_ref(),
+            "learnings",
+            "Other repo",
+            "Not relevant",
+            Some("/elsewhere"),
+        );
+        capture(memory.as_ref(), "decisions", "Global", "Applies everywhere", None);
+        capture(
+            memory.as_ref(),
+            "sessions",
+            "Session 1",
+            "Refactored dispatch",
+            Some("/repo"),
+        );
+
+        let handler = SessionContextHandler::new(true, memory, ContextConfig::default());
+        let result = handler.handle(HookEvent::SessionStart, &json!({ "cwd": "/repo" }));
+
+        let HookResult::Context { context } = result else {
+            panic!("expected context");
+        };
+        assert!(context.contains("### Last session"));
+        assert!(context.contains("Refactored dispatch"));
+        assert!(context.contains("- Use eyre — Wrap errors with context"));
+        assert!(context.contains("- Global"));
+        assert!(!context.contains("Other repo"));
+    }
+
+    #[test]
+    fn test_digest_respects_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+        for i in 0..5 {
+            capture(
+                memory.as_ref(),
+                "learnings",
+                &format!("Learning {}", i),
+                &"x".repeat(200),
+                None,
+            );
+        }
+
+        let config = ContextConfig {
+            max_chars: Some(120),
+            ..ContextConfig::default()
+        };
+        let handler = SessionContextHandler::new(true, memory, config);
+        let HookResult::Context { context } = handler.handle(HookEvent::SessionStart, &json!({})) else {
+            panic!("expected context");
+        };
+        assert_eq!(context.chars().count(), 120);
+        assert!(context.ends_with('…'));
+    }
+
+    #[test]
+    fn test_empty_memory_allows() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+        let handler = SessionContextHandler::new(true, memory, ContextConfig::default());
+        assert!(matches!(
+            handler.handle(HookEvent::SessionStart, &json!({ "cwd": "/repo" })),
+            HookResult::Allow
+        ));
+        assert!(!handler.handles(HookEvent::Stop));
+    }
+}
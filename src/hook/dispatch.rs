@@ -2,13 +2,18 @@
 
 use super::{HookEvent, HookHandler, HookResult};
 
-/// Dispatch a hook event to all registered handlers
+/// Dispatch a hook event to all registered handlers.
+///
+/// The first Block wins. Context from every handler is merged, in handler
+/// order, into a single `HookResult::Context`.
 pub fn dispatch(event: HookEvent, payload: &serde_json::Value, handlers: &[Box<dyn HookHandler>]) -> HookResult {
+    let mut contexts = Vec::new();
+
     for handler in handlers {
         if handler.handles(event) {
             let result = handler.handle(event, payload);
-            match &result {
-                HookResult::Block { message } => {
+            match result {
+                HookResult::Block { ref message } => {
                     log::info!("Hook blocked: {}", message);
                     return result;
                 }
@@ -16,6 +21,9 @@ pub fn dispatch(event: HookEvent, payload: &serde_json::Value, handlers: &[Box<d
                     log::error!("Hook error: {}", message);
                     // Continue to next handler
                 }
+                HookResult::Context { context } => {
+                    contexts.push(context);
+                }
                 HookResult::Allow => {
                     // Continue to next handler
                 }
@@ -23,5 +31,61 @@ pub fn dispatch(event: HookEvent, payload: &serde_json::Value, handlers: &[Box<d
         }
     }
 
-    HookResult::Allow
+    if contexts.is_empty() {
+        HookResult::Allow
+    } else {
+        HookResult::Context {
+            context: contexts.join("\n\n"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(HookResult);
+
+    impl HookHandler for Fixed {
+        fn handles(&self, _event: HookEvent) -> bool {
+            true
+        }
+
+        fn handle(&self, _event: HookEvent, _payload: &serde_json::Value) -> HookResult {
+            self.0.clone()
+        }
+    }
+
+    fn context(text: &str) -> Box<dyn HookHandler> {
+        Box::new(Fixed(HookResult::Context {
+            context: text.to_string(),
+        }))
+    }
+
+    #[test]
+    fn test_dispatch_merges_context() {
+        let handlers = vec![
+            context("one"),
+            Box::new(Fixed(HookResult::Allow)) as Box<dyn HookHandler>,
+            context("two"),
+        ];
+        match dispatch(HookEvent::SessionStart, &serde_json::json!({}), &handlers) {
+            HookResult::Context { context } => assert_eq!(context, "one\n\ntwo"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dispatch_block_wins() {
+        let handlers = vec![
+            context("one"),
+            Box::new(Fixed(HookResult::Block {
+                message: "no".to_string(),
+            })) as Box<dyn HookHandler>,
+        ];
+        assert!(matches!(
+            dispatch(HookEvent::PreToolUse, &serde_json::json!({}), &handlers),
+            HookResult::Block { .. }
+        ));
+    }
 }
@@ -1,21 +1,184 @@
 //! History hook handler
 //!
-//! Captures session lifecycle events: SessionStart, Stop, SessionEnd.
+//! Captures session lifecycle events: SessionStart, Stop, SessionEnd, and
+//! SubagentStop (recorded under the parent session). Entries are written
+//! through the active MemoryProvider.
+//!
+//! With a journal attached, prompts (UserPromptSubmit) and tool calls
+//! (PreToolUse/PostToolUse) are appended to the session journal, and the
+//! Stop summary is built from it. SessionEnd prunes expired journals.
 
-use std::path::PathBuf;
+use chrono::Local;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use super::{HookEvent, HookHandler, HookResult};
-use crate::history::{HistoryEntry, HistoryStore};
+use crate::config::RetentionPolicy;
+use crate::contract::memory::MemoryProvider;
+use crate::history::journal::{self, Journal, JournalEvent, JournalSummary};
+use crate::history::project::{PROJECT_KEY, Project};
+use crate::history::truncate;
 
 /// History hook handler - captures session lifecycle data
 pub struct HistoryHandler {
     enabled: bool,
-    history_path: PathBuf,
+    memory: Arc<dyn MemoryProvider>,
+    journal: Option<Journal>,
+    capture_prompts: bool,
+    capture_tools: bool,
+    journal_retention: RetentionPolicy,
 }
 
 impl HistoryHandler {
-    pub fn new(enabled: bool, history_path: PathBuf) -> Self {
-        Self { enabled, history_path }
+    pub fn new(enabled: bool, memory: Arc<dyn MemoryProvider>) -> Self {
+        Self {
+            enabled,
+            memory,
+            journal: None,
+            capture_prompts: false,
+            capture_tools: false,
+            journal_retention: journal::retention(&HashMap::new()),
+        }
+    }
+
+    /// Journal prompts and/or tool calls (both are opt-in)
+    pub fn with_journal(mut self, journal: Journal, capture_prompts: bool, capture_tools: bool) -> Self {
+        self.journal = Some(journal);
+        self.capture_prompts = capture_prompts;
+        self.capture_tools = capture_tools;
+        self
+    }
+
+    /// Policy for pruning journals at SessionEnd
+    pub fn with_journal_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.journal_retention = policy;
+        self
+    }
+
+    fn journal_append(&self, payload: &serde_json::Value, event: JournalEvent) -> HookResult {
+        let Some(journal) = &self.journal else {
+            return HookResult::Allow;
+        };
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+        match journal.append(session_id, event) {
+            Ok(()) => HookResult::Allow,
+            Err(e) => HookResult::Error {
+                message: format!("Failed to journal event: {}", e),
+            },
+        }
+    }
+
+    fn on_prompt(&self, payload: &serde_json::Value) -> HookResult {
+        let prompt = payload.get("prompt").and_then(|v| v.as_str()).unwrap_or("");
+        self.journal_append(
+            payload,
+            JournalEvent::Prompt {
+                prompt: truncate(prompt, 2000),
+            },
+        )
+    }
+
+    fn on_pre_tool_use(&self, payload: &serde_json::Value) -> HookResult {
+        let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let tool_use_id = payload.get("tool_use_id").and_then(|v| v.as_str());
+        self.journal_append(
+            payload,
+            JournalEvent::ToolStart {
+                tool: tool.to_string(),
+                tool_use_id: tool_use_id.map(str::to_string),
+            },
+        )
+    }
+
+    fn on_post_tool_use(&self, payload: &serde_json::Value) -> HookResult {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let tool_use_id = payload.get("tool_use_id").and_then(|v| v.as_str());
+        let input = payload.get("tool_input").cloned().unwrap_or_default();
+        let response = payload.get("tool_response").cloned().unwrap_or_default();
+
+        let (success, error) = journal::tool_outcome(&response);
+        let duration_ms = self
+            .journal
+            .as_ref()
+            .and_then(|j| j.started_at(session_id, tool, tool_use_id))
+            .and_then(|start| (Local::now() - start).num_milliseconds().try_into().ok());
+
+        self.journal_append(
+            payload,
+            JournalEvent::Tool {
+                tool: tool.to_string(),
+                input: journal::summarize_input(tool, &input),
+                success,
+                error,
+                duration_ms,
+                tool_use_id: tool_use_id.map(str::to_string),
+            },
+        )
+    }
+
+    fn on_subagent_stop(&self, payload: &serde_json::Value) -> HookResult {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let agent_type = payload
+            .get("agent_type")
+            .or_else(|| payload.get("subagent_type"))
+            .and_then(|v| v.as_str());
+        let outcome = if payload.get("error").is_some_and(|e| !e.is_null()) {
+            "error"
+        } else {
+            payload
+                .get("outcome")
+                .or_else(|| payload.get("stop_reason"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("completed")
+        };
+
+        // Subagents are launched by the Task tool; time them from its start
+        let duration_ms: Option<u64> = self
+            .journal
+            .as_ref()
+            .and_then(|j| j.started_at(session_id, "Task", None))
+            .and_then(|start| (Local::now() - start).num_milliseconds().try_into().ok());
+
+        let agent = agent_type.unwrap_or("subagent");
+        let mut content = format!("Subagent `{}` finished: {}", agent, outcome);
+        if let Some(ms) = duration_ms {
+            content.push_str(&format!(" after {:.1}s", ms as f64 / 1000.0));
+        }
+
+        let mut metadata = HashMap::from([
+            ("title".to_string(), json!(format!("Subagent {} {}", agent, outcome))),
+            ("tags".to_string(), json!(["subagent", agent, outcome])),
+            ("session_id".to_string(), json!(session_id)),
+            ("outcome".to_string(), json!(outcome)),
+        ]);
+        if let Some(ms) = duration_ms {
+            metadata.insert("duration_ms".to_string(), json!(ms.to_string()));
+        }
+        add_location(&mut metadata, payload);
+
+        if let Err(e) = self.memory.capture("events", &content, metadata) {
+            log::error!("Failed to log subagent stop: {}", e);
+        }
+
+        // Low volume, so journaled whenever a journal is attached
+        self.journal_append(
+            payload,
+            JournalEvent::Subagent {
+                agent_type: agent_type.map(str::to_string),
+                outcome: outcome.to_string(),
+                duration_ms,
+            },
+        )
+    }
+
+    /// Summary of the session journal, if there is one
+    fn journal_summary(&self, session_id: &str) -> Option<JournalSummary> {
+        let records = self.journal.as_ref()?.read(session_id).ok()?;
+        let summary = JournalSummary::from_records(&records);
+        (!summary.is_empty()).then_some(summary)
     }
 
     fn on_session_start(&self, payload: &serde_json::Value) -> HookResult {
@@ -44,14 +207,14 @@ impl HistoryHandler {
         );
 
         let title = format!("Session {} started", &session_id[..8.min(session_id.len())]);
-        let entry = HistoryEntry::new("events", &title, &content)
-            .with_tag("session_start")
-            .with_tag(session_type)
-            .with_metadata("session_id", session_id)
-            .with_metadata("cwd", cwd);
-
-        let store = HistoryStore::new(self.history_path.clone());
-        if let Err(e) = store.store(&entry) {
+        let mut metadata = HashMap::from([
+            ("title".to_string(), json!(title)),
+            ("tags".to_string(), json!(["session_start", session_type])),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        add_location(&mut metadata, payload);
+
+        if let Err(e) = self.memory.capture("events", &content, metadata) {
             log::error!("Failed to log session start: {}", e);
         }
 
@@ -67,18 +230,20 @@ impl HistoryHandler {
             .unwrap_or("completed");
 
         // Build summary from available info
-        let summary = build_session_summary(payload);
+        let summary = build_session_summary(payload, self.journal_summary(session_id).as_ref());
 
         // Create history entry
         let title = format!("Session {}", &session_id[..8.min(session_id.len())]);
-        let entry = HistoryEntry::new("sessions", &title, &summary)
-            .with_tag(stop_reason)
-            .with_metadata("session_id", session_id);
-
-        let store = HistoryStore::new(self.history_path.clone());
-        match store.store(&entry) {
+        let mut metadata = HashMap::from([
+            ("title".to_string(), json!(title)),
+            ("tags".to_string(), json!([stop_reason])),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        add_location(&mut metadata, payload);
+
+        match self.memory.capture("sessions", &summary, metadata) {
             Ok(path) => {
-                log::info!("Captured session to: {}", path.display());
+                log::info!("Captured session to: {}", path);
                 HookResult::Allow
             }
             Err(e) => {
@@ -97,22 +262,38 @@ impl HistoryHandler {
 
         // Create an event entry for session end
         let title = format!("Session {} ended", &session_id[..8.min(session_id.len())]);
-        let entry = HistoryEntry::new("events", &title, "Session completed.")
-            .with_tag("session_end")
-            .with_metadata("session_id", session_id);
-
-        let store = HistoryStore::new(self.history_path.clone());
-        if let Err(e) = store.store(&entry) {
+        let mut metadata = HashMap::from([
+            ("title".to_string(), json!(title)),
+            ("tags".to_string(), json!(["session_end"])),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        add_location(&mut metadata, payload);
+
+        if let Err(e) = self.memory.capture("events", "Session completed.", metadata) {
             log::error!("Failed to log session end: {}", e);
         }
 
+        if let Some(journal) = &self.journal {
+            match journal.prune(&self.journal_retention) {
+                Ok(0) => {}
+                Ok(removed) => log::info!("Pruned {} expired journal(s)", removed),
+                Err(e) => log::warn!("Failed to prune journals: {}", e),
+            }
+        }
+
         HookResult::Allow
     }
 }
 
 impl HookHandler for HistoryHandler {
     fn handles(&self, event: HookEvent) -> bool {
-        self.enabled && matches!(event, HookEvent::SessionStart | HookEvent::Stop | HookEvent::SessionEnd)
+        self.enabled
+            && match event {
+                HookEvent::SessionStart | HookEvent::Stop | HookEvent::SessionEnd | HookEvent::SubagentStop => true,
+                HookEvent::UserPromptSubmit => self.journal.is_some() && self.capture_prompts,
+                HookEvent::PreToolUse | HookEvent::PostToolUse => self.journal.is_some() && self.capture_tools,
+                _ => false,
+            }
     }
 
     fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
@@ -120,13 +301,26 @@ impl HookHandler for HistoryHandler {
             HookEvent::SessionStart => self.on_session_start(payload),
             HookEvent::Stop => self.on_stop(payload),
             HookEvent::SessionEnd => self.on_session_end(payload),
+            HookEvent::SubagentStop => self.on_subagent_stop(payload),
+            HookEvent::UserPromptSubmit => self.on_prompt(payload),
+            HookEvent::PreToolUse => self.on_pre_tool_use(payload),
+            HookEvent::PostToolUse => self.on_post_tool_use(payload),
             _ => HookResult::Allow,
         }
     }
 }
 
-/// Build a session summary from the Stop payload
-fn build_session_summary(payload: &serde_json::Value) -> String {
+/// Record the payload's `cwd` and the project it belongs to
+fn add_location(metadata: &mut HashMap<String, serde_json::Value>, payload: &serde_json::Value) {
+    if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        metadata.insert("cwd".to_string(), json!(cwd));
+        let project = Project::detect(std::path::Path::new(cwd));
+        metadata.insert(PROJECT_KEY.to_string(), json!(project.id));
+    }
+}
+
+/// Build a session summary from the Stop payload and the session journal
+fn build_session_summary(payload: &serde_json::Value, journal: Option<&JournalSummary>) -> String {
     let mut summary = String::new();
 
     // Extract what we can from the payload
@@ -149,8 +343,10 @@ fn build_session_summary(payload: &serde_json::Value) -> String {
         summary.push_str("\n\n");
     }
 
-    // If we got tools used
-    if let Some(tools) = payload.get("tools_used").and_then(|v| v.as_array())
+    // Prefer the journal's record of prompts and tool calls
+    if let Some(journal) = journal {
+        summary.push_str(&journal.to_markdown());
+    } else if let Some(tools) = payload.get("tools_used").and_then(|v| v.as_array())
         && !tools.is_empty()
     {
         summary.push_str("## Tools Used\n\n");
@@ -173,7 +369,88 @@ fn build_session_summary(payload: &serde_json::Value) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use crate::history::provider::FileMemoryProvider;
+
+    #[test]
+    fn test_stop_captures_through_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+        let handler = HistoryHandler::new(true, memory.clone());
+
+        let payload = json!({ "session_id": "abcdef1234", "stop_reason": "end_turn", "cwd": "/nonexistent/repo" });
+        assert!(matches!(handler.handle(HookEvent::Stop, &payload), HookResult::Allow));
+
+        let sessions = memory.get_recent("sessions", 5).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].title(), "Session abcdef12");
+        assert_eq!(sessions[0].tags(), vec!["end_turn"]);
+        assert_eq!(sessions[0].meta_str("session_id"), Some("abcdef1234"));
+        assert_eq!(sessions[0].meta_str("project"), Some("/nonexistent/repo"));
+    }
+
+    #[test]
+    fn test_journal_feeds_stop_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+        let handler = HistoryHandler::new(true, memory.clone()).with_journal(Journal::new(dir.path()), true, true);
+
+        assert!(handler.handles(HookEvent::UserPromptSubmit));
+        handler.handle(
+            HookEvent::UserPromptSubmit,
+            &json!({ "session_id": "s1", "prompt": "run the tests" }),
+        );
+        handler.handle(
+            HookEvent::PreToolUse,
+            &json!({ "session_id": "s1", "tool_name": "Bash", "tool_input": { "command": "cargo test" } }),
+        );
+        handler.handle(
+            HookEvent::PostToolUse,
+            &json!({
+                "session_id": "s1",
+                "tool_name": "Bash",
+                "tool_input": { "command": "cargo test" },
+                "tool_response": { "stdout": "ok", "exit_code": 0 }
+            }),
+        );
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+
+        let sessions = memory.get_recent("sessions", 1).unwrap();
+        assert!(sessions[0].content.contains("- run the tests"));
+        assert!(sessions[0].content.contains("- Bash ×1"));
+        // The journal directory is not a history category
+        assert_eq!(memory.list_categories(), vec!["sessions"]);
+    }
+
+    #[test]
+    fn test_subagent_recorded_under_parent_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new(dir.path().to_path_buf()));
+        let handler = HistoryHandler::new(true, memory.clone()).with_journal(Journal::new(dir.path()), false, false);
+
+        handler.handle(
+            HookEvent::SubagentStop,
+            &json!({ "session_id": "parent", "agent_type": "code-reviewer" }),
+        );
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "parent" }));
+
+        let events = memory.get_recent("events", 5).unwrap();
+        assert_eq!(events[0].title(), "Subagent code-reviewer completed");
+        assert_eq!(events[0].meta_str("session_id"), Some("parent"));
+
+        let sessions = memory.get_recent("sessions", 1).unwrap();
+        assert!(sessions[0].content.contains("- code-reviewer: completed"));
+    }
+
+    #[test]
+    fn test_journal_is_opt_in() {
+        let memory: Arc<dyn MemoryProvider> = Arc::new(FileMemoryProvider::new("/nonexistent".into()));
+        let handler = HistoryHandler::new(true, memory.clone());
+        assert!(!handler.handles(HookEvent::PostToolUse));
+        let handler =
+            HistoryHandler::new(true, memory).with_journal(Journal::new("/nonexistent".as_ref()), true, false);
+        assert!(handler.handles(HookEvent::UserPromptSubmit));
+        assert!(!handler.handles(HookEvent::PostToolUse));
+    }
 
     #[test]
     fn test_build_session_summary() {
@@ -182,7 +459,7 @@ mod tests {
             "tools_used": ["Bash", "Edit"]
         });
 
-        let summary = build_session_summary(&payload);
+        let summary = build_session_summary(&payload, None);
         assert!(summary.contains("user_request"));
         assert!(summary.contains("Bash"));
         assert!(summary.contains("Edit"));
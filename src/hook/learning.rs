@@ -0,0 +1,658 @@
+//! Error and learning extraction hook
+//!
+//! On PostToolUse, failing tool calls (nonzero Bash exits, failed edits) are
+//! filed under `errors/`, one entry per distinct failure: a repeat of the
+//! same command bumps the entry's `occurrences` instead of adding a new one.
+//!
+//! On Stop, the session's tool calls are scanned for error→fix sequences: a
+//! failure that was later retried successfully. Repeated failures that got
+//! fixed become `learnings/` candidates, tagged `candidate` for review. The
+//! calls come from the session journal (see `history::journal`) when
+//! `capture_tools` records them there. Otherwise this handler keeps its own
+//! journal under `<history>/.learning/` holding only failures and the calls
+//! that retried them, removed at SessionEnd.
+//!
+//! Both categories go through the MemoryProvider, which updates repeated
+//! errors in place. With the file provider, entries compacted into monthly
+//! archives still count: an archived error is updated inside its archive,
+//! and an archived learning is not stored again.
+
+use chrono::Local;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::contract::memory::{MemoryProvider, MemoryResult};
+use crate::history::journal::{self, Journal, JournalEvent, JournalRecord};
+use crate::history::project::{PROJECT_KEY, Project};
+use crate::history::truncate;
+
+const ERRORS_CATEGORY: &str = "errors";
+const LEARNINGS_CATEGORY: &str = "learnings";
+
+/// Tag marking auto-extracted learnings awaiting review
+pub const CANDIDATE_TAG: &str = "candidate";
+
+/// Directory (under the history root) of this handler's own journals
+const FAILURES_DIR: &str = ".learning";
+
+/// Metadata key identifying a distinct failure
+const SIGNATURE_KEY: &str = "signature";
+
+/// Most intermediate steps listed in a learning candidate
+const MAX_FIX_STEPS: usize = 10;
+
+/// Error/learning extraction handler
+pub struct LearningHandler {
+    enabled: bool,
+    memory: Arc<dyn MemoryProvider>,
+    journal: Journal,
+    /// Whether the session journal records tool calls
+    journaled: bool,
+    /// Failures and their retries, kept when the session journal has no tool calls
+    failures: Journal,
+}
+
+impl LearningHandler {
+    /// Journals live under `history_dir`, whichever provider stores entries
+    pub fn new(enabled: bool, memory: Arc<dyn MemoryProvider>, history_dir: &Path) -> Self {
+        Self {
+            enabled,
+            memory,
+            journal: Journal::new(history_dir),
+            journaled: false,
+            failures: Journal::in_dir(history_dir.join(FAILURES_DIR)),
+        }
+    }
+
+    /// Read tool calls from the session journal, which records them
+    /// (`capture_tools`), instead of keeping failures separately
+    pub fn with_journaled_tools(mut self, journaled: bool) -> Self {
+        self.journaled = journaled;
+        self
+    }
+
+    fn on_post_tool_use(&self, payload: &serde_json::Value) -> HookResult {
+        let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let input = payload.get("tool_input").cloned().unwrap_or_default();
+        let response = payload.get("tool_response").cloned().unwrap_or_default();
+
+        let (success, error) = journal::tool_outcome(&response);
+        let input = journal::summarize_input(tool, &input);
+        if !self.journaled {
+            let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+            if let Err(e) = self.track(session_id, tool, &input, success, error.clone()) {
+                log::warn!("Failed to track tool failure: {}", e);
+            }
+        }
+        if success {
+            return HookResult::Allow;
+        }
+
+        match self.record_error(tool, &input, error.as_deref().unwrap_or("failed"), payload) {
+            Ok(count) => {
+                log::info!("Recorded {} failure ({} occurrences): {}", tool, count, input);
+                HookResult::Allow
+            }
+            Err(e) => HookResult::Error {
+                message: format!("Failed to record error: {}", e),
+            },
+        }
+    }
+
+    /// Keep what Stop needs to find fixes without the session journal: every
+    /// failure, and a success only when it retries one
+    fn track(
+        &self,
+        session_id: &str,
+        tool: &str,
+        input: &str,
+        success: bool,
+        error: Option<String>,
+    ) -> eyre::Result<()> {
+        if success {
+            let wanted = signature(tool, input);
+            let retried = self.failures.read(session_id)?.iter().any(|r| match &r.event {
+                JournalEvent::Tool {
+                    tool,
+                    input,
+                    success: false,
+                    ..
+                } => signature(tool, input) == wanted,
+                _ => false,
+            });
+            if !retried {
+                return Ok(());
+            }
+        }
+        self.failures.append(
+            session_id,
+            JournalEvent::Tool {
+                tool: tool.to_string(),
+                input: input.to_string(),
+                success,
+                error,
+                duration_ms: None,
+                tool_use_id: None,
+            },
+        )
+    }
+
+    /// File a failure under `errors/`, returning its occurrence count
+    fn record_error(&self, tool: &str, input: &str, error: &str, payload: &serde_json::Value) -> eyre::Result<u32> {
+        let signature = signature(tool, input);
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let now = Local::now().to_rfc3339();
+
+        if let Some(mut entry) = self.find(ERRORS_CATEGORY, &signature)? {
+            let count = occurrences(&entry) + 1;
+            entry
+                .metadata
+                .insert("occurrences".to_string(), json!(count.to_string()));
+            entry.metadata.insert("last_seen".to_string(), json!(now));
+            entry.metadata.insert("session_id".to_string(), json!(session_id));
+            entry.content = error_content(tool, input, error);
+            self.memory.update(&entry)?;
+            return Ok(count);
+        }
+
+        let mut metadata = HashMap::from([
+            (
+                "title".to_string(),
+                json!(single_line(&format!("{}: {}", tool, input), 100)),
+            ),
+            ("tags".to_string(), json!(["error", tool.to_lowercase()])),
+            (SIGNATURE_KEY.to_string(), json!(signature)),
+            ("tool".to_string(), json!(tool)),
+            ("occurrences".to_string(), json!("1")),
+            ("last_seen".to_string(), json!(now)),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        insert_project(&mut metadata, payload);
+
+        self.memory
+            .capture(ERRORS_CATEGORY, &error_content(tool, input, error), metadata)?;
+        Ok(1)
+    }
+
+    /// Existing entry in `category` with the given signature
+    fn find(&self, category: &str, signature: &str) -> eyre::Result<Option<MemoryResult>> {
+        Ok(self
+            .memory
+            .query(category, "", usize::MAX)?
+            .into_iter()
+            .find(|e| e.meta_str(SIGNATURE_KEY) == Some(signature)))
+    }
+
+    fn on_stop(&self, payload: &serde_json::Value) -> HookResult {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+        let journal = if self.journaled { &self.journal } else { &self.failures };
+        let records = match journal.read(session_id) {
+            Ok(records) => records,
+            Err(e) => {
+                return HookResult::Error {
+                    message: format!("Failed to read session journal: {}", e),
+                };
+            }
+        };
+
+        let mut promoted = 0;
+        for fix in find_fixes(&records) {
+            // Only failures seen more than once are worth a learning
+            let total = match self.find(ERRORS_CATEGORY, &fix.signature) {
+                Ok(Some(entry)) => occurrences(&entry).max(fix.failures),
+                _ => fix.failures,
+            };
+            if total < 2 {
+                continue;
+            }
+
+            match self.promote(&fix, total, payload) {
+                Ok(true) => promoted += 1,
+                Ok(false) => {}
+                Err(e) => log::error!("Failed to store learning candidate: {}", e),
+            }
+        }
+
+        if promoted > 0 {
+            log::info!("Promoted {} error→fix sequences to learning candidates", promoted);
+        }
+        HookResult::Allow
+    }
+
+    /// Drop the session's failures, and those of sessions that never ended
+    fn on_session_end(&self, payload: &serde_json::Value) -> HookResult {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let path = self.failures.path(session_id);
+        if path.exists()
+            && let Err(e) = fs::remove_file(&path)
+        {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+        if let Err(e) = self.failures.prune(&journal::retention(&HashMap::new())) {
+            log::warn!("Failed to prune learning journals: {}", e);
+        }
+        HookResult::Allow
+    }
+
+    /// Store a learning candidate unless one already exists for the failure
+    fn promote(&self, fix: &Fix, occurrences: u32, payload: &serde_json::Value) -> eyre::Result<bool> {
+        if self.find(LEARNINGS_CATEGORY, &fix.signature)?.is_some() {
+            return Ok(false);
+        }
+
+        let mut content = format!(
+            "`{}` failed {} times before succeeding.\n\n## Error\n\n```\n{}\n```\n",
+            fix.input, occurrences, fix.error
+        );
+        if !fix.steps.is_empty() {
+            content.push_str("\n## Fix\n\n");
+            for step in &fix.steps {
+                content.push_str(&format!("- {}\n", step));
+            }
+        }
+
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let mut metadata = HashMap::from([
+            (
+                "title".to_string(),
+                json!(single_line(&format!("Fix for {}: {}", fix.tool, fix.input), 100)),
+            ),
+            ("tags".to_string(), json!([CANDIDATE_TAG, fix.tool.to_lowercase()])),
+            (SIGNATURE_KEY.to_string(), json!(fix.signature)),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        insert_project(&mut metadata, payload);
+
+        self.memory.capture(LEARNINGS_CATEGORY, &content, metadata)?;
+        Ok(true)
+    }
+}
+
+impl HookHandler for LearningHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled
+            && match event {
+                HookEvent::PostToolUse | HookEvent::Stop => true,
+                HookEvent::SessionEnd => !self.journaled,
+                _ => false,
+            }
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        match event {
+            HookEvent::PostToolUse => self.on_post_tool_use(payload),
+            HookEvent::Stop => self.on_stop(payload),
+            HookEvent::SessionEnd => self.on_session_end(payload),
+            _ => HookResult::Allow,
+        }
+    }
+}
+
+/// A failure followed, later in the session, by a successful retry
+#[derive(Debug, PartialEq)]
+struct Fix {
+    signature: String,
+    tool: String,
+    input: String,
+    error: String,
+    /// Failures of this call in the session before it succeeded
+    failures: u32,
+    /// What happened between the last failure and the success
+    steps: Vec<String>,
+}
+
+/// Find error→fix sequences in a session journal
+fn find_fixes(records: &[JournalRecord]) -> Vec<Fix> {
+    // (signature, tool, input, success, error)
+    let calls: Vec<(String, &str, &str, bool, Option<&str>)> = records
+        .iter()
+        .filter_map(|r| match &r.event {
+            JournalEvent::Tool {
+                tool,
+                input,
+                success,
+                error,
+                ..
+            } => Some((
+                signature(tool, input),
+                tool.as_str(),
+                input.as_str(),
+                *success,
+                error.as_deref(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    let mut fixes: Vec<Fix> = Vec::new();
+
+    for (i, (sig, tool, input, success, _)) in calls.iter().enumerate() {
+        if !success || fixes.iter().any(|f| &f.signature == sig) {
+            continue;
+        }
+
+        // Earlier failures of the same call, back to its previous success
+        let failures: Vec<usize> = (0..i)
+            .rev()
+            .take_while(|&j| !(calls[j].0 == *sig && calls[j].3))
+            .filter(|&j| calls[j].0 == *sig)
+            .collect();
+        let Some(&last_failure) = failures.first() else {
+            continue;
+        };
+
+        let steps = calls[last_failure + 1..i]
+            .iter()
+            .filter(|(s, _, _, ok, _)| *ok && s != sig)
+            .take(MAX_FIX_STEPS)
+            .map(|(_, t, inp, _, _)| format!("{}: `{}`", t, single_line(inp, 120)))
+            .collect();
+
+        fixes.push(Fix {
+            signature: sig.clone(),
+            tool: tool.to_string(),
+            input: input.to_string(),
+            error: calls[last_failure].4.unwrap_or("failed").to_string(),
+            failures: failures.len() as u32,
+            steps,
+        });
+    }
+
+    fixes
+}
+
+/// Identity of a failing call: tool plus whitespace-normalized input
+fn signature(tool: &str, input: &str) -> String {
+    format!("{}:{}", tool, input.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn occurrences(entry: &MemoryResult) -> u32 {
+    entry.meta_str("occurrences").and_then(|v| v.parse().ok()).unwrap_or(1)
+}
+
+/// Record the session's working directory and project
+fn insert_project(metadata: &mut HashMap<String, serde_json::Value>, payload: &serde_json::Value) {
+    if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        metadata.insert("cwd".to_string(), json!(cwd));
+        metadata.insert(PROJECT_KEY.to_string(), json!(Project::detect(Path::new(cwd)).id));
+    }
+}
+
+fn error_content(tool: &str, input: &str, error: &str) -> String {
+    format!(
+        "## {}\n\n```\n{}\n```\n\n## Error\n\n```\n{}\n```",
+        if tool == "Bash" { "Command" } else { "Input" },
+        input,
+        error
+    )
+}
+
+/// Collapse to one line for titles and frontmatter values
+fn single_line(s: &str, max: usize) -> String {
+    truncate(&s.split_whitespace().collect::<Vec<_>>().join(" "), max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetentionPolicy;
+    use crate::history::provider::FileMemoryProvider;
+    use crate::history::{HistoryFilter, HistoryStore, retention};
+
+    fn handler(dir: &Path) -> LearningHandler {
+        LearningHandler::new(true, Arc::new(FileMemoryProvider::new(dir.to_path_buf())), dir)
+    }
+
+    fn tool(tool: &str, input: &str, success: bool) -> JournalRecord {
+        JournalRecord {
+            at: Local::now(),
+            event: JournalEvent::Tool {
+                tool: tool.into(),
+                input: input.into(),
+                success,
+                error: (!success).then(|| "exit 101: error[E0425]".to_string()),
+                duration_ms: None,
+                tool_use_id: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_find_fixes() {
+        let records = vec![
+            tool("Bash", "cargo build", false),
+            tool("Read", "src/main.rs", true),
+            tool("Bash", "cargo build", false),
+            tool("Edit", "src/main.rs", true),
+            tool("Bash", "cargo  build", true),
+            tool("Bash", "ls", true),
+        ];
+        let fixes = find_fixes(&records);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].failures, 2);
+        assert_eq!(fixes[0].steps, vec!["Edit: `src/main.rs`"]);
+        assert_eq!(fixes[0].error, "exit 101: error[E0425]");
+
+        // Never fixed
+        assert!(find_fixes(&[tool("Bash", "make", false)]).is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_deduplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        let handler = handler(dir.path());
+
+        let payload = json!({
+            "session_id": "s1",
+            "tool_name": "Bash",
+            "tool_input": { "command": "cargo build" },
+            "tool_response": { "stderr": "error[E0425]", "exit_code": 101 }
+        });
+        handler.handle(HookEvent::PostToolUse, &payload);
+        handler.handle(HookEvent::PostToolUse, &payload);
+
+        let errors = store.live_entries(ERRORS_CATEGORY).unwrap();
+        assert_eq!(errors.len(), 1);
+        let entry = &errors[0].1;
+        assert_eq!(entry.metadata["occurrences"], "2");
+        assert!(entry.content.contains("cargo build"));
+        assert!(entry.content.contains("error[E0425]"));
+
+        // Successful calls are not recorded
+        handler.handle(
+            HookEvent::PostToolUse,
+            &json!({ "tool_name": "Bash", "tool_input": { "command": "ls" }, "tool_response": { "stdout": "" } }),
+        );
+        assert_eq!(store.live_entries(ERRORS_CATEGORY).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_stop_promotes_repeated_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        let journal = Journal::new(dir.path());
+        for record in [
+            tool("Bash", "cargo build", false),
+            tool("Bash", "cargo build", false),
+            tool("Edit", "src/main.rs", true),
+            tool("Bash", "cargo build", true),
+        ] {
+            journal.append("s1", record.event).unwrap();
+        }
+
+        let handler = handler(dir.path()).with_journaled_tools(true);
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+
+        let learnings = store.live_entries(LEARNINGS_CATEGORY).unwrap();
+        assert_eq!(learnings.len(), 1);
+        let entry = &learnings[0].1;
+        assert_eq!(entry.title, "Fix for Bash: cargo build");
+        assert!(entry.tags.contains(&CANDIDATE_TAG.to_string()));
+        assert!(entry.content.contains("- Edit: `src/main.rs`"));
+    }
+
+    #[test]
+    fn test_fixes_without_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        let handler = handler(dir.path());
+
+        let call = |command: &str, exit_code: i32| {
+            json!({
+                "session_id": "s1",
+                "tool_name": "Bash",
+                "tool_input": { "command": command },
+                "tool_response": { "stderr": "error[E0425]", "exit_code": exit_code }
+            })
+        };
+        for payload in [
+            call("cargo build", 101),
+            call("ls", 0),
+            call("cargo build", 101),
+            call("cargo build", 0),
+        ] {
+            handler.handle(HookEvent::PostToolUse, &payload);
+        }
+
+        // Only the failures and their retry are kept, outside the session journal
+        assert!(!dir.path().join(journal::JOURNAL_DIR).exists());
+        assert_eq!(handler.failures.read("s1").unwrap().len(), 3);
+
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+        let learnings = store.live_entries(LEARNINGS_CATEGORY).unwrap();
+        assert_eq!(learnings.len(), 1);
+        assert_eq!(learnings[0].1.title, "Fix for Bash: cargo build");
+
+        handler.handle(HookEvent::SessionEnd, &json!({ "session_id": "s1" }));
+        assert!(!handler.failures.path("s1").exists());
+    }
+
+    /// Keeps entries in memory, like a plugin provider with its own storage
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<MemoryResult>>);
+
+    impl MemoryProvider for Recorder {
+        fn capture(
+            &self,
+            category: &str,
+            content: &str,
+            mut metadata: HashMap<String, serde_json::Value>,
+        ) -> eyre::Result<String> {
+            let mut entries = self.0.lock().unwrap();
+            let id = entries.len().to_string();
+            metadata.insert("id".to_string(), json!(id));
+            entries.push(MemoryResult {
+                path: id.clone(),
+                category: category.to_string(),
+                timestamp: Local::now().to_rfc3339(),
+                content: content.to_string(),
+                metadata,
+            });
+            Ok(id)
+        }
+
+        fn query(&self, category: &str, _query: &str, _limit: usize) -> eyre::Result<Vec<MemoryResult>> {
+            let entries = self.0.lock().unwrap();
+            Ok(entries.iter().filter(|e| e.category == category).cloned().collect())
+        }
+
+        fn list_categories(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn get_recent(&self, category: &str, count: usize) -> eyre::Result<Vec<MemoryResult>> {
+            self.query(category, "", count)
+        }
+
+        fn update(&self, entry: &MemoryResult) -> eyre::Result<()> {
+            let mut entries = self.0.lock().unwrap();
+            let stored = entries.iter_mut().find(|e| e.path == entry.path).unwrap();
+            *stored = entry.clone();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_entries_go_through_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = Arc::new(Recorder::default());
+        let handler = LearningHandler::new(true, memory.clone(), dir.path());
+
+        let call = |exit_code: i32| {
+            json!({
+                "session_id": "s1",
+                "tool_name": "Bash",
+                "tool_input": { "command": "cargo build" },
+                "tool_response": { "stderr": "error[E0425]", "exit_code": exit_code }
+            })
+        };
+        for payload in [call(101), call(101), call(0)] {
+            handler.handle(HookEvent::PostToolUse, &payload);
+        }
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+
+        let errors = memory.query(ERRORS_CATEGORY, "", 10).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].meta_str("occurrences"), Some("2"));
+        let learnings = memory.query(LEARNINGS_CATEGORY, "", 10).unwrap();
+        assert_eq!(learnings.len(), 1);
+        assert_eq!(learnings[0].title(), "Fix for Bash: cargo build");
+        assert!(!dir.path().join(ERRORS_CATEGORY).exists());
+    }
+
+    #[test]
+    fn test_archived_entries_are_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().to_path_buf());
+        let journal = Journal::new(dir.path());
+        for record in [
+            tool("Bash", "cargo build", false),
+            tool("Bash", "cargo build", false),
+            tool("Bash", "cargo build", true),
+        ] {
+            journal.append("s1", record.event).unwrap();
+        }
+        let handler = handler(dir.path()).with_journaled_tools(true);
+        let failure = json!({
+            "session_id": "s1",
+            "tool_name": "Bash",
+            "tool_input": { "command": "cargo build" },
+            "tool_response": { "stderr": "error[E0425]", "exit_code": 101 }
+        });
+        handler.handle(HookEvent::PostToolUse, &failure);
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+
+        let compact = RetentionPolicy {
+            compact_after_days: Some(0),
+            ..Default::default()
+        };
+        for category in [ERRORS_CATEGORY, LEARNINGS_CATEGORY] {
+            let plan = retention::plan(&store, category, &compact, Local::now()).unwrap();
+            retention::apply(&store, &plan).unwrap();
+            assert!(store.live_entries(category).unwrap().is_empty());
+        }
+
+        handler.handle(HookEvent::PostToolUse, &failure);
+        handler.handle(HookEvent::Stop, &json!({ "session_id": "s1" }));
+
+        let all = |category: &str| {
+            let filter = HistoryFilter {
+                category: Some(category.to_string()),
+                ..Default::default()
+            };
+            store.entries(&filter).unwrap()
+        };
+        let errors = all(ERRORS_CATEGORY);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].metadata["occurrences"], "2");
+        assert_eq!(all(LEARNINGS_CATEGORY).len(), 1);
+        assert!(store.live_entries(ERRORS_CATEGORY).unwrap().is_empty());
+    }
+}
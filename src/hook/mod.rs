@@ -7,9 +7,15 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod compaction;
+pub mod context;
 pub mod dispatch;
 pub mod history;
+pub mod learning;
+pub mod notification;
+pub mod notify;
 pub mod security;
+pub mod skills;
 
 /// Hook event types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -28,6 +34,20 @@ pub enum HookEvent {
 }
 
 impl HookEvent {
+    /// Every event, in Claude Code's documentation order
+    pub const ALL: [HookEvent; 10] = [
+        Self::PreToolUse,
+        Self::PostToolUse,
+        Self::Stop,
+        Self::SessionStart,
+        Self::SessionEnd,
+        Self::SubagentStop,
+        Self::Notification,
+        Self::PermissionRequest,
+        Self::UserPromptSubmit,
+        Self::PreCompact,
+    ];
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().replace(['-', '_'], "").as_str() {
             "pretooluse" => Some(Self::PreToolUse),
@@ -54,6 +74,8 @@ pub enum HookResult {
     Block { message: String },
     /// Error occurred (logged but allows action)
     Error { message: String },
+    /// Allow the action and hand extra context to Claude
+    Context { context: String },
 }
 
 impl HookResult {
@@ -62,6 +84,20 @@ impl HookResult {
             HookResult::Allow => 0,
             HookResult::Block { .. } => 2,
             HookResult::Error { .. } => 0, // Errors don't block
+            HookResult::Context { .. } => 0,
+        }
+    }
+
+    /// JSON printed to stdout for Claude Code, if the result carries any
+    pub fn output(&self, event: HookEvent) -> Option<serde_json::Value> {
+        match self {
+            HookResult::Context { context } => Some(serde_json::json!({
+                "hookSpecificOutput": {
+                    "hookEventName": event,
+                    "additionalContext": context,
+                }
+            })),
+            _ => None,
         }
     }
 }
@@ -136,6 +172,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hook_result_context_output() {
+        let result = HookResult::Context {
+            context: "remember this".to_string(),
+        };
+        assert_eq!(result.exit_code(), 0);
+        let output = result.output(HookEvent::SessionStart).unwrap();
+        assert_eq!(output["hookSpecificOutput"]["hookEventName"], "SessionStart");
+        assert_eq!(output["hookSpecificOutput"]["additionalContext"], "remember this");
+        assert!(HookResult::Allow.output(HookEvent::SessionStart).is_none());
+    }
+
     #[test]
     fn test_hook_event_serialization() {
         let event = HookEvent::PreToolUse;
@@ -0,0 +1,62 @@
+//! Notification hook handler
+//!
+//! Claude Code fires Notification when it needs the user's attention
+//! (a permission prompt, or waiting idle for input). Each one is recorded
+//! in the `events` category under its session.
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::contract::memory::MemoryProvider;
+use crate::history::truncate;
+
+/// Notification handler - records attention requests
+pub struct NotificationHandler {
+    enabled: bool,
+    memory: Arc<dyn MemoryProvider>,
+}
+
+impl NotificationHandler {
+    pub fn new(enabled: bool, memory: Arc<dyn MemoryProvider>) -> Self {
+        Self { enabled, memory }
+    }
+}
+
+impl HookHandler for NotificationHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && event == HookEvent::Notification
+    }
+
+    fn handle(&self, _event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let message = payload
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Claude needs your attention");
+        let kind = payload
+            .get("notification_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("notification");
+
+        let mut metadata = HashMap::from([
+            (
+                "title".to_string(),
+                json!(format!("Notification: {}", truncate(message, 80))),
+            ),
+            ("tags".to_string(), json!(["notification", kind])),
+            ("session_id".to_string(), json!(session_id)),
+        ]);
+        if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()) {
+            metadata.insert("cwd".to_string(), json!(cwd));
+        }
+
+        match self.memory.capture("events", message, metadata) {
+            Ok(_) => HookResult::Allow,
+            Err(e) => HookResult::Error {
+                message: format!("Failed to record notification: {}", e),
+            },
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Notify hook handler
+//!
+//! Sends a notification for every hook event routed to a channel in
+//! `[notifications.events]`. Delivery never affects the hook result.
+
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::contract::notification::{Notification, Urgency};
+use crate::history::project::Project;
+use crate::history::{journal, truncate};
+use crate::notify::Notifier;
+
+/// Notify handler - forwards configured events to notification channels
+pub struct NotifyHandler {
+    notifier: Arc<Notifier>,
+}
+
+impl NotifyHandler {
+    pub fn new(notifier: Arc<Notifier>) -> Self {
+        Self { notifier }
+    }
+}
+
+impl HookHandler for NotifyHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.notifier.wants(&format!("{:?}", event))
+    }
+
+    fn handle(&self, event: HookEvent, payload: &Value) -> HookResult {
+        self.notifier
+            .notify(&format!("{:?}", event), &build_notification(event, payload));
+        HookResult::Allow
+    }
+}
+
+/// Build the notification for a hook event
+pub fn build_notification(event: HookEvent, payload: &Value) -> Notification {
+    let field = |key: &str| payload.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let location = field("cwd")
+        .map(|cwd| Project::detect(Path::new(cwd)).id)
+        .unwrap_or_else(|| "unknown project".to_string());
+    let tool = field("tool_name").unwrap_or("unknown");
+
+    let (title, body, urgency) = match event {
+        HookEvent::Stop => ("Session finished".to_string(), location, Urgency::Low),
+        HookEvent::SubagentStop => (
+            "Subagent finished".to_string(),
+            format!("{} in {}", field("agent_type").unwrap_or("subagent"), location),
+            Urgency::Low,
+        ),
+        HookEvent::Notification => (
+            "Claude needs your attention".to_string(),
+            field("message").unwrap_or(&location).to_string(),
+            Urgency::Normal,
+        ),
+        HookEvent::PreToolUse | HookEvent::PostToolUse | HookEvent::PermissionRequest => {
+            let input = payload.get("tool_input").cloned().unwrap_or_default();
+            (
+                format!("{:?}: {}", event, tool),
+                journal::summarize_input(tool, &input),
+                Urgency::Normal,
+            )
+        }
+        HookEvent::UserPromptSubmit => (
+            "Prompt submitted".to_string(),
+            field("prompt").unwrap_or_default().to_string(),
+            Urgency::Low,
+        ),
+        _ => (format!("{:?}", event), location, Urgency::Low),
+    };
+
+    Notification::new(&title, &truncate(&body, 200), urgency)
+}
+
+/// Build the notification for an action blocked by a hook
+pub fn block_notification(payload: &Value, message: &str) -> Notification {
+    let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let input = payload.get("tool_input").cloned().unwrap_or_default();
+    let reason = message.trim_start_matches("🚨 BLOCKED:").trim();
+
+    Notification::new(
+        &format!("Blocked {}", tool),
+        &format!("{}\n{}", reason, truncate(&journal::summarize_input(tool, &input), 200)),
+        Urgency::Critical,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_notification() {
+        let stop = build_notification(HookEvent::Stop, &json!({"session_id": "s1"}));
+        assert_eq!(stop.title, "Session finished");
+        assert_eq!(stop.urgency, Urgency::Low);
+
+        let attention = build_notification(
+            HookEvent::Notification,
+            &json!({"message": "Claude needs your permission to use Bash"}),
+        );
+        assert_eq!(attention.body, "Claude needs your permission to use Bash");
+
+        let blocked = block_notification(
+            &json!({"tool_name": "Bash", "tool_input": {"command": "rm -rf /"}}),
+            "🚨 BLOCKED: Recursive delete of root",
+        );
+        assert_eq!(blocked.title, "Blocked Bash");
+        assert_eq!(blocked.urgency, Urgency::Critical);
+        assert!(blocked.body.starts_with("Recursive delete of root"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Skill suggestion hook handler
+//!
+//! On UserPromptSubmit, asks each installed skill's SkillProvider how well it
+//! matches the prompt and hands the best matches to Claude as additional
+//! context. Opt-in via `hooks.suggest_skills`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::config::ClaudeScope;
+use crate::contract::skill::SkillProvider;
+use crate::plugin::intent::{self, IndexedSkill, SkillIndex};
+
+/// Suggest at most this many skills per prompt
+const MAX_SUGGESTIONS: usize = 3;
+/// Skip matches below this confidence
+const MIN_CONFIDENCE: f32 = 0.3;
+
+/// Skill suggestion handler - points Claude at relevant installed skills
+pub struct SkillSuggestHandler {
+    enabled: bool,
+    skill_dirs: Vec<(ClaudeScope, PathBuf)>,
+    plugins_dir: PathBuf,
+    registries_dir: PathBuf,
+}
+
+impl SkillSuggestHandler {
+    pub fn new(
+        enabled: bool,
+        skill_dirs: Vec<(ClaudeScope, PathBuf)>,
+        plugins_dir: PathBuf,
+        registries_dir: PathBuf,
+    ) -> Self {
+        Self {
+            enabled,
+            skill_dirs,
+            plugins_dir,
+            registries_dir,
+        }
+    }
+}
+
+impl HookHandler for SkillSuggestHandler {
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && event == HookEvent::UserPromptSubmit
+    }
+
+    fn handle(&self, _event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let prompt = payload.get("prompt").and_then(|v| v.as_str()).unwrap_or_default();
+        if prompt.trim().is_empty() {
+            return HookResult::Allow;
+        }
+
+        let index = Arc::new(SkillIndex::new(intent::load_docs(
+            &self.skill_dirs,
+            &self.plugins_dir,
+            &self.registries_dir,
+        )));
+        let mut matches: Vec<(f32, IndexedSkill)> = index
+            .providers()
+            .into_iter()
+            .map(|skill| (skill.match_intent(prompt), skill))
+            .filter(|(confidence, _)| *confidence >= MIN_CONFIDENCE)
+            .collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.skill_name().cmp(b.1.skill_name())));
+        matches.truncate(MAX_SUGGESTIONS);
+
+        if matches.is_empty() {
+            return HookResult::Allow;
+        }
+        HookResult::Context {
+            context: render(matches.iter().map(|(_, skill)| skill)),
+        }
+    }
+}
+
+fn render<'a>(skills: impl Iterator<Item = &'a IndexedSkill>) -> String {
+    let mut out = String::from("## Relevant skills\n\nThese installed skills look relevant to this request:\n");
+    for skill in skills {
+        out.push_str(&format!("\n- **{}** — {}", skill.skill_name(), skill.doc().description));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_suggests_matching_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        for (name, description) in [
+            (
+                "cidr",
+                "CIDR calculator for network calculations. Use when working with IP subnets.",
+            ),
+            (
+                "dashify",
+                "Normalize filenames by lowercasing and replacing spaces with dashes.",
+            ),
+        ] {
+            fs::create_dir_all(skills.join(name)).unwrap();
+            fs::write(
+                skills.join(name).join("SKILL.md"),
+                format!("---\nname: {}\ndescription: {}\n---\n", name, description),
+            )
+            .unwrap();
+        }
+
+        let handler = SkillSuggestHandler::new(
+            true,
+            vec![(ClaudeScope::User, skills)],
+            tmp.path().join("plugins"),
+            tmp.path().join("registries"),
+        );
+        assert!(handler.handles(HookEvent::UserPromptSubmit));
+
+        match handler.handle(
+            HookEvent::UserPromptSubmit,
+            &json!({"prompt": "which subnet is 10.0.3.7 in for a /22 network?"}),
+        ) {
+            HookResult::Context { context } => {
+                assert!(context.contains("**cidr**"));
+                assert!(!context.contains("dashify"));
+            }
+            other => panic!("expected context, got {:?}", other),
+        }
+
+        assert!(matches!(
+            handler.handle(HookEvent::UserPromptSubmit, &json!({"prompt": "hello there"})),
+            HookResult::Allow
+        ));
+    }
+}
@@ -10,6 +10,7 @@ mod config;
 mod contract;
 mod history;
 mod hook;
+mod notify;
 mod plugin;
 
 use cli::{Cli, Commands};
@@ -52,7 +53,9 @@ fn run(cli: Cli, config: Config) -> Result<()> {
         Commands::History { action } => commands::history::run(action, &config),
         Commands::Config { action } => commands::config::run(action, &config),
         Commands::Registry { action } => commands::registry::run(action, &config),
+        Commands::Skill { action } => commands::skill::run(action, &config),
         Commands::Run { plugin, action, args } => commands::run::run(&plugin, &action, &args, &config),
+        Commands::Apply { dry_run } => commands::apply::run(dry_run, &config),
         Commands::Status { format } => commands::status::run(cli::OutputFormat::resolve(format), &config),
         Commands::Completions { shell } => commands::completions::run(shell),
     }
@@ -0,0 +1,30 @@
+//! Desktop notifications via `notify-send`
+
+use eyre::{Context, Result};
+use std::process::Command;
+
+use crate::contract::notification::{Notification, NotificationProvider};
+
+/// Sends desktop notifications with `notify-send` (libnotify)
+pub struct DesktopNotifier;
+
+impl NotificationProvider for DesktopNotifier {
+    fn provider_name(&self) -> &str {
+        "desktop"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let status = Command::new("notify-send")
+            .arg("--app-name=paii")
+            .arg(format!("--urgency={}", notification.urgency.as_str()))
+            .arg(&notification.title)
+            .arg(&notification.body)
+            .status()
+            .context("Failed to run notify-send (is libnotify installed?)")?;
+
+        if !status.success() {
+            eyre::bail!("notify-send exited with {:?}", status.code());
+        }
+        Ok(())
+    }
+}
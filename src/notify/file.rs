@@ -0,0 +1,91 @@
+//! File and FIFO notification sink
+//!
+//! Appends each notification as a JSON line. Pointing `path` at a named pipe
+//! lets another process (a status bar, a chat bridge) consume them; when no
+//! reader has the pipe open the notification is dropped instead of blocking
+//! the hook.
+
+use eyre::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::contract::notification::{Notification, NotificationProvider};
+
+/// Appends notifications as JSON lines to a file or FIFO
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl NotificationProvider for FileNotifier {
+    fn provider_name(&self) -> &str {
+        "file"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let mut line = serde_json::to_string(notification)?;
+        line.push('\n');
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            // Opening a FIFO for writing blocks until there is a reader
+            use std::os::unix::fs::OpenOptionsExt;
+            options.custom_flags(libc::O_NONBLOCK);
+        }
+
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = options
+            .open(&self.path)
+            .context(format!("Failed to open {}", self.path.display()))?;
+        file.write_all(line.as_bytes())
+            .context(format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::notification::Urgency;
+
+    #[test]
+    fn test_appends_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/notify.jsonl");
+        let notifier = FileNotifier::new(path.clone());
+
+        notifier.send(&Notification::new("one", "a", Urgency::Low)).unwrap();
+        notifier.send(&Notification::new("two", "b", Urgency::Normal)).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let titles: Vec<String> = content
+            .lines()
+            .map(|l| serde_json::from_str::<Notification>(l).unwrap().title)
+            .collect();
+        assert_eq!(titles, vec!["one", "two"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fifo_without_reader_does_not_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("notify.fifo");
+        let c_path = std::ffi::CString::new(fifo.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        let notifier = FileNotifier::new(fifo);
+        assert!(notifier.send(&Notification::new("t", "b", Urgency::Low)).is_err());
+    }
+}
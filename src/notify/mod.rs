@@ -0,0 +1,142 @@
+//! Notification delivery
+//!
+//! Built-in NotificationProviders and the routing that maps events to the
+//! channels configured in `[notifications]`:
+//!
+//! ```toml
+//! [notifications.channels.desktop]
+//! type = "desktop"
+//!
+//! [notifications.events]
+//! stop = ["desktop"]
+//! block = ["desktop"]
+//! ```
+//!
+//! Event names are hook events in snake_case (`stop`, `notification`,
+//! `subagent_stop`, ...) plus `block` for actions stopped by a hook.
+//! Delivery failures are logged and never fail the hook.
+
+pub mod desktop;
+pub mod file;
+pub mod webhook;
+
+use std::collections::HashMap;
+
+use crate::config::{ChannelConfig, Config, NotificationsConfig};
+use crate::contract::notification::{Notification, NotificationProvider};
+
+/// Event name for actions blocked by a hook
+pub const BLOCK_EVENT: &str = "block";
+
+/// Normalize an event name so `Stop`, `stop`, `subagent-stop` and
+/// `SubagentStop` all match the same `[notifications.events]` key
+pub fn event_key(event: &str) -> String {
+    event.to_lowercase().replace(['-', '_'], "")
+}
+
+/// Build the provider for a configured channel
+pub fn provider(channel: &ChannelConfig) -> Box<dyn NotificationProvider> {
+    match channel {
+        ChannelConfig::Desktop => Box::new(desktop::DesktopNotifier),
+        ChannelConfig::Webhook { url, headers } => Box::new(webhook::WebhookNotifier::new(url, headers.clone())),
+        ChannelConfig::File { path } => Box::new(file::FileNotifier::new(Config::expand_path(path))),
+    }
+}
+
+/// Routes notifications for an event to its configured channels
+pub struct Notifier {
+    channels: HashMap<String, Box<dyn NotificationProvider>>,
+    events: HashMap<String, Vec<String>>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        for (event, names) in &config.events {
+            for name in names.iter().filter(|n| !config.channels.contains_key(*n)) {
+                log::warn!("Notification event '{}' routes to unknown channel '{}'", event, name);
+            }
+        }
+
+        Self {
+            channels: config
+                .channels
+                .iter()
+                .map(|(name, channel)| (name.clone(), provider(channel)))
+                .collect(),
+            events: config
+                .events
+                .iter()
+                .map(|(event, names)| (event_key(event), names.clone()))
+                .collect(),
+        }
+    }
+
+    /// Whether any channel is configured for an event
+    pub fn wants(&self, event: &str) -> bool {
+        self.events
+            .get(&event_key(event))
+            .is_some_and(|names| !names.is_empty())
+    }
+
+    /// Send a notification to every channel configured for `event`.
+    ///
+    /// Returns the number of channels that accepted it.
+    pub fn notify(&self, event: &str, notification: &Notification) -> usize {
+        let Some(names) = self.events.get(&event_key(event)) else {
+            return 0;
+        };
+
+        let mut delivered = 0;
+        for name in names {
+            let Some(provider) = self.channels.get(name) else {
+                continue;
+            };
+            let mut notification = notification.clone();
+            notification.channel = Some(name.clone());
+
+            match provider.send(&notification) {
+                Ok(()) => delivered += 1,
+                Err(e) => log::warn!(
+                    "Notification via {} ({}) failed: {:#}",
+                    name,
+                    provider.provider_name(),
+                    e
+                ),
+            }
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::notification::Urgency;
+
+    #[test]
+    fn test_routes_to_configured_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("notify.log");
+
+        let config = NotificationsConfig {
+            channels: HashMap::from([("log".to_string(), ChannelConfig::File { path: log.clone() })]),
+            events: HashMap::from([
+                ("stop".to_string(), vec!["log".to_string()]),
+                (BLOCK_EVENT.to_string(), vec!["log".to_string(), "missing".to_string()]),
+            ]),
+        };
+        let notifier = Notifier::new(&config);
+
+        assert!(notifier.wants("Stop"));
+        assert!(!notifier.wants("notification"));
+
+        let notification = Notification::new("Blocked", "rm -rf /", Urgency::Critical);
+        assert_eq!(notifier.notify(BLOCK_EVENT, &notification), 1);
+        assert_eq!(notifier.notify("notification", &notification), 0);
+
+        let written = std::fs::read_to_string(&log).unwrap();
+        let sent: Notification = serde_json::from_str(written.trim()).unwrap();
+        assert_eq!(sent.channel.as_deref(), Some("log"));
+        assert_eq!(sent.urgency, Urgency::Critical);
+    }
+}
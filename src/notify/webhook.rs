@@ -0,0 +1,119 @@
+//! Webhook notifications: the notification as a JSON POST body
+
+use eyre::{Context, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::contract::notification::{Notification, NotificationProvider};
+
+/// How long a POST may take in total. Notifications are sent from inside
+/// hooks, so a slow endpoint must not hold up Claude Code.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// POSTs notifications as JSON to a URL
+pub struct WebhookNotifier {
+    url: String,
+    headers: HashMap<String, String>,
+    timeout: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, headers: HashMap<String, String>) -> Self {
+        Self {
+            url: url.to_string(),
+            headers,
+            timeout: TIMEOUT,
+        }
+    }
+}
+
+impl NotificationProvider for WebhookNotifier {
+    fn provider_name(&self) -> &str {
+        "webhook"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let body = serde_json::to_string(notification)?;
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let mut request = agent
+            .post(&self.url)
+            .header("User-Agent", concat!("paii/", env!("CARGO_PKG_VERSION")))
+            .header("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.header(name, shellexpand::env(value).unwrap_or_default().as_ref());
+        }
+
+        request
+            .send(body.as_str())
+            .context(format!("POST to {} failed", self.url))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::notification::Urgency;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_posts_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+
+        let notifier = WebhookNotifier::new(&url, HashMap::new());
+        notifier
+            .send(&Notification::new("Session stopped", "All done", Urgency::Low))
+            .unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(body["title"], "Session stopped");
+        assert_eq!(body["urgency"], "low");
+    }
+
+    #[test]
+    fn test_times_out() {
+        // Accepts the connection but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || listener.accept().map(|(stream, _)| stream));
+
+        let notifier = WebhookNotifier {
+            timeout: Duration::from_millis(200),
+            ..WebhookNotifier::new(&url, HashMap::new())
+        };
+        let started = std::time::Instant::now();
+        let result = notifier.send(&Notification::new("Session stopped", "All done", Urgency::Low));
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+        drop(server.join());
+    }
+}
@@ -0,0 +1,368 @@
+//! Claude Code subagents provided by plugins
+//!
+//! A plugin lists its subagents in `[provides.agents]`:
+//!
+//! ```toml
+//! [provides.agents]
+//! paths = ["agents/researcher/AGENT.md", "agents/reviewer.md"]
+//! ```
+//!
+//! or ships them under `agents/` (`agents/<name>/AGENT.md` or
+//! `agents/<name>.md`), which is picked up automatically. On install each
+//! agent is synced to `<scope>/.claude/agents/<name>.md`. Claude Code expects
+//! a flat directory of agent files, so ownership is tracked in one
+//! `.paii-plugins.toml` index next to them rather than per-agent markers.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manifest::PluginManifest;
+use super::skill::{is_valid_name, parse_frontmatter, symlink};
+
+/// Agent definition file inside `agents/<name>/`
+pub const AGENT_FILE: &str = "AGENT.md";
+
+/// Directory scanned when `[provides.agents]` is absent
+pub const AGENTS_DIR: &str = "agents";
+
+/// Index of agent name -> owning plugin in a Claude agents directory
+pub const OWNERS_FILE: &str = ".paii-plugins.toml";
+
+/// Model aliases Claude Code accepts besides full model ids
+const MODEL_ALIASES: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+
+/// AGENT.md frontmatter
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AgentFrontmatter {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Comma-separated tool names; all tools when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl AgentFrontmatter {
+    /// Tool names, empty when the agent inherits every tool
+    pub fn tool_list(&self) -> Vec<&str> {
+        self.tools
+            .as_deref()
+            .map(|t| t.split(',').map(str::trim).filter(|t| !t.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Check the fields Claude Code requires
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if self.name.is_empty() {
+            problems.push("missing `name`".to_string());
+        } else if !is_valid_name(&self.name) {
+            problems.push(format!(
+                "`name` '{}' must be lowercase letters, numbers and hyphens",
+                self.name
+            ));
+        }
+
+        if self.description.trim().is_empty() {
+            problems.push("missing `description`".to_string());
+        }
+
+        if let Some(ref tools) = self.tools {
+            let bad: Vec<&str> = tools.split(',').map(str::trim).filter(|t| !is_tool_name(t)).collect();
+            if !bad.is_empty() {
+                problems.push(format!("invalid `tools` entries: {:?}", bad));
+            }
+        }
+
+        if let Some(ref model) = self.model
+            && !MODEL_ALIASES.contains(&model.as_str())
+            && !model.starts_with("claude-")
+        {
+            problems.push(format!(
+                "`model` '{}' must be one of {} or a claude-* model id",
+                model,
+                MODEL_ALIASES.join(", ")
+            ));
+        }
+
+        if !problems.is_empty() {
+            eyre::bail!("{}", problems.join("; "));
+        }
+        Ok(())
+    }
+}
+
+/// `Read`, `mcp__server__tool` or `Bash(git:*)`
+fn is_tool_name(tool: &str) -> bool {
+    let name = tool
+        .split_once('(')
+        .map_or(tool, |(name, args)| if args.ends_with(')') { name } else { "" });
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A subagent shipped by a plugin
+#[derive(Debug, Clone)]
+pub struct Agent {
+    pub frontmatter: AgentFrontmatter,
+    /// The agent's Markdown file
+    pub source: PathBuf,
+}
+
+impl Agent {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+        let frontmatter: AgentFrontmatter = parse_frontmatter(&content)
+            .and_then(|fm: AgentFrontmatter| fm.validate().map(|_| fm))
+            .context(format!("Invalid {}", path.display()))?;
+
+        Ok(Self {
+            frontmatter,
+            source: path.to_path_buf(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.frontmatter.name
+    }
+}
+
+/// Load and validate every agent a plugin provides
+pub fn discover(plugin_dir: &Path, manifest: &PluginManifest) -> Result<Vec<Agent>> {
+    let paths: Vec<PathBuf> = match manifest.agent_paths() {
+        Some(paths) => paths
+            .iter()
+            .map(|p| {
+                let path = plugin_dir.join(p);
+                if path.is_dir() { path.join(AGENT_FILE) } else { path }
+            })
+            .collect(),
+        None => scan(&plugin_dir.join(AGENTS_DIR)),
+    };
+
+    let agents = paths.iter().map(|p| Agent::load(p)).collect::<Result<Vec<_>>>()?;
+
+    let mut names: Vec<&str> = agents.iter().map(Agent::name).collect();
+    names.sort();
+    if let Some(dup) = names.windows(2).find(|w| w[0] == w[1]) {
+        eyre::bail!("Plugin defines agent '{}' more than once", dup[0]);
+    }
+    Ok(agents)
+}
+
+/// `agents/<name>/AGENT.md` and `agents/<name>.md`
+fn scan(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            if path.is_dir() {
+                Some(path.join(AGENT_FILE)).filter(|p| p.exists())
+            } else {
+                Some(path).filter(|p| p.extension().is_some_and(|e| e == "md"))
+            }
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Agent name -> plugin for the agents paii synced into a directory
+pub fn owners(agents_dir: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(agents_dir.join(OWNERS_FILE))
+        .ok()
+        .and_then(|c| toml::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn write_owners(agents_dir: &Path, owners: &BTreeMap<String, String>) -> Result<()> {
+    let path = agents_dir.join(OWNERS_FILE);
+    if owners.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    fs::write(&path, toml::to_string(owners)?).context(format!("Failed to write {}", path.display()))
+}
+
+/// Make sure syncing `agents` for `plugin` won't clobber someone else's agent
+pub fn check_collisions(agents: &[Agent], plugin: &str, agents_dir: &Path) -> Result<()> {
+    let owners = owners(agents_dir);
+    for agent in agents {
+        let target = agents_dir.join(format!("{}.md", agent.name()));
+        if target.symlink_metadata().is_err() {
+            continue;
+        }
+        match owners.get(agent.name()) {
+            Some(owner) if owner == plugin => {}
+            Some(owner) => eyre::bail!(
+                "Agent '{}' is already provided by plugin '{}' ({})",
+                agent.name(),
+                owner,
+                target.display()
+            ),
+            None => eyre::bail!(
+                "Agent '{}' already exists at {} and is not managed by paii",
+                agent.name(),
+                target.display()
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Sync a plugin's agents into an agents directory, replacing whatever the
+/// plugin synced before. With `link`, files are symlinked (dev installs).
+pub fn sync(agents: &[Agent], plugin: &str, agents_dir: &Path, link: bool) -> Result<Vec<PathBuf>> {
+    unsync(plugin, agents_dir)?;
+    if agents.is_empty() {
+        return Ok(Vec::new());
+    }
+    fs::create_dir_all(agents_dir).context(format!("Failed to create {}", agents_dir.display()))?;
+
+    let mut owners = owners(agents_dir);
+    let mut synced = Vec::new();
+    for agent in agents {
+        let target = agents_dir.join(format!("{}.md", agent.name()));
+        if target.symlink_metadata().is_ok() {
+            fs::remove_file(&target)?;
+        }
+        if link {
+            symlink(&fs::canonicalize(&agent.source)?, &target)?;
+        } else {
+            fs::copy(&agent.source, &target).context(format!("Failed to copy {}", agent.source.display()))?;
+        }
+        owners.insert(agent.name().to_string(), plugin.to_string());
+        synced.push(target);
+    }
+    write_owners(agents_dir, &owners)?;
+    Ok(synced)
+}
+
+/// Remove every agent a plugin synced into an agents directory
+pub fn unsync(plugin: &str, agents_dir: &Path) -> Result<Vec<String>> {
+    let mut owners = owners(agents_dir);
+    let names: Vec<String> = owners
+        .iter()
+        .filter(|(_, owner)| owner.as_str() == plugin)
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    for name in &names {
+        let target = agents_dir.join(format!("{}.md", name));
+        if target.symlink_metadata().is_ok() {
+            fs::remove_file(&target).context(format!("Failed to remove {}", target.display()))?;
+        }
+        owners.remove(name);
+    }
+    if !names.is_empty() {
+        write_owners(agents_dir, &owners)?;
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_agent(path: &Path, frontmatter: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("---\n{}\n---\n\nYou are an agent.\n", frontmatter)).unwrap();
+    }
+
+    fn manifest(provides: &str) -> PluginManifest {
+        PluginManifest::from_str(&format!(
+            "[plugin]\nname = \"incident\"\nversion = \"0.1.0\"\ndescription = \"d\"\n{}",
+            provides
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_frontmatter_validation() {
+        let ok: AgentFrontmatter = parse_frontmatter(
+            "---\nname: code-reviewer\ndescription: Reviews code.\ntools: Read, Grep, Bash(git:*), mcp__jira__search\nmodel: inherit\n---\n",
+        )
+        .unwrap();
+        assert!(ok.validate().is_ok());
+        assert_eq!(ok.tool_list().len(), 4);
+
+        let bad: AgentFrontmatter =
+            parse_frontmatter("---\nname: Reviewer\ntools: Read, , Bash(\nmodel: gpt-4\n---\n").unwrap();
+        let err = bad.validate().unwrap_err().to_string();
+        assert!(err.contains("lowercase"));
+        assert!(err.contains("missing `description`"));
+        assert!(err.contains("invalid `tools`"));
+        assert!(err.contains("`model` 'gpt-4'"));
+    }
+
+    #[test]
+    fn test_discover() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plugin = tmp.path();
+        write_agent(
+            &plugin.join("agents/researcher/AGENT.md"),
+            "name: incident-researcher\ndescription: Researches incidents.",
+        );
+        write_agent(
+            &plugin.join("agents/scribe.md"),
+            "name: scribe\ndescription: Takes notes.",
+        );
+
+        let found = discover(plugin, &manifest("")).unwrap();
+        let names: Vec<&str> = found.iter().map(Agent::name).collect();
+        assert_eq!(names, vec!["incident-researcher", "scribe"]);
+
+        // An explicit list wins over the agents/ scan
+        let listed = discover(
+            plugin,
+            &manifest("[provides.agents]\npaths = [\"agents/researcher\"]\n"),
+        )
+        .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name(), "incident-researcher");
+    }
+
+    #[test]
+    fn test_sync_and_unsync() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plugin = tmp.path().join("plugin");
+        let agents_dir = tmp.path().join("agents");
+        write_agent(
+            &plugin.join("agents/scribe.md"),
+            "name: scribe\ndescription: Takes notes.",
+        );
+        let agents = discover(&plugin, &manifest("")).unwrap();
+
+        // A hand-written agent with the same name is protected
+        write_agent(&agents_dir.join("scribe.md"), "name: scribe\ndescription: Mine.");
+        assert!(
+            check_collisions(&agents, "incident", &agents_dir)
+                .unwrap_err()
+                .to_string()
+                .contains("not managed by paii")
+        );
+        fs::remove_file(agents_dir.join("scribe.md")).unwrap();
+
+        let synced = sync(&agents, "incident", &agents_dir, false).unwrap();
+        assert_eq!(synced, vec![agents_dir.join("scribe.md")]);
+        assert_eq!(owners(&agents_dir).get("scribe").map(String::as_str), Some("incident"));
+        assert!(check_collisions(&agents, "incident", &agents_dir).is_ok());
+        assert!(check_collisions(&agents, "other", &agents_dir).is_err());
+
+        assert_eq!(unsync("incident", &agents_dir).unwrap(), vec!["scribe"]);
+        assert!(!agents_dir.join("scribe.md").exists());
+        assert!(!agents_dir.join(OWNERS_FILE).exists());
+    }
+}
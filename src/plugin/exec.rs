@@ -0,0 +1,197 @@
+//! Plugin process execution
+//!
+//! Plugins run as subprocesses: `<entry point> <action> [args...]`, with the
+//! plugin directory as the working directory. Python plugins use
+//! `src/main.py`; Rust plugins use their built binary (building it on demand).
+//!
+//! Settings from the manifest's `[config]` (defaults, overridden by
+//! `[plugins.<name>.config]` in paii.toml) reach the plugin as JSON in
+//! `PAII_PLUGIN_CONFIG`, and each setting that names an `env` var is also
+//! exported under that name.
+
+use colored::*;
+use eyre::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::Plugin;
+use super::manifest::{BuildSpec, BuildType, PluginLanguage};
+
+/// Build the command that invokes a plugin action
+pub fn command(plugin: &Plugin, action: &str, args: &[String]) -> Result<Command> {
+    let mut cmd = match plugin.manifest.plugin.language {
+        PluginLanguage::Python => python_command(&plugin.path)?,
+        PluginLanguage::Rust => rust_command(&plugin.path)?,
+        PluginLanguage::Mixed => {
+            // Try Python first, then Rust
+            if plugin.path.join("src").join("main.py").exists() {
+                python_command(&plugin.path)?
+            } else {
+                rust_command(&plugin.path)?
+            }
+        }
+    };
+
+    cmd.arg(action).args(args).current_dir(&plugin.path);
+    for (var, value) in environment(plugin)? {
+        cmd.env(var, value);
+    }
+    Ok(cmd)
+}
+
+/// Environment variables carrying a plugin's settings
+fn environment(plugin: &Plugin) -> Result<Vec<(String, String)>> {
+    let mut settings = toml::Table::new();
+    let mut vars = Vec::new();
+
+    for (key, spec) in &plugin.manifest.config {
+        let Some(value) = plugin.config.get(key).or(spec.default.as_ref()) else {
+            continue;
+        };
+        if let Some(var) = &spec.env {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                other => serde_json::to_string(other)?,
+            };
+            vars.push((var.clone(), value));
+        }
+        settings.insert(key.clone(), value.clone());
+    }
+    // Overrides the manifest doesn't declare still reach the plugin
+    for (key, value) in &plugin.config {
+        settings.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+
+    if !settings.is_empty() {
+        vars.push((
+            "PAII_PLUGIN_CONFIG".to_string(),
+            serde_json::to_string(&settings).context("Failed to serialize plugin config")?,
+        ));
+    }
+    Ok(vars)
+}
+
+/// Run a plugin action, optionally feeding `input` on stdin, and return its stdout
+pub fn run_action(plugin: &Plugin, action: &str, args: &[String], input: Option<&str>) -> Result<String> {
+    let mut cmd = command(plugin, action, args)?;
+    cmd.stdin(if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::inherit()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .context(format!("Failed to execute plugin '{}'", plugin.manifest.plugin.name))?;
+
+    if let Some(input) = input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        stdin
+            .write_all(input.as_bytes())
+            .context("Failed to write plugin input")?;
+    }
+
+    let output = child.wait_with_output().context("Failed to wait for plugin")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        eyre::bail!(
+            "Plugin failed with exit code {:?}:\n{}\n{}",
+            output.status.code(),
+            stdout,
+            stderr
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn python_command(plugin_path: &Path) -> Result<Command> {
+    let main_py = plugin_path.join("src").join("main.py");
+
+    if !main_py.exists() {
+        eyre::bail!("Python main not found: {}", main_py.display());
+    }
+
+    let mut cmd = Command::new("python3");
+    cmd.arg(main_py);
+    Ok(cmd)
+}
+
+fn rust_command(plugin_path: &Path) -> Result<Command> {
+    Ok(Command::new(rust_binary(plugin_path)?))
+}
+
+/// Locate a Rust plugin's binary, building it if needed
+pub fn rust_binary(plugin_path: &Path) -> Result<PathBuf> {
+    let plugin_name = plugin_path.file_name().and_then(|n| n.to_str()).unwrap_or("plugin");
+
+    // Try release first, then debug
+    let binary_paths = [
+        plugin_path.join("target").join("release").join(plugin_name),
+        plugin_path.join("target").join("debug").join(plugin_name),
+    ];
+
+    if let Some(binary) = binary_paths.iter().find(|p| p.exists()) {
+        return Ok(binary.clone());
+    }
+
+    // Try to build it
+    eprintln!("{} Building Rust plugin: {}", "→".blue(), plugin_name.cyan());
+    let status = Command::new("cargo")
+        .arg("build")
+        .arg("--release")
+        .current_dir(plugin_path)
+        .stdout(Stdio::null())
+        .status()
+        .context("Failed to build Rust plugin")?;
+
+    if !status.success() {
+        eyre::bail!("Failed to build Rust plugin");
+    }
+
+    Ok(binary_paths[0].clone())
+}
+
+/// Build a plugin's runtime in `plugin_dir` as its `[build]` section describes:
+/// `uv sync` for a Python project, `cargo build --release` for Rust, or the
+/// declared `build_command`. Installs copy sources only, so this is what puts
+/// a plugin's virtualenv or binaries where its MCP servers expect them.
+pub fn build(plugin_dir: &Path, spec: &BuildSpec) -> Result<()> {
+    let mut cmd = if let Some(command) = &spec.build_command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    } else {
+        match spec.r#type {
+            BuildType::Uv if plugin_dir.join("pyproject.toml").exists() => {
+                let mut cmd = Command::new("uv");
+                cmd.arg("sync");
+                cmd
+            }
+            BuildType::Cargo => {
+                let mut cmd = Command::new("cargo");
+                cmd.arg("build").arg("--release");
+                cmd
+            }
+            _ => return Ok(()),
+        }
+    };
+
+    eprintln!("{} Building plugin in {}", "→".blue(), plugin_dir.display());
+    let status = cmd
+        .current_dir(plugin_dir)
+        .stdout(Stdio::null())
+        .status()
+        .context(format!("Failed to run build in {}", plugin_dir.display()))?;
+
+    if !status.success() {
+        eyre::bail!("Build failed in {} ({})", plugin_dir.display(), status);
+    }
+    Ok(())
+}
@@ -0,0 +1,339 @@
+//! Skill intent matching
+//!
+//! Ranks installed skills against free text with BM25 over each skill's
+//! name, SKILL.md description and its plugin's keywords and registry tags.
+//! Everything is local; nothing leaves the machine.
+
+use eyre::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::ClaudeScope;
+use crate::contract::skill::SkillProvider;
+use crate::plugin::loader::load_plugin;
+use crate::plugin::registry;
+use crate::plugin::skill::{self, SKILL_FILE};
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 length normalization
+const B: f64 = 0.75;
+/// Raw score at which `match_intent` reports 0.5 confidence
+const HALF_CONFIDENCE_SCORE: f64 = 2.0;
+/// Name and keyword terms count this many times as much as description terms
+const FIELD_BOOST: usize = 2;
+
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "by", "can", "do", "for", "from", "how", "i", "in", "is", "it",
+    "me", "my", "of", "on", "or", "please", "so", "that", "the", "this", "to", "use", "using", "want", "what", "when",
+    "with", "you", "your",
+];
+
+/// The searchable text of one skill
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillDoc {
+    pub name: String,
+    pub description: String,
+    /// Plugin keywords and registry tags
+    pub keywords: Vec<String>,
+    /// Directory containing SKILL.md
+    pub path: PathBuf,
+    pub plugin: Option<String>,
+}
+
+/// A ranked skill
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillMatch {
+    pub name: String,
+    pub description: String,
+    pub score: f64,
+    /// Score squashed into 0.0 - 1.0
+    pub confidence: f32,
+    pub plugin: Option<String>,
+}
+
+/// BM25 index over installed skills
+#[derive(Debug)]
+pub struct SkillIndex {
+    docs: Vec<SkillDoc>,
+    term_freqs: Vec<HashMap<String, usize>>,
+    lengths: Vec<usize>,
+    avg_len: f64,
+    doc_freqs: HashMap<String, usize>,
+}
+
+impl SkillIndex {
+    pub fn new(docs: Vec<SkillDoc>) -> Self {
+        let term_freqs: Vec<HashMap<String, usize>> = docs.iter().map(doc_terms).collect();
+        let lengths: Vec<usize> = term_freqs.iter().map(|tf| tf.values().sum()).collect();
+        let avg_len = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+
+        let mut doc_freqs: HashMap<String, usize> = HashMap::new();
+        for tf in &term_freqs {
+            for term in tf.keys() {
+                *doc_freqs.entry(term.clone()).or_default() += 1;
+            }
+        }
+
+        Self {
+            docs,
+            term_freqs,
+            lengths,
+            avg_len,
+            doc_freqs,
+        }
+    }
+
+    pub fn docs(&self) -> &[SkillDoc] {
+        &self.docs
+    }
+
+    /// BM25 score of document `i` for a query
+    pub fn score(&self, i: usize, query: &str) -> f64 {
+        let n = self.docs.len() as f64;
+        let tf = &self.term_freqs[i];
+        let len_norm = if self.avg_len > 0.0 {
+            self.lengths[i] as f64 / self.avg_len
+        } else {
+            1.0
+        };
+
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        terms
+            .iter()
+            .filter_map(|term| {
+                let f = *tf.get(term)? as f64;
+                let df = self.doc_freqs.get(term).copied().unwrap_or(0) as f64;
+                let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                Some(idf * f * (K1 + 1.0) / (f + K1 * (1.0 - B + B * len_norm)))
+            })
+            .sum()
+    }
+
+    /// Skills matching a query, best first
+    pub fn rank(&self, query: &str, limit: usize) -> Vec<SkillMatch> {
+        let mut matches: Vec<SkillMatch> = (0..self.docs.len())
+            .filter_map(|i| {
+                let score = self.score(i, query);
+                let doc = &self.docs[i];
+                (score > 0.0).then(|| SkillMatch {
+                    name: doc.name.clone(),
+                    description: doc.description.clone(),
+                    score,
+                    confidence: confidence(score),
+                    plugin: doc.plugin.clone(),
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+        matches.truncate(limit);
+        matches
+    }
+
+    /// SkillProvider views of every indexed skill
+    pub fn providers(self: &Arc<Self>) -> Vec<IndexedSkill> {
+        (0..self.docs.len())
+            .map(|i| IndexedSkill {
+                index: Arc::clone(self),
+                i,
+            })
+            .collect()
+    }
+}
+
+/// An installed SKILL.md skill, scored against its index
+pub struct IndexedSkill {
+    index: Arc<SkillIndex>,
+    i: usize,
+}
+
+impl IndexedSkill {
+    /// The indexed text of this skill
+    pub fn doc(&self) -> &SkillDoc {
+        &self.index.docs[self.i]
+    }
+}
+
+impl SkillProvider for IndexedSkill {
+    fn skill_name(&self) -> &str {
+        &self.index.docs[self.i].name
+    }
+
+    fn skill_path(&self) -> PathBuf {
+        self.index.docs[self.i].path.join(SKILL_FILE)
+    }
+
+    fn match_intent(&self, intent: &str) -> f32 {
+        confidence(self.index.score(self.i, intent))
+    }
+
+    fn execute(
+        &self,
+        action: &str,
+        _context: HashMap<String, serde_json::Value>,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        eyre::bail!(
+            "Skill '{}' is instructions for Claude and has no '{}' action",
+            self.skill_name(),
+            action
+        )
+    }
+}
+
+fn confidence(score: f64) -> f32 {
+    (score / (score + HALF_CONFIDENCE_SCORE)) as f32
+}
+
+/// Lowercased word terms with stopwords dropped and plurals folded
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .map(|w| match w.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => w,
+        })
+        .collect()
+}
+
+fn doc_terms(doc: &SkillDoc) -> HashMap<String, usize> {
+    let mut tf: HashMap<String, usize> = HashMap::new();
+    for term in tokenize(&doc.description) {
+        *tf.entry(term).or_default() += 1;
+    }
+    let boosted = std::iter::once(doc.name.as_str()).chain(doc.keywords.iter().map(String::as_str));
+    for term in boosted.flat_map(tokenize) {
+        *tf.entry(term).or_default() += FIELD_BOOST;
+    }
+    tf
+}
+
+/// Collect the installed skills Claude sees, with their plugins' keywords.
+///
+/// `skill_dirs` is in precedence order; a name already seen shadows later ones.
+pub fn load_docs(skill_dirs: &[(ClaudeScope, PathBuf)], plugins_dir: &Path, registries_dir: &Path) -> Vec<SkillDoc> {
+    let tags = registry_tags(registries_dir);
+    let mut docs: Vec<SkillDoc> = Vec::new();
+
+    for (scope, dir) in skill_dirs {
+        for installed in skill::installed(dir, *scope) {
+            if installed.error.is_some() || docs.iter().any(|d| d.name == installed.name) {
+                continue;
+            }
+
+            let mut keywords = Vec::new();
+            if let Some(ref plugin) = installed.plugin {
+                if let Ok(p) = load_plugin(plugins_dir.join(plugin)) {
+                    keywords.extend(p.manifest.plugin.keywords);
+                }
+                keywords.extend(tags.get(plugin).cloned().unwrap_or_default());
+            }
+
+            docs.push(SkillDoc {
+                name: installed.name,
+                description: installed.description,
+                keywords,
+                path: installed.path,
+                plugin: installed.plugin,
+            });
+        }
+    }
+    docs
+}
+
+/// Tags and keywords for each plugin in the cached registries
+fn registry_tags(registries_dir: &Path) -> HashMap<String, Vec<String>> {
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    let (registries, _) = registry::load_cached(registries_dir);
+    for cached in registries {
+        for entry in cached.registry.plugins {
+            tags.entry(entry.name)
+                .or_default()
+                .extend(entry.tags.into_iter().chain(entry.keywords));
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(name: &str, description: &str, keywords: &[&str]) -> SkillDoc {
+        SkillDoc {
+            name: name.to_string(),
+            description: description.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            path: PathBuf::from(name),
+            plugin: None,
+        }
+    }
+
+    fn index() -> SkillIndex {
+        SkillIndex::new(vec![
+            doc(
+                "rust-coder",
+                "Write Rust code using Scott's conventions. Use when creating Rust CLIs or libraries.",
+                &["cargo", "clippy"],
+            ),
+            doc(
+                "python-coder",
+                "Write Python code using Scott's conventions. Use when creating Python scripts.",
+                &["uv", "pytest"],
+            ),
+            doc(
+                "cidr",
+                "CIDR calculator for network calculations. Use when working with IP addresses and subnets.",
+                &["network"],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Use the Rust CLIs, please!"), vec!["rust", "cli"]);
+        assert_eq!(tokenize("class address"), vec!["class", "address"]);
+    }
+
+    #[test]
+    fn test_rank() {
+        let index = index();
+
+        let ranked = index.rank("split this subnet into /26 networks", 5);
+        assert_eq!(ranked[0].name, "cidr");
+        assert_eq!(ranked.len(), 1);
+
+        // Keywords count even when the description doesn't mention them
+        assert_eq!(index.rank("fix clippy warnings", 5)[0].name, "rust-coder");
+
+        // Terms shared by every skill rank below distinctive ones
+        let ranked = index.rank("write python code", 5);
+        assert_eq!(ranked[0].name, "python-coder");
+        assert!(ranked[0].score > ranked[1].score);
+
+        assert!(index.rank("bake a cake", 5).is_empty());
+    }
+
+    #[test]
+    fn test_match_intent() {
+        let index = Arc::new(index());
+        let providers = index.providers();
+        let cidr = providers.iter().find(|p| p.skill_name() == "cidr").unwrap();
+
+        let high = cidr.match_intent("calculate the subnet mask for this IP network");
+        assert!(high > 0.5 && high < 1.0);
+        assert_eq!(cidr.match_intent("write some python"), 0.0);
+        assert_eq!(cidr.skill_path(), PathBuf::from("cidr/SKILL.md"));
+    }
+}
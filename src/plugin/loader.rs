@@ -20,6 +20,7 @@ pub fn load_plugin<P: AsRef<Path>>(path: P) -> eyre::Result<Plugin> {
         manifest,
         path: path.to_path_buf(),
         state: PluginState::Discovered,
+        config: toml::Table::new(),
     })
 }
 
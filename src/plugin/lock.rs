@@ -0,0 +1,258 @@
+//! Plugin lockfile (paii.lock)
+//!
+//! Every install records exactly what was installed: the registry it was
+//! resolved from (or the git ref for a direct git install), the source URL
+//! and subpath, the git commit, the version and a hash of the installed
+//! files. `paii plugin sync` reinstalls the same set on another machine, and
+//! `update` uses the recorded registry and version constraint, or re-fetches
+//! the recorded git source.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lockfile format version
+pub const LOCK_VERSION: u32 = 1;
+
+/// Build output and caches an install doesn't copy, besides hidden directories
+const IGNORED_DIRS: &[&str] = &["target", "__pycache__", "node_modules"];
+
+const HEADER: &str = "# Generated by paii. Commit it to share your plugin set; `paii plugin sync` installs it.\n\n";
+
+/// One installed plugin
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LockedPlugin {
+    pub version: String,
+    /// Registry the plugin was resolved from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Git URL or local path installed from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Plugin directory within `source`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Branch or tag asked for when installing straight from git; `update`
+    /// follows it
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Git commit the plugin was installed from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// `sha256:<hex>` of the installed files; see [`content_hash`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Version constraint given at install; `update` stays within it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_req: Option<String>,
+    /// Installed as a symlink to `source`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dev: bool,
+}
+
+impl LockedPlugin {
+    /// A plugin installed from a local directory
+    pub fn local(path: &Path, dev: bool) -> Self {
+        Self {
+            source: Some(
+                fs::canonicalize(path)
+                    .unwrap_or_else(|_| path.to_path_buf())
+                    .display()
+                    .to_string(),
+            ),
+            dev,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct LockfileData {
+    version: u32,
+    plugins: BTreeMap<String, LockedPlugin>,
+}
+
+/// The lockfile on disk
+#[derive(Debug, Default)]
+pub struct Lockfile {
+    path: PathBuf,
+    plugins: BTreeMap<String, LockedPlugin>,
+}
+
+impl Lockfile {
+    /// Load a lockfile; a missing file is an empty lock
+    pub fn load(path: &Path) -> Result<Self> {
+        let plugins = if path.exists() {
+            let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+            let data: LockfileData = toml::from_str(&content).context(format!("Failed to parse {}", path.display()))?;
+            if data.version > LOCK_VERSION {
+                eyre::bail!(
+                    "{} is lockfile version {}; this paii supports up to {}",
+                    path.display(),
+                    data.version,
+                    LOCK_VERSION
+                );
+            }
+            data.plugins
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            plugins,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, plugin: &str) -> Option<&LockedPlugin> {
+        self.plugins.get(plugin)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &LockedPlugin)> {
+        self.plugins.iter()
+    }
+
+    /// Record an installed plugin and save
+    pub fn record(&mut self, plugin: &str, locked: LockedPlugin) -> Result<()> {
+        self.plugins.insert(plugin.to_string(), locked);
+        self.save()
+    }
+
+    /// Forget a removed plugin and save
+    pub fn forget(&mut self, plugin: &str) -> Result<()> {
+        if self.plugins.remove(plugin).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = LockfileData {
+            version: LOCK_VERSION,
+            plugins: self.plugins.clone(),
+        };
+        fs::write(&self.path, format!("{}{}", HEADER, toml::to_string_pretty(&data)?))
+            .context(format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Hash a plugin's files as `sha256:<hex>`.
+///
+/// Covers the same files an install copies (see [`is_ignored_dir`]), by
+/// relative path and content, so the hash of a source checkout matches the
+/// hash of its installed copy.
+pub fn content_hash(dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let content = fs::read(dir.join(&relative)).context(format!("Failed to read {}", relative))?;
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("sha256:{}", hex::encode(hasher.finalize())))
+}
+
+/// Whether a directory is build output or a cache, left out of installs
+/// and content hashes
+pub fn is_ignored_dir(name: &str) -> bool {
+    name.starts_with('.') || IGNORED_DIRS.contains(&name)
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir).context(format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if path.is_dir() {
+            if is_ignored_dir(&name) {
+                continue;
+            }
+            collect_files(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_forget() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("paii.lock");
+        let locked = LockedPlugin {
+            version: "1.2.0".to_string(),
+            registry: Some("work".to_string()),
+            source: Some("https://example.com/plugins".to_string()),
+            path: Some("plugins/incident".to_string()),
+            git_ref: None,
+            commit: Some("0123abcd".to_string()),
+            hash: Some("sha256:00".to_string()),
+            version_req: Some("^1.2".to_string()),
+            dev: false,
+        };
+
+        let mut lock = Lockfile::load(&path).unwrap();
+        lock.record("incident", locked.clone()).unwrap();
+        lock.record("local", LockedPlugin::local(tmp.path(), true)).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Generated by paii"));
+        assert!(content.contains("version = 1"));
+
+        let mut reloaded = Lockfile::load(&path).unwrap();
+        assert_eq!(reloaded.get("incident"), Some(&locked));
+        assert!(reloaded.get("local").unwrap().dev);
+
+        reloaded.forget("incident").unwrap();
+        assert!(Lockfile::load(&path).unwrap().get("incident").is_none());
+
+        fs::write(&path, "version = 9\n").unwrap();
+        assert!(Lockfile::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = tmp.path().join("a");
+        fs::create_dir_all(a.join("src")).unwrap();
+        fs::write(a.join("plugin.toml"), "[plugin]").unwrap();
+        fs::write(a.join("src/main.py"), "print()").unwrap();
+        fs::write(a.join(".mcp.json"), "{}").unwrap();
+
+        let hash = content_hash(&a).unwrap();
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash.len(), "sha256:".len() + 64);
+
+        // Build output and hidden directories don't count
+        fs::create_dir_all(a.join("target/release")).unwrap();
+        fs::write(a.join("target/release/bin"), "x").unwrap();
+        fs::create_dir_all(a.join(".venv")).unwrap();
+        fs::write(a.join(".venv/python"), "x").unwrap();
+        fs::create_dir_all(a.join("src/__pycache__")).unwrap();
+        fs::write(a.join("src/__pycache__/main.cpython-312.pyc"), "x").unwrap();
+        assert_eq!(content_hash(&a).unwrap(), hash);
+
+        fs::write(a.join("src/main.py"), "print(1)").unwrap();
+        assert_ne!(content_hash(&a).unwrap(), hash);
+    }
+}
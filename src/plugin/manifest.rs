@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::hook::HookEvent;
+use crate::plugin::version;
+
 /// Plugin manifest structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PluginManifest {
@@ -44,7 +47,8 @@ pub struct PluginInfo {
     pub repository: Option<String>,
     pub homepage: Option<String>,
 
-    #[serde(default)]
+    /// Search terms; older manifests call these `tags`
+    #[serde(default, alias = "tags")]
     pub keywords: Vec<String>,
 }
 
@@ -57,12 +61,37 @@ pub enum PluginLanguage {
     Mixed,
 }
 
+impl PluginLanguage {
+    /// The name used in plugin.toml and registries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginLanguage::Python => "python",
+            PluginLanguage::Rust => "rust",
+            PluginLanguage::Mixed => "mixed",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct PaiiRequirements {
     #[serde(default)]
     pub core_version: Option<String>,
 }
 
+impl PaiiRequirements {
+    /// Fail unless the running paii satisfies `core_version`
+    pub fn check(&self) -> eyre::Result<()> {
+        if !version::core_satisfied(self.core_version.as_deref())? {
+            eyre::bail!(
+                "Requires paii {} but this is paii {}",
+                self.core_version.as_deref().unwrap_or_default(),
+                version::paii_version()
+            );
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum ProvideSpec {
@@ -72,8 +101,15 @@ pub enum ProvideSpec {
         #[serde(default)]
         service: Option<String>,
     },
+    /// `[provides.agents]`: subagent definitions, relative to the plugin
+    Agents {
+        paths: Vec<String>,
+    },
 }
 
+/// Key of the `[provides]` entry listing subagents
+pub const AGENTS_KEY: &str = "agents";
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConsumeSpec {
     pub contract: String,
@@ -119,6 +155,41 @@ pub struct HooksSpec {
 
     #[serde(default)]
     pub subagent_stop: bool,
+
+    #[serde(default)]
+    pub notification: bool,
+
+    #[serde(default)]
+    pub user_prompt_submit: bool,
+
+    #[serde(default)]
+    pub permission_request: bool,
+
+    #[serde(default)]
+    pub pre_compact: bool,
+}
+
+impl HooksSpec {
+    /// Whether the plugin subscribes to an event
+    pub fn subscribes(&self, event: HookEvent) -> bool {
+        match event {
+            HookEvent::PreToolUse => self.pre_tool_use,
+            HookEvent::PostToolUse => self.post_tool_use,
+            HookEvent::Stop => self.stop,
+            HookEvent::SessionStart => self.session_start,
+            HookEvent::SessionEnd => self.session_end,
+            HookEvent::SubagentStop => self.subagent_stop,
+            HookEvent::Notification => self.notification,
+            HookEvent::PermissionRequest => self.permission_request,
+            HookEvent::UserPromptSubmit => self.user_prompt_submit,
+            HookEvent::PreCompact => self.pre_compact,
+        }
+    }
+
+    /// Subscribed events
+    pub fn events(&self) -> Vec<HookEvent> {
+        HookEvent::ALL.into_iter().filter(|e| self.subscribes(*e)).collect()
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -138,9 +209,19 @@ pub enum BuildType {
     Uv,
     Cargo,
     Custom,
+    /// SKILL.md only; nothing to build
+    Skill,
 }
 
 impl PluginManifest {
+    /// Agent paths declared in `[provides.agents]`, if any
+    pub fn agent_paths(&self) -> Option<&[String]> {
+        match self.provides.get(AGENTS_KEY) {
+            Some(ProvideSpec::Agents { paths }) => Some(paths),
+            _ => None,
+        }
+    }
+
     /// Load a manifest from a TOML file
     pub fn load<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
         let content = std::fs::read_to_string(&path)?;
@@ -221,6 +302,12 @@ type = "cargo"
         assert!(matches!(manifest.build.r#type, BuildType::Cargo));
     }
 
+    #[test]
+    fn test_tags_are_keywords() {
+        let manifest = PluginManifest::from_str(&format!("{}tags = [\"shell\", \"cli\"]\n", MINIMAL_MANIFEST)).unwrap();
+        assert_eq!(manifest.plugin.keywords, vec!["shell", "cli"]);
+    }
+
     #[test]
     fn test_default_plugin_language() {
         let lang = PluginLanguage::default();
@@ -241,6 +328,28 @@ type = "cargo"
         assert!(!hooks.stop);
         assert!(!hooks.session_start);
         assert!(!hooks.session_end);
+        assert!(hooks.events().is_empty());
+    }
+
+    #[test]
+    fn test_every_event_can_be_subscribed() {
+        let toml_str = r#"
+pre_tool_use = true
+post_tool_use = true
+stop = true
+session_start = true
+session_end = true
+subagent_stop = true
+notification = true
+user_prompt_submit = true
+permission_request = true
+pre_compact = true
+"#;
+        let hooks: HooksSpec = toml::from_str(toml_str).unwrap();
+        assert_eq!(hooks.events(), HookEvent::ALL.to_vec());
+
+        let hooks: HooksSpec = toml::from_str("notification = true").unwrap();
+        assert_eq!(hooks.events(), vec![HookEvent::Notification]);
     }
 
     #[test]
@@ -271,4 +380,14 @@ contract = "OtherContract"
         assert_eq!(reparsed.plugin.name, manifest.plugin.name);
         assert_eq!(reparsed.plugin.version, manifest.plugin.version);
     }
+
+    #[test]
+    fn test_core_version_check() {
+        let mut manifest = PluginManifest::from_str(FULL_MANIFEST).unwrap();
+        assert!(manifest.paii.check().is_ok());
+
+        manifest.paii.core_version = Some(">=99.0.0".to_string());
+        let err = manifest.paii.check().unwrap_err();
+        assert!(err.to_string().contains("Requires paii >=99.0.0"));
+    }
 }
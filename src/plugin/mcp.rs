@@ -0,0 +1,458 @@
+//! MCP servers bundled with plugins
+//!
+//! A plugin ships MCP server definitions in `.mcp.json` at its root, in the
+//! same format Claude Code uses:
+//!
+//! ```json
+//! { "mcpServers": { "jira": { "command": "python", "args": ["src/server.py"] } } }
+//! ```
+//!
+//! On install the commands are resolved against the installed plugin
+//! (`${CLAUDE_PLUGIN_ROOT}`, relative paths, the plugin's `.venv` and built
+//! binaries) and the servers are merged into Claude Code's MCP config:
+//! `~/.claude.json` for the user scope, `.mcp.json` at the project root for
+//! the project scope. Which servers paii added, and for which plugin, is
+//! tracked in `.paii-mcp.toml` in the scope's `.claude/` directory.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// MCP definitions shipped at the plugin root
+pub const MCP_FILE: &str = ".mcp.json";
+
+/// Index of server name -> owning plugin, kept in the scope's `.claude/`
+pub const OWNERS_FILE: &str = ".paii-mcp.toml";
+
+/// Placeholders replaced with the installed plugin's path
+const ROOT_VARS: &[&str] = &["${CLAUDE_PLUGIN_ROOT}", "${PAII_PLUGIN_ROOT}"];
+
+/// Protocol version sent in the doctor's `initialize` request
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// One MCP server definition
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct McpServer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Everything else (`type`, `url`, `headers`, ...) passes through as is
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl McpServer {
+    /// Whether Claude Code talks to this server over stdio
+    pub fn is_stdio(&self) -> bool {
+        self.command.is_some()
+            && self
+                .extra
+                .get("type")
+                .and_then(Value::as_str)
+                .is_none_or(|t| t == "stdio")
+    }
+
+    /// Resolve the command, args and env against an installed plugin
+    pub fn resolve(&self, plugin_dir: &Path) -> Self {
+        let root = plugin_dir.display().to_string();
+        let expand = |s: &str| ROOT_VARS.iter().fold(s.to_string(), |acc, var| acc.replace(var, &root));
+
+        Self {
+            command: self.command.as_deref().map(|c| resolve_command(&expand(c), plugin_dir)),
+            args: self.args.iter().map(|a| expand(a)).collect(),
+            env: self.env.iter().map(|(k, v)| (k.clone(), expand(v))).collect(),
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+/// Find a server command inside the plugin: relative paths, the plugin's
+/// virtualenv (for `python` and console scripts) and built Rust binaries.
+/// Anything else is left for PATH lookup.
+fn resolve_command(command: &str, plugin_dir: &Path) -> String {
+    let path = Path::new(command);
+    if path.is_absolute() {
+        return command.to_string();
+    }
+    if command.contains('/') {
+        return plugin_dir.join(path).display().to_string();
+    }
+
+    let venv_name = match command {
+        "python" | "python3" => "python",
+        other => other,
+    };
+    let candidates = [
+        plugin_dir.join(".venv").join("bin").join(venv_name),
+        plugin_dir.join("target").join("release").join(command),
+        plugin_dir.join("target").join("debug").join(command),
+    ];
+    candidates
+        .iter()
+        .find(|p| p.exists())
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| command.to_string())
+}
+
+/// Read and resolve the MCP servers a plugin provides
+pub fn load(plugin_dir: &Path) -> Result<BTreeMap<String, McpServer>> {
+    let path = plugin_dir.join(MCP_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let file: Value = serde_json::from_str(&content).context(format!("Invalid JSON in {}", path.display()))?;
+    let servers: BTreeMap<String, McpServer> =
+        serde_json::from_value(file.get("mcpServers").cloned().unwrap_or_default())
+            .context(format!("Invalid mcpServers in {}", path.display()))?;
+
+    for (name, server) in &servers {
+        if server.command.is_none() && !server.extra.contains_key("url") {
+            eyre::bail!(
+                "MCP server '{}' in {} has neither `command` nor `url`",
+                name,
+                path.display()
+            );
+        }
+    }
+
+    Ok(servers
+        .into_iter()
+        .map(|(name, server)| (name, server.resolve(plugin_dir)))
+        .collect())
+}
+
+/// Claude Code MCP config file plus paii's ownership index for one scope
+#[derive(Debug, Clone)]
+pub struct McpConfig {
+    /// `~/.claude.json` or `<project>/.mcp.json`
+    pub path: PathBuf,
+    /// `.paii-mcp.toml` in the scope's `.claude/`
+    pub owners_path: PathBuf,
+}
+
+impl McpConfig {
+    pub fn new(path: PathBuf, owners_path: PathBuf) -> Self {
+        Self { path, owners_path }
+    }
+
+    /// Server name -> plugin for the servers paii registered
+    pub fn owners(&self) -> BTreeMap<String, String> {
+        fs::read_to_string(&self.owners_path)
+            .ok()
+            .and_then(|c| toml::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_owners(&self, owners: &BTreeMap<String, String>) -> Result<()> {
+        if owners.is_empty() {
+            if self.owners_path.exists() {
+                fs::remove_file(&self.owners_path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = self.owners_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.owners_path, toml::to_string(owners)?)
+            .context(format!("Failed to write {}", self.owners_path.display()))
+    }
+
+    fn read(&self) -> Result<Value> {
+        if !self.path.exists() {
+            return Ok(json!({}));
+        }
+        let content = fs::read_to_string(&self.path).context(format!("Failed to read {}", self.path.display()))?;
+        if content.trim().is_empty() {
+            return Ok(json!({}));
+        }
+        serde_json::from_str(&content).context(format!("Invalid JSON in {}", self.path.display()))
+    }
+
+    fn write(&self, config: &Value) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so Claude Code never sees a half-written file
+        let tmp = self.path.with_extension("json.paii-tmp");
+        fs::write(&tmp, format!("{}\n", serde_json::to_string_pretty(config)?))?;
+        fs::rename(&tmp, &self.path).context(format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Server names currently configured
+    pub fn servers(&self) -> Result<Vec<String>> {
+        Ok(self
+            .read()?
+            .get("mcpServers")
+            .and_then(Value::as_object)
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Make sure registering `servers` for `plugin` won't replace servers
+    /// configured by hand or by another plugin
+    pub fn check_collisions(&self, servers: &BTreeMap<String, McpServer>, plugin: &str) -> Result<()> {
+        let existing = self.servers()?;
+        let owners = self.owners();
+        for name in servers.keys().filter(|n| existing.contains(n)) {
+            match owners.get(name) {
+                Some(owner) if owner == plugin => {}
+                Some(owner) => eyre::bail!(
+                    "MCP server '{}' is already provided by plugin '{}' ({})",
+                    name,
+                    owner,
+                    self.path.display()
+                ),
+                None => eyre::bail!(
+                    "MCP server '{}' is already configured in {} and is not managed by paii",
+                    name,
+                    self.path.display()
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge a plugin's servers into the config, replacing the ones it
+    /// registered before
+    pub fn register(&self, servers: &BTreeMap<String, McpServer>, plugin: &str) -> Result<Vec<String>> {
+        self.unregister(plugin)?;
+        if servers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut config = self.read()?;
+        let root = config
+            .as_object_mut()
+            .ok_or_else(|| eyre::eyre!("{} is not a JSON object", self.path.display()))?;
+        let section = root
+            .entry("mcpServers")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| eyre::eyre!("mcpServers in {} is not an object", self.path.display()))?;
+
+        let mut owners = self.owners();
+        for (name, server) in servers {
+            section.insert(name.clone(), serde_json::to_value(server)?);
+            owners.insert(name.clone(), plugin.to_string());
+        }
+
+        self.write(&config)?;
+        self.write_owners(&owners)?;
+        Ok(servers.keys().cloned().collect())
+    }
+
+    /// Remove every server a plugin registered
+    pub fn unregister(&self, plugin: &str) -> Result<Vec<String>> {
+        let mut owners = self.owners();
+        let names: Vec<String> = owners
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == plugin)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if names.is_empty() {
+            return Ok(names);
+        }
+
+        let mut config = self.read()?;
+        if let Some(section) = config.get_mut("mcpServers").and_then(Value::as_object_mut) {
+            for name in &names {
+                section.shift_remove(name);
+            }
+        }
+        for name in &names {
+            owners.remove(name);
+        }
+
+        self.write(&config)?;
+        self.write_owners(&owners)?;
+        Ok(names)
+    }
+}
+
+/// Start a stdio server, send `initialize` and wait for its response.
+///
+/// Returns the server's reported `name version`.
+pub fn probe(server: &McpServer, cwd: &Path, timeout: Duration) -> Result<String> {
+    let command = server
+        .command
+        .as_deref()
+        .ok_or_else(|| eyre::eyre!("not a stdio server"))?;
+
+    let mut child = Command::new(command)
+        .args(&server.args)
+        .envs(&server.env)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("Failed to start {}", command))?;
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "paii", "version": env!("CARGO_PKG_VERSION")},
+        },
+    });
+
+    let mut stdin = child.stdin.take().ok_or_else(|| eyre::eyre!("no stdin"))?;
+    let stdout = child.stdout.take().ok_or_else(|| eyre::eyre!("no stdout"))?;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        // Servers may log to stdout before answering; wait for our response
+        for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+            if let Ok(message) = serde_json::from_str::<Value>(&line)
+                && message.get("id") == Some(&json!(1))
+            {
+                let _ = tx.send(message);
+                return;
+            }
+        }
+    });
+
+    let sent = writeln!(stdin, "{}", request).and_then(|_| stdin.flush());
+    let response = rx.recv_timeout(timeout);
+    drop(stdin);
+    let _ = child.kill();
+    let _ = child.wait();
+
+    sent.context("Failed to send initialize")?;
+    let response = response.map_err(|_| eyre::eyre!("no initialize response within {}s", timeout.as_secs()))?;
+
+    if let Some(error) = response.get("error") {
+        eyre::bail!("initialize failed: {}", error);
+    }
+    let info = response
+        .pointer("/result/serverInfo")
+        .ok_or_else(|| eyre::eyre!("initialize response has no serverInfo"))?;
+    Ok(format!(
+        "{} {}",
+        info.get("name").and_then(Value::as_str).unwrap_or("unknown"),
+        info.get("version").and_then(Value::as_str).unwrap_or("")
+    )
+    .trim()
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(dir: &Path) {
+        fs::create_dir_all(dir.join(".venv/bin")).unwrap();
+        fs::write(dir.join(".venv/bin/python"), "").unwrap();
+        fs::write(
+            dir.join(MCP_FILE),
+            r#"{"mcpServers": {
+                "jira": {"command": "python", "args": ["${CLAUDE_PLUGIN_ROOT}/src/server.py"], "env": {"JIRA_URL": "x"}},
+                "remote": {"type": "http", "url": "https://example.com/mcp"}
+            }}"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_resolves_against_plugin() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_plugin(tmp.path());
+
+        let servers = load(tmp.path()).unwrap();
+        let jira = &servers["jira"];
+        let root = tmp.path().display().to_string();
+        assert_eq!(
+            jira.command.as_deref(),
+            Some(format!("{}/.venv/bin/python", root).as_str())
+        );
+        assert_eq!(jira.args, vec![format!("{}/src/server.py", root)]);
+        assert!(jira.is_stdio());
+
+        let remote = &servers["remote"];
+        assert!(!remote.is_stdio());
+        assert_eq!(remote.extra["url"], "https://example.com/mcp");
+
+        assert_eq!(
+            resolve_command("./bin/server", tmp.path()),
+            format!("{}/./bin/server", root)
+        );
+        assert_eq!(resolve_command("npx", tmp.path()), "npx");
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plugin = tmp.path().join("plugin");
+        write_plugin(&plugin);
+        let servers = load(&plugin).unwrap();
+
+        let config = McpConfig::new(
+            tmp.path().join(".claude.json"),
+            tmp.path().join(".claude").join(OWNERS_FILE),
+        );
+        fs::write(
+            &config.path,
+            r#"{"numStartups": 3, "mcpServers": {"mine": {"command": "mine"}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.register(&servers, "jira").unwrap(), vec!["jira", "remote"]);
+        let written: Value = serde_json::from_str(&fs::read_to_string(&config.path).unwrap()).unwrap();
+        assert_eq!(written["numStartups"], 3);
+        assert_eq!(
+            written["mcpServers"].as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["mine", "jira", "remote"]
+        );
+
+        // Hand-written and other plugins' servers are protected
+        let mine = BTreeMap::from([("mine".to_string(), McpServer::default())]);
+        assert!(config.check_collisions(&mine, "jira").is_err());
+        assert!(config.check_collisions(&servers, "jira").is_ok());
+        assert!(config.check_collisions(&servers, "other").is_err());
+
+        assert_eq!(config.unregister("jira").unwrap(), vec!["jira", "remote"]);
+        assert_eq!(config.servers().unwrap(), vec!["mine"]);
+        assert!(!config.owners_path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe() {
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("server.sh");
+        fs::write(
+            &script,
+            "read line\necho 'starting up'\necho '{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{},\"serverInfo\":{\"name\":\"fake\",\"version\":\"1.0\"}}}'\n",
+        )
+        .unwrap();
+
+        let server = McpServer {
+            command: Some("sh".to_string()),
+            args: vec![script.display().to_string()],
+            ..Default::default()
+        };
+        assert_eq!(probe(&server, tmp.path(), Duration::from_secs(5)).unwrap(), "fake 1.0");
+
+        let silent = McpServer {
+            command: Some("sleep".to_string()),
+            args: vec!["5".to_string()],
+            ..Default::default()
+        };
+        assert!(probe(&silent, tmp.path(), Duration::from_millis(200)).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! MemoryProvider backed by a plugin process
+//!
+//! A plugin that `provides` MemoryProvider is called as
+//! `<entry point> memory <method>` with a JSON object of parameters on stdin.
+//! It must print a single JSON object to stdout: `{"result": ...}` on success
+//! or `{"error": "message"}` on failure.
+//!
+//! | method            | params                                   | result               |
+//! |-------------------|------------------------------------------|----------------------|
+//! | `capture`         | `category`, `content`, `metadata`        | path/id string       |
+//! | `query`           | `category`, `query`, `limit`, `since`?   | `[MemoryResult]`     |
+//! | `list_categories` | none                                     | `[string]`           |
+//! | `get_recent`      | `category`, `count`                      | `[MemoryResult]`     |
+//! | `update`          | `entry` (a changed `MemoryResult`)       | ignored              |
+
+use chrono::NaiveDate;
+use eyre::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::HashMap;
+
+use super::{Plugin, exec};
+use crate::contract::memory::{MemoryProvider, MemoryResult};
+
+/// Plugin action used for the memory protocol
+const MEMORY_ACTION: &str = "memory";
+
+pub struct PluginMemoryProvider {
+    plugin: Plugin,
+}
+
+impl PluginMemoryProvider {
+    pub fn new(plugin: &Plugin) -> Self {
+        Self { plugin: plugin.clone() }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let name = &self.plugin.manifest.plugin.name;
+        log::debug!("Memory call {} on plugin {}", method, name);
+
+        let output = exec::run_action(
+            &self.plugin,
+            MEMORY_ACTION,
+            &[method.to_string()],
+            Some(&params.to_string()),
+        )
+        .context(format!("Memory plugin '{}' failed on {}", name, method))?;
+
+        parse_response(&output).context(format!("Bad {} response from memory plugin '{}'", method, name))
+    }
+}
+
+/// Parse a `{"result": ...}` / `{"error": ...}` protocol response
+fn parse_response<T: DeserializeOwned>(output: &str) -> Result<T> {
+    let mut response: serde_json::Value = serde_json::from_str(output.trim()).context("Response is not JSON")?;
+
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        let message = error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string());
+        eyre::bail!("{}", message);
+    }
+
+    let result = response
+        .get_mut("result")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| eyre::eyre!("Response has neither 'result' nor 'error'"))?;
+    serde_json::from_value(result).context("Unexpected result shape")
+}
+
+impl MemoryProvider for PluginMemoryProvider {
+    fn capture(&self, category: &str, content: &str, metadata: HashMap<String, serde_json::Value>) -> Result<String> {
+        self.call(
+            "capture",
+            json!({ "category": category, "content": content, "metadata": metadata }),
+        )
+    }
+
+    fn query(&self, category: &str, query: &str, limit: usize) -> Result<Vec<MemoryResult>> {
+        self.query_since(category, query, None, limit)
+    }
+
+    fn query_since(
+        &self,
+        category: &str,
+        query: &str,
+        since: Option<NaiveDate>,
+        limit: usize,
+    ) -> Result<Vec<MemoryResult>> {
+        self.call(
+            "query",
+            json!({
+                "category": category,
+                "query": query,
+                "limit": limit,
+                "since": since.map(|d| d.format("%Y-%m-%d").to_string()),
+            }),
+        )
+    }
+
+    fn list_categories(&self) -> Vec<String> {
+        self.call("list_categories", json!({})).unwrap_or_else(|e| {
+            log::error!("{:#}", e);
+            Vec::new()
+        })
+    }
+
+    fn get_recent(&self, category: &str, count: usize) -> Result<Vec<MemoryResult>> {
+        self.call("get_recent", json!({ "category": category, "count": count }))
+    }
+
+    fn update(&self, entry: &MemoryResult) -> Result<()> {
+        self.call::<serde_json::Value>("update", json!({ "entry": entry }))
+            .map(|_| ())
+    }
+
+    fn provider_name(&self) -> String {
+        format!("plugin ({})", self.plugin.manifest.plugin.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_result() {
+        let categories: Vec<String> = parse_response(r#"{"result": ["sessions", "learnings"]}"#).unwrap();
+        assert_eq!(categories, vec!["sessions", "learnings"]);
+    }
+
+    #[test]
+    fn test_parse_memory_results() {
+        let output = r#"{"result": [{"path": "db:1", "category": "sessions", "timestamp": "2025-01-02T03:04:05+00:00",
+            "content": "hello", "metadata": {"title": "Hi"}}]}"#;
+        let results: Vec<MemoryResult> = parse_response(output).unwrap();
+        assert_eq!(results[0].title(), "Hi");
+        assert_eq!(results[0].date(), NaiveDate::from_ymd_opt(2025, 1, 2));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_response::<String>(r#"{"error": "disk full"}"#).unwrap_err();
+        assert!(err.to_string().contains("disk full"));
+        assert!(parse_response::<String>("not json").is_err());
+        assert!(parse_response::<String>("{}").is_err());
+    }
+}
@@ -11,16 +11,31 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::contract::{ContractRegistry, ContractType};
+
+pub mod agent;
+pub mod exec;
+pub mod intent;
 pub mod loader;
+pub mod lock;
 pub mod manifest;
+pub mod mcp;
+pub mod memory;
+pub mod publish;
 pub mod registry;
+pub mod signing;
+pub mod skill;
+pub mod source;
+pub mod version;
 
 /// A loaded plugin
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Plugin {
     pub manifest: manifest::PluginManifest,
     pub path: PathBuf,
     pub state: PluginState,
+    /// `[plugins.<name>.config]` overrides from paii.toml
+    pub config: toml::Table,
 }
 
 /// Plugin lifecycle state
@@ -36,6 +51,7 @@ pub enum PluginState {
 pub struct PluginManager {
     pub plugins: HashMap<String, Plugin>,
     pub plugins_dir: PathBuf,
+    pub contracts: ContractRegistry,
 }
 
 impl PluginManager {
@@ -43,21 +59,71 @@ impl PluginManager {
         Self {
             plugins: HashMap::new(),
             plugins_dir,
+            contracts: ContractRegistry::new(),
         }
     }
 
     /// Discover all plugins in the plugins directory
     pub fn discover(&mut self) -> eyre::Result<()> {
-        // TODO: Scan plugins_dir for plugin.toml files
+        if !self.plugins_dir.exists() {
+            return Ok(());
+        }
+
+        for entry in std::fs::read_dir(&self.plugins_dir)? {
+            let path = entry?.path();
+            // is_dir() follows symlinks, so dev installs are included
+            if !path.is_dir() || !path.join("plugin.toml").exists() {
+                continue;
+            }
+
+            match loader::load_plugin(&path) {
+                Ok(plugin) => {
+                    self.plugins.insert(plugin.manifest.plugin.name.clone(), plugin);
+                }
+                Err(e) => log::warn!("Failed to load plugin at {}: {}", path.display(), e),
+            }
+        }
         Ok(())
     }
 
-    /// Load all discovered plugins
+    /// Load all discovered plugins and resolve the contracts they provide.
+    ///
+    /// Plugins are processed in name order; when two plugins provide the same
+    /// single-provider contract, the first one wins and the conflict is logged.
     pub fn load_all(&mut self) -> eyre::Result<()> {
-        // TODO: Parse manifests and resolve contracts
+        let mut names: Vec<String> = self.plugins.keys().cloned().collect();
+        names.sort();
+
+        for name in names {
+            let Some(plugin) = self.plugins.get_mut(&name) else {
+                continue;
+            };
+
+            for (key, spec) in &plugin.manifest.provides {
+                match ContractType::from_provide(key, spec) {
+                    // Any number of plugins can provide skills side by side
+                    Some(ContractType::SkillProvider) => {}
+                    Some(contract) => {
+                        if let Err(e) = self.contracts.register(contract, name.clone()) {
+                            log::warn!("Ignoring provider from plugin {}: {}", name, e);
+                        }
+                    }
+                    None if key == manifest::AGENTS_KEY => {}
+                    None => log::debug!("Plugin {} provides unknown contract '{}'", name, key),
+                }
+            }
+            plugin.state = PluginState::Loaded;
+        }
         Ok(())
     }
 
+    /// Get the plugin providing a contract, if any
+    pub fn provider(&self, contract: &ContractType) -> Option<&Plugin> {
+        self.contracts
+            .get_provider(contract)
+            .and_then(|name| self.plugins.get(name))
+    }
+
     /// Get a plugin by name
     pub fn get(&self, name: &str) -> Option<&Plugin> {
         self.plugins.get(name)
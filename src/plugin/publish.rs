@@ -0,0 +1,303 @@
+//! Registry authoring
+//!
+//! `paii registry publish` generates a plugin's registry entry from its
+//! plugin.toml and writes it into a registry file. The file is edited in
+//! place, so comments and hand-added fields such as `hash` survive.
+//! `paii registry lint` uses [`drift`] to report entries that no longer
+//! match their plugin.
+//!
+//! plugin.toml has no counterpart for an entry's `type` and `tags`; publish
+//! replaces them with `provides` and `keywords`.
+
+use eyre::{Context, Result};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::plugin::manifest::PluginManifest;
+use crate::plugin::registry::{RegistryEntry, RegistryMeta, SCHEMA_VERSION};
+
+/// Entry fields generated from plugin.toml, overwritten on every publish
+const GENERATED: &[&str] = &[
+    "name",
+    "version",
+    "description",
+    "source",
+    "path",
+    "core_version",
+    "language",
+    "provides",
+    "keywords",
+];
+
+/// Entry fields publish drops in favor of `provides` and `keywords`
+const SUPERSEDED: &[&str] = &["type", "tags"];
+
+/// What publish did to the registry file
+#[derive(Debug, PartialEq)]
+pub enum Published {
+    Added,
+    /// The entry for the same version was regenerated
+    Updated,
+    /// An entry for this older version was replaced
+    Replaced(String),
+}
+
+/// Contents of a new registry file
+pub fn init(meta: &RegistryMeta) -> Result<String> {
+    let meta = RegistryMeta {
+        schema_version: Some(SCHEMA_VERSION),
+        ..meta.clone()
+    };
+    Ok(format!("[registry]\n{}", toml::to_string(&meta)?))
+}
+
+/// The registry entry for a plugin whose repository is `source`, found at
+/// `path` within it
+pub fn entry(manifest: &PluginManifest, source: String, path: Option<String>) -> RegistryEntry {
+    let info = &manifest.plugin;
+    let mut provides: Vec<String> = manifest.provides.keys().cloned().collect();
+    provides.sort();
+    RegistryEntry {
+        name: info.name.clone(),
+        version: Some(info.version.clone()),
+        description: Some(info.description.clone()).filter(|d| !d.is_empty()),
+        source: Some(source),
+        path,
+        core_version: manifest.paii.core_version.clone(),
+        language: Some(info.language.as_str().to_string()),
+        provides,
+        keywords: info.keywords.clone(),
+        ..Default::default()
+    }
+}
+
+/// Add `entry` to registry TOML, or regenerate the existing entry for it.
+///
+/// The entry with the same name and version is updated in place. Failing
+/// that, an unpinned entry (no `ref` or `commit`) for another version is
+/// replaced when `entry` is unpinned too: both would install from the
+/// default branch, which holds only one of them. Otherwise the entry is
+/// appended.
+pub fn publish(content: &str, entry: &RegistryEntry) -> Result<(String, Published)> {
+    let mut doc: DocumentMut = content.parse().context("Invalid registry TOML")?;
+    let mut generated = toml::to_string(entry)?
+        .parse::<DocumentMut>()
+        .context("Failed to generate registry entry")?
+        .as_table()
+        .clone();
+
+    let plugins = doc
+        .entry("plugins")
+        .or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| eyre::eyre!("`plugins` must be [[plugins]] tables"))?;
+
+    let field = |table: &Table, key: &str| table.get(key).and_then(|v| v.as_str()).map(String::from);
+    let pinned = |table: &Table| table.contains_key("ref") || table.contains_key("commit");
+    let same_name: Vec<usize> = plugins
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| field(t, "name").as_deref() == Some(entry.name.as_str()))
+        .map(|(i, _)| i)
+        .collect();
+    let exact = same_name
+        .iter()
+        .copied()
+        .find(|&i| plugins.get(i).is_some_and(|t| field(t, "version") == entry.version));
+    let unpinned = same_name
+        .iter()
+        .copied()
+        .find(|&i| plugins.get(i).is_some_and(|t| !pinned(t)))
+        .filter(|_| entry.git_ref.is_none() && entry.commit.is_none());
+
+    let published = if let Some(table) = exact.and_then(|i| plugins.get_mut(i)) {
+        regenerate(table, &generated, false);
+        Published::Updated
+    } else if let Some(table) = unpinned.and_then(|i| plugins.get_mut(i)) {
+        let previous = field(table, "version").unwrap_or_default();
+        regenerate(table, &generated, true);
+        Published::Replaced(previous)
+    } else {
+        if !content.trim().is_empty() {
+            generated.decor_mut().set_prefix("\n");
+        }
+        plugins.push(generated);
+        Published::Added
+    };
+    Ok((doc.to_string(), published))
+}
+
+/// Overwrite the generated fields of an existing entry, keeping the rest
+fn regenerate(table: &mut Table, generated: &Table, new_version: bool) {
+    for key in GENERATED.iter().chain(SUPERSEDED) {
+        match generated.get(key) {
+            Some(item) => {
+                table.insert(key, item.clone());
+            }
+            None => {
+                table.remove(key);
+            }
+        }
+    }
+    for key in ["ref", "hash"] {
+        if let Some(item) = generated.get(key) {
+            table.insert(key, item.clone());
+        } else if new_version && key == "hash" {
+            // The hash was of the old version's files
+            table.remove(key);
+        }
+    }
+}
+
+/// How a registry entry differs from the entry its plugin.toml generates,
+/// beyond name and version
+pub fn drift(entry: &RegistryEntry, generated: &RegistryEntry) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut differs = |field: &str, same: bool| {
+        if !same {
+            problems.push(format!("{} differs from plugin.toml", field));
+        }
+    };
+    fn sorted<'a>(list: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+        let mut list: Vec<_> = list.collect();
+        list.sort();
+        list.dedup();
+        list
+    }
+    differs("description", entry.description == generated.description);
+    differs("language", entry.language == generated.language);
+    differs("core_version", entry.core_version == generated.core_version);
+    differs(
+        "provides",
+        sorted(entry.provides.iter()) == sorted(generated.provides.iter()),
+    );
+    // Legacy tags count as keywords here; they are reported below
+    differs(
+        "keywords",
+        sorted(entry.keywords.iter().chain(&entry.tags)) == sorted(generated.keywords.iter()),
+    );
+
+    if entry.kind.is_some() {
+        problems.push("`type` has no counterpart in plugin.toml; list contracts under [provides]".to_string());
+    }
+    if !entry.tags.is_empty() {
+        problems.push("`tags` are `keywords` in plugin.toml".to_string());
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::registry::Registry;
+
+    const MANIFEST: &str = r#"
+[plugin]
+name = "otto"
+version = "0.2.0"
+description = "Otto task runner skill"
+language = "mixed"
+keywords = ["build", "ci"]
+
+[paii]
+core_version = ">=0.1.0"
+
+[provides]
+skill = "otto"
+"#;
+
+    const REGISTRY: &str = r#"# Core plugins
+[registry]
+name = "core"
+
+# Skills
+[[plugins]]
+name = "otto"
+version = "0.1.0"
+description = "Old description"
+source = "https://github.com/scottidler/paii"
+path = "plugins/otto"
+type = "skill"
+tags = ["make"]
+
+[[plugins]]
+name = "aka"
+version = "0.1.0"
+source = "https://github.com/scottidler/paii"
+"#;
+
+    fn otto() -> RegistryEntry {
+        let manifest = PluginManifest::from_str(MANIFEST).unwrap();
+        entry(
+            &manifest,
+            "https://github.com/scottidler/paii".to_string(),
+            Some("plugins/otto".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_entry_from_manifest() {
+        let entry = otto();
+        assert_eq!(entry.version.as_deref(), Some("0.2.0"));
+        assert_eq!(entry.language.as_deref(), Some("mixed"));
+        assert_eq!(entry.core_version.as_deref(), Some(">=0.1.0"));
+        assert_eq!(entry.provides, vec!["skill"]);
+        assert_eq!(entry.keywords, vec!["build", "ci"]);
+        assert!(entry.kind.is_none() && entry.tags.is_empty());
+    }
+
+    #[test]
+    fn test_publish() {
+        // A new version replaces the unpinned entry, keeping comments
+        let (content, published) = publish(REGISTRY, &otto()).unwrap();
+        assert_eq!(published, Published::Replaced("0.1.0".to_string()));
+        assert!(content.starts_with("# Core plugins\n") && content.contains("# Skills\n[[plugins]]"));
+        let registry = Registry::parse(&content).unwrap();
+        assert_eq!(registry.plugins.len(), 2);
+        let entry = registry.find("otto").unwrap();
+        assert_eq!(entry.version.as_deref(), Some("0.2.0"));
+        assert_eq!(entry.description.as_deref(), Some("Otto task runner skill"));
+        assert!(entry.kind.is_none() && entry.tags.is_empty());
+        assert!(drift(entry, &otto()).is_empty());
+
+        // Publishing again is an update and changes nothing
+        let (again, published) = publish(&content, &otto()).unwrap();
+        assert_eq!(published, Published::Updated);
+        assert_eq!(again, content);
+
+        // A version pinned to a tag is added next to the others
+        let mut pinned = otto();
+        pinned.version = Some("0.3.0".to_string());
+        pinned.git_ref = Some("v0.3.0".to_string());
+        let (content, published) = publish(&content, &pinned).unwrap();
+        assert_eq!(published, Published::Added);
+        let registry = Registry::parse(&content).unwrap();
+        assert_eq!(registry.versions("otto").len(), 2);
+        assert!(content.contains("\n\n[[plugins]]\nname = \"otto\"\nversion = \"0.3.0\""));
+    }
+
+    #[test]
+    fn test_init_and_publish() {
+        let meta = RegistryMeta {
+            name: "work".to_string(),
+            ..Default::default()
+        };
+        let content = init(&meta).unwrap();
+        assert_eq!(Registry::parse(&content).unwrap().registry.name, "work");
+
+        let (content, published) = publish(&content, &otto()).unwrap();
+        assert_eq!(published, Published::Added);
+        assert_eq!(Registry::parse(&content).unwrap().plugins.len(), 1);
+    }
+
+    #[test]
+    fn test_drift() {
+        let registry = Registry::parse(REGISTRY).unwrap();
+        let mut generated = otto();
+        generated.version = Some("0.1.0".to_string());
+        let problems = drift(registry.find("otto").unwrap(), &generated);
+        assert!(problems.contains(&"description differs from plugin.toml".to_string()));
+        assert!(problems.contains(&"provides differs from plugin.toml".to_string()));
+        assert!(problems.iter().any(|p| p.starts_with("`type`")));
+        assert!(problems.iter().any(|p| p.starts_with("`tags`")));
+    }
+}
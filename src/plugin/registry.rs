@@ -1,55 +1,719 @@
 //! Plugin registry management
 //!
-//! Registries are sources of plugin metadata for discovery.
+//! Registries are sources of plugin metadata for discovery. A registry is a
+//! TOML file with a `[registry]` header and one `[[plugins]]` table per
+//! plugin:
+//!
+//! ```toml
+//! [registry]
+//! name = "paii-core"
+//! schema_version = 1
+//!
+//! [[plugins]]
+//! name = "otto"
+//! version = "0.1.0"
+//! source = "https://github.com/scottidler/paii"
+//! path = "plugins/otto"
+//! provides = ["skill"]
+//! keywords = ["build", "ci"]
+//! ```
+//!
+//! A plugin may appear once per version, each with its own `ref` and
+//! `core_version`. An entry can pin a `commit` and a content `hash`, which
+//! install verifies. Cached copies live in `paths.registries` as
+//! `<name>.toml`, with any detached signature as `<name>.toml.sig` and the
+//! fetch metadata ([`CacheMeta`]) as `<name>.toml.meta`.
 
+use chrono::{DateTime, Duration, Utc};
+use eyre::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::plugin::version;
+use semver::{Version, VersionReq};
+
+/// Newest registry schema this paii understands
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Registry header
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RegistryMeta {
+    pub name: String,
+    /// Registry format version; files without one are treated as version 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Version of the registry's contents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maintainer: Option<String>,
+}
+
+impl RegistryMeta {
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version.unwrap_or(1)
+    }
+}
 
 /// A plugin registry entry
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct RegistryEntry {
-    pub source: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-
-    #[serde(default)]
+    /// Git repository containing the plugin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Plugin directory within `source`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Git branch, tag or commit holding this version (default branch if unset)
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Git commit this version must be installed from; overrides `ref`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// `sha256:<hex>` of the plugin's files (see `paii plugin verify`);
+    /// install refuses a source that doesn't match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// paii versions this plugin version works with, e.g. `>=0.1.0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Contracts the plugin provides (`memory`, `skill`, ...)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub provides: Vec<String>,
-
-    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub keywords: Vec<String>,
 }
 
+impl RegistryEntry {
+    /// Whether the name, description, tags, keywords or provided contracts
+    /// contain `query` (case-insensitive)
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let contains = |s: &str| s.to_lowercase().contains(&query);
+        contains(&self.name)
+            || self.description.as_deref().is_some_and(contains)
+            || self
+                .tags
+                .iter()
+                .chain(&self.keywords)
+                .chain(&self.provides)
+                .any(|t| contains(t))
+    }
+
+    /// Whether the plugin provides a contract
+    pub fn provides(&self, contract: &str) -> bool {
+        self.provides.iter().any(|p| p.eq_ignore_ascii_case(contract))
+    }
+
+    /// The entry's version, if it is valid semver
+    pub fn semver(&self) -> Option<Version> {
+        self.version.as_deref().and_then(|v| version::parse_version(v).ok())
+    }
+
+    /// Whether this version satisfies `req` (any version when `None`) and
+    /// works with the running paii
+    pub fn compatible(&self, req: Option<&VersionReq>) -> bool {
+        let core_ok = version::core_satisfied(self.core_version.as_deref()).unwrap_or(false);
+        let req_ok = match req {
+            Some(req) => self.semver().is_some_and(|v| req.matches(&v)),
+            None => true,
+        };
+        core_ok && req_ok
+    }
+}
+
 /// A plugin registry
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Registry {
     #[serde(default)]
-    pub plugins: HashMap<String, RegistryEntry>,
+    pub registry: RegistryMeta,
+    #[serde(default)]
+    pub plugins: Vec<RegistryEntry>,
 }
 
 impl Registry {
-    /// Load a registry from a TOML file or URL
-    pub fn load(source: &str) -> eyre::Result<Self> {
-        // TODO: Handle URL sources (git repos, HTTP)
-        let content = std::fs::read_to_string(source)?;
-        let registry: Self = toml::from_str(&content)?;
+    /// Parse registry TOML, rejecting schema versions this paii doesn't know
+    pub fn parse(content: &str) -> Result<Self> {
+        let registry: Self = toml::from_str(content).context("Invalid registry TOML")?;
+        let version = registry.registry.schema_version();
+        if version == 0 || version > SCHEMA_VERSION {
+            eyre::bail!(
+                "Unsupported registry schema_version {} (this paii supports up to {}); upgrade paii",
+                version,
+                SCHEMA_VERSION
+            );
+        }
         Ok(registry)
     }
 
+    /// Parse a registry fetched from `url`, refusing bodies that aren't
+    /// registry TOML, such as the web page GitHub serves for a file
+    pub fn parse_fetched(content: &str, url: &str) -> Result<Self> {
+        let start: String = content
+            .trim_start()
+            .chars()
+            .take(512)
+            .collect::<String>()
+            .to_lowercase();
+        if start.starts_with("<!doctype html") || start.starts_with("<html") || start.contains("<head") {
+            eyre::bail!("{} returned an HTML page, not registry TOML{}", url, url_hint(url));
+        }
+        let table: toml::Table = toml::from_str(content)
+            .map_err(|e| eyre::eyre!("{} did not return TOML{}: {}", url, url_hint(url), e.message()))?;
+        if !table.contains_key("registry") && !table.contains_key("plugins") {
+            eyre::bail!("{} is TOML but not a registry (no [registry] or [[plugins]])", url);
+        }
+        Self::parse(content).wrap_err(format!("Invalid registry at {}", url))
+    }
+
+    /// Load a registry from a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+        Self::parse(&content).context(format!("Failed to parse registry {}", path.display()))
+    }
+
+    /// Every listed version of a plugin, newest first
+    pub fn versions(&self, name: &str) -> Vec<&RegistryEntry> {
+        let mut versions: Vec<&RegistryEntry> = self.plugins.iter().filter(|p| p.name == name).collect();
+        versions.sort_by_key(|p| std::cmp::Reverse(p.semver()));
+        versions
+    }
+
+    /// Look up a plugin by exact name, newest version first
+    pub fn find(&self, name: &str) -> Option<&RegistryEntry> {
+        self.versions(name).into_iter().next()
+    }
+
+    /// The newest version of a plugin compatible with `req` and this paii
+    pub fn best(&self, name: &str, req: Option<&VersionReq>) -> Option<&RegistryEntry> {
+        self.versions(name).into_iter().find(|p| p.compatible(req))
+    }
+
     /// Search for plugins matching a query
-    pub fn search(&self, query: &str) -> Vec<(&str, &RegistryEntry)> {
-        let query_lower = query.to_lowercase();
-        self.plugins
-            .iter()
-            .filter(|(name, entry)| {
-                name.to_lowercase().contains(&query_lower)
-                    || entry
-                        .description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&query_lower))
-                        .unwrap_or(false)
-                    || entry.keywords.iter().any(|k| k.to_lowercase().contains(&query_lower))
-            })
-            .map(|(name, entry)| (name.as_str(), entry))
-            .collect()
+    pub fn search(&self, query: &str) -> Vec<&RegistryEntry> {
+        self.plugins.iter().filter(|p| p.matches(query)).collect()
+    }
+
+    /// Plugins that provide a contract
+    pub fn providing(&self, contract: &str) -> Vec<&RegistryEntry> {
+        self.plugins.iter().filter(|p| p.provides(contract)).collect()
+    }
+}
+
+/// A registry from the local cache
+#[derive(Debug, Clone)]
+pub struct CachedRegistry {
+    /// Name the registry is configured under (the cache file stem)
+    pub name: String,
+    pub registry: Registry,
+}
+
+/// Load every cached registry in `dir`, sorted by name.
+///
+/// Files that fail to load are returned as errors alongside the rest so
+/// callers can warn about them without losing the good ones.
+pub fn load_cached(dir: &Path) -> (Vec<CachedRegistry>, Vec<(String, eyre::Report)>) {
+    let mut registries = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        match Registry::load(&path) {
+            Ok(registry) => registries.push(CachedRegistry { name, registry }),
+            Err(e) => errors.push((name, e)),
+        }
+    }
+
+    (registries, errors)
+}
+
+/// Suggest a URL that serves the file itself for a GitHub page URL
+fn url_hint(url: &str) -> String {
+    let Some(rest) = url.strip_prefix("https://github.com/") else {
+        return String::new();
+    };
+    let parts: Vec<&str> = rest.splitn(5, '/').collect();
+    match parts.as_slice() {
+        [owner, repo, "blob", git_ref, path] => format!(
+            " (use https://raw.githubusercontent.com/{}/{}/{}/{})",
+            owner, repo, git_ref, path
+        ),
+        [owner, repo, first, more @ ..] if !first.is_empty() => {
+            let path = std::iter::once(*first)
+                .chain(more.iter().copied())
+                .collect::<Vec<_>>()
+                .join("/");
+            format!(" (use git+https://github.com/{}/{}.git:{})", owner, repo, path)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Order registries by priority: names in `order` first, in that order,
+/// then everything else by name
+pub fn prioritize(registries: &mut [CachedRegistry], order: &[String]) {
+    registries.sort_by_key(|r| {
+        let rank = order.iter().position(|n| *n == r.name).unwrap_or(order.len());
+        (rank, r.name.clone())
+    });
+}
+
+/// `<cache file>.<ext>`: the signature (`sig`) and metadata (`meta`) kept
+/// next to a cached registry
+pub fn sidecar(cache_file: &Path, ext: &str) -> PathBuf {
+    let mut path = cache_file.as_os_str().to_owned();
+    path.push(format!(".{}", ext));
+    PathBuf::from(path)
+}
+
+/// When and how a cached registry was fetched, stored as `<name>.toml.meta`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CacheMeta {
+    /// URL the cache was fetched from
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Validators for conditional requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Commit a `git+` registry was read at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+impl CacheMeta {
+    /// Metadata for a cache file; `None` if missing or unreadable
+    pub fn load(cache_file: &Path) -> Option<Self> {
+        let content = fs::read_to_string(sidecar(cache_file, "meta")).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    pub fn save(&self, cache_file: &Path) -> Result<()> {
+        let path = sidecar(cache_file, "meta");
+        fs::write(&path, toml::to_string_pretty(self)?).context(format!("Failed to write {}", path.display()))
+    }
+
+    /// Time since the cache was fetched
+    pub fn age(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.fetched_at.map(|at| now - at)
+    }
+
+    /// Older than `max_age`, or of unknown age
+    pub fn is_stale(&self, max_age: Duration, now: DateTime<Utc>) -> bool {
+        self.age(now).is_none_or(|age| age > max_age)
+    }
+}
+
+/// Parse a cache age such as `30m`, `12h`, `7d` or `0` (always refresh)
+pub fn parse_max_age(max_age: &str) -> Result<Duration> {
+    let max_age = max_age.trim();
+    if max_age == "0" {
+        return Ok(Duration::zero());
+    }
+    let split = max_age.find(|c: char| !c.is_ascii_digit()).unwrap_or(max_age.len());
+    let (number, unit) = max_age.split_at(split);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid max age '{}' (expected e.g. 30m, 12h, 7d)", max_age))?;
+    match unit {
+        "s" => Ok(Duration::seconds(number)),
+        "m" => Ok(Duration::minutes(number)),
+        "h" => Ok(Duration::hours(number)),
+        "d" => Ok(Duration::days(number)),
+        "w" => Ok(Duration::weeks(number)),
+        _ => eyre::bail!("Invalid max age '{}' (expected a unit: s, m, h, d or w)", max_age),
+    }
+}
+
+/// `5m ago`, `3h ago`, `2d ago`
+pub fn format_age(age: Duration) -> String {
+    if age < Duration::minutes(1) {
+        "just now".to_string()
+    } else if age < Duration::hours(1) {
+        format!("{}m ago", age.num_minutes())
+    } else if age < Duration::days(1) {
+        format!("{}h ago", age.num_hours())
+    } else {
+        format!("{}d ago", age.num_days())
+    }
+}
+
+/// Split `registry:name` into its parts; a bare name has no registry
+pub fn parse_spec(spec: &str) -> (Option<&str>, &str) {
+    match spec.split_once(':') {
+        Some((registry, name)) if !registry.is_empty() => (Some(registry), name),
+        _ => (None, spec),
+    }
+}
+
+/// A plugin name resolved against prioritized registries
+#[derive(Debug)]
+pub struct Resolved<'a> {
+    /// Registry the plugin comes from
+    pub registry: &'a str,
+    pub entry: &'a RegistryEntry,
+    /// Other registries that also list the name
+    pub shadowed: Vec<&'a str>,
+    /// Version constraint from the spec
+    pub req: Option<VersionReq>,
+}
+
+/// Find a plugin by `name` or `registry:name`, optionally followed by
+/// `@constraint`, in registries already in priority order.
+///
+/// The first registry listing the name wins, and within it the newest
+/// version compatible with the constraint and the running paii. When it has
+/// no such version, resolution fails rather than falling through to a
+/// lower-priority registry.
+pub fn resolve<'a>(registries: &'a [CachedRegistry], spec: &str) -> Result<Resolved<'a>> {
+    let (spec, req) = version::split_spec(spec)?;
+    let (registry, name) = parse_spec(spec);
+
+    let candidates: Vec<&CachedRegistry> = match registry {
+        Some(registry) => vec![registries.iter().find(|r| r.name == registry).ok_or_else(|| {
+            eyre::eyre!(
+                "Registry '{}' is not cached. Run 'paii registry update' first.",
+                registry
+            )
+        })?],
+        None => registries.iter().collect(),
+    };
+    let listing: Vec<&CachedRegistry> = candidates
+        .into_iter()
+        .filter(|r| r.registry.find(name).is_some())
+        .collect();
+
+    if listing.is_empty() {
+        match registry {
+            Some(registry) => eyre::bail!("Plugin '{}' not found in registry '{}'", name, registry),
+            None => eyre::bail!(
+                "Plugin '{}' not found in any registry.\n\
+                 Hint: Run 'paii registry search {}' to find similar plugins.",
+                name,
+                name
+            ),
+        }
+    }
+
+    // Lower-priority registries never stand in for the first one listing the
+    // name; picking one of them takes an explicit `registry:name`
+    let cached = listing[0];
+    let shadowed: Vec<&str> = listing[1..].iter().map(|r| r.name.as_str()).collect();
+    if let Some(entry) = cached.registry.best(name, req.as_ref()) {
+        return Ok(Resolved {
+            registry: &cached.name,
+            entry,
+            shadowed,
+            req,
+        });
+    }
+
+    let available: Vec<String> = cached
+        .registry
+        .versions(name)
+        .into_iter()
+        .map(|p| {
+            let core = p
+                .core_version
+                .as_deref()
+                .map(|c| format!(", needs paii {}", c))
+                .unwrap_or_default();
+            format!("{}{}", p.version.as_deref().unwrap_or("?"), core)
+        })
+        .collect();
+    let others = match shadowed.first() {
+        Some(other) => format!(
+            "\nHint: '{}' is also in {}; install {}:{} to use another registry.",
+            name,
+            shadowed.join(", "),
+            other,
+            name
+        ),
+        None => String::new(),
+    };
+    eyre::bail!(
+        "No version of '{}' in registry '{}' matches {} with paii {}. Available: {}{}",
+        name,
+        cached.name,
+        req.map(|r| r.to_string()).unwrap_or_else(|| "*".to_string()),
+        version::paii_version(),
+        available.join(", "),
+        others
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = r#"
+[registry]
+name = "test"
+schema_version = 1
+
+[[plugins]]
+name = "jira"
+version = "1.0.0"
+description = "Jira integration"
+source = "https://example.com/jira"
+type = "integration"
+provides = ["memory"]
+tags = ["tickets"]
+
+[[plugins]]
+name = "otto"
+description = "Task runner skill"
+provides = ["skill"]
+keywords = ["build"]
+"#;
+
+    #[test]
+    fn test_parse_and_search() {
+        let registry = Registry::parse(REGISTRY).unwrap();
+        assert_eq!(registry.registry.schema_version(), 1);
+        assert_eq!(registry.find("jira").unwrap().kind.as_deref(), Some("integration"));
+        assert!(registry.find("missing").is_none());
+
+        let names = |entries: Vec<&RegistryEntry>| entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(registry.search("TICKET")), vec!["jira"]);
+        assert_eq!(names(registry.search("build")), vec!["otto"]);
+        assert_eq!(names(registry.providing("Skill")), vec!["otto"]);
+        assert!(registry.providing("history").is_empty());
+    }
+
+    #[test]
+    fn test_schema_version() {
+        // Legacy files without a version are version 1
+        let legacy = Registry::parse("[registry]\nname = \"old\"\n").unwrap();
+        assert_eq!(legacy.registry.schema_version(), 1);
+
+        let err = Registry::parse("[registry]\nname = \"new\"\nschema_version = 99\n").unwrap_err();
+        assert!(err.to_string().contains("schema_version 99"));
+    }
+
+    #[test]
+    fn test_load_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("b.toml"), REGISTRY).unwrap();
+        fs::write(tmp.path().join("a.toml"), "[registry]\nname = \"a\"\n").unwrap();
+        fs::write(tmp.path().join("bad.toml"), "<html>").unwrap();
+        fs::write(tmp.path().join("notes.txt"), "ignored").unwrap();
+
+        let (registries, errors) = load_cached(tmp.path());
+        assert_eq!(
+            registries.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "bad");
+    }
+
+    fn cached(name: &str, plugins: &[&str]) -> CachedRegistry {
+        CachedRegistry {
+            name: name.to_string(),
+            registry: Registry {
+                plugins: plugins
+                    .iter()
+                    .map(|p| RegistryEntry {
+                        name: p.to_string(),
+                        source: Some(format!("https://{}.example/{}", name, p)),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_resolve_by_priority() {
+        let mut registries = vec![
+            cached("core", &["otto", "incident"]),
+            cached("community", &["incident"]),
+            cached("work", &["incident"]),
+        ];
+        prioritize(&mut registries, &["work".to_string()]);
+        assert_eq!(
+            registries.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            vec!["work", "community", "core"]
+        );
+
+        let resolved = resolve(&registries, "incident").unwrap();
+        assert_eq!(resolved.registry, "work");
+        assert_eq!(resolved.shadowed, vec!["community", "core"]);
+        assert!(resolved.req.is_none());
+
+        let resolved = resolve(&registries, "core:incident").unwrap();
+        assert_eq!(resolved.registry, "core");
+        assert_eq!(resolved.entry.source.as_deref(), Some("https://core.example/incident"));
+        assert!(resolved.shadowed.is_empty());
+
+        assert!(resolve(&registries, "otto").unwrap().shadowed.is_empty());
+        assert!(resolve(&registries, "work:otto").is_err());
+        assert!(resolve(&registries, "nope:otto").is_err());
+        assert!(resolve(&registries, "missing").is_err());
+    }
+
+    fn versioned(name: &str, versions: &[(&str, Option<&str>)]) -> CachedRegistry {
+        CachedRegistry {
+            name: name.to_string(),
+            registry: Registry {
+                plugins: versions
+                    .iter()
+                    .map(|(v, core)| RegistryEntry {
+                        name: "incident".to_string(),
+                        version: Some(v.to_string()),
+                        git_ref: Some(format!("v{}", v)),
+                        core_version: core.map(str::to_string),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_resolve_versions() {
+        let registries = vec![
+            versioned("work", &[("1.0.0", None)]),
+            versioned(
+                "core",
+                &[
+                    ("1.2.0", None),
+                    ("2.0.0", Some(">=99.0.0")),
+                    ("1.10.1", None),
+                    ("0.9.0", None),
+                ],
+            ),
+        ];
+
+        // Newest version compatible with this paii
+        assert_eq!(
+            registries[1].registry.versions("incident")[0].version.as_deref(),
+            Some("2.0.0")
+        );
+        let best = registries[1].registry.best("incident", None).unwrap();
+        assert_eq!(best.version.as_deref(), Some("1.10.1"));
+        assert_eq!(best.git_ref.as_deref(), Some("v1.10.1"));
+
+        // A constraint the top registry can't meet doesn't fall through to the next
+        let err = resolve(&registries, "incident@^1.2").unwrap_err().to_string();
+        assert!(err.contains("No version of 'incident' in registry 'work' matches ^1.2"));
+        assert!(err.contains("Available: 1.0.0"));
+        assert!(err.contains("install core:incident"));
+
+        let resolved = resolve(&registries, "core:incident@^1.2").unwrap();
+        assert_eq!(resolved.registry, "core");
+        assert_eq!(resolved.entry.version.as_deref(), Some("1.10.1"));
+        assert!(resolved.shadowed.is_empty());
+        assert_eq!(resolved.req.unwrap().to_string(), "^1.2");
+
+        let resolved = resolve(&registries, "core:incident@~1.2").unwrap();
+        assert_eq!(resolved.entry.version.as_deref(), Some("1.2.0"));
+
+        let err = resolve(&registries, "core:incident@^2").unwrap_err().to_string();
+        assert!(err.contains("No version of 'incident' in registry 'core' matches ^2"));
+        assert!(err.contains("2.0.0, needs paii >=99.0.0"));
+    }
+
+    #[test]
+    fn test_bundled_registry_parses() {
+        let registry = Registry::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("registry/plugins.toml")).unwrap();
+        assert_eq!(registry.registry.schema_version(), SCHEMA_VERSION);
+        assert!(registry.find("otto").is_some());
+    }
+
+    #[test]
+    fn test_cache_meta() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_file = tmp.path().join("core.toml");
+        assert!(CacheMeta::load(&cache_file).is_none());
+
+        let now = Utc::now();
+        let meta = CacheMeta {
+            url: "https://example.com/plugins.toml".to_string(),
+            fetched_at: Some(now - Duration::hours(3)),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            commit: None,
+        };
+        meta.save(&cache_file).unwrap();
+        assert_eq!(CacheMeta::load(&cache_file), Some(meta.clone()));
+        assert!(tmp.path().join("core.toml.meta").exists());
+
+        assert!(meta.is_stale(Duration::hours(1), now));
+        assert!(!meta.is_stale(Duration::days(1), now));
+        assert!(CacheMeta::default().is_stale(Duration::days(1), now));
+        assert_eq!(format_age(meta.age(now).unwrap()), "3h ago");
+
+        // Metadata files aren't registries
+        let (registries, errors) = load_cached(tmp.path());
+        assert!(registries.is_empty() && errors.is_empty());
+    }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(parse_max_age("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_max_age("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_max_age("0").unwrap(), Duration::zero());
+        assert!(parse_max_age("7").is_err());
+        assert!(parse_max_age("d").is_err());
+        assert!(parse_max_age("1y").is_err());
+    }
+
+    #[test]
+    fn test_parse_fetched() {
+        let url = "https://github.com/scottidler/paii/registry/plugins.toml";
+        let err = Registry::parse_fetched("<!DOCTYPE html>\n<html lang=\"en\">", url).unwrap_err();
+        assert!(err.to_string().contains("HTML page"));
+        assert!(
+            err.to_string()
+                .contains("git+https://github.com/scottidler/paii.git:registry/plugins.toml")
+        );
+
+        let blob = "https://github.com/owner/repo/blob/main/registry.toml";
+        let err = Registry::parse_fetched("<html><head>", blob).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("https://raw.githubusercontent.com/owner/repo/main/registry.toml")
+        );
+
+        assert!(Registry::parse_fetched("not = [toml", "https://example.com/r.toml").is_err());
+        assert!(Registry::parse_fetched("title = \"a blog post\"", "https://example.com/r.toml").is_err());
+        assert!(Registry::parse_fetched("[registry]\nname = \"core\"\n", "https://example.com/r.toml").is_ok());
     }
 }
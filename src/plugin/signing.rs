@@ -0,0 +1,128 @@
+//! Registry signatures
+//!
+//! A registry can ship a detached ed25519 signature next to it: `<url>.sig`
+//! holds the base64 signature over the registry file's exact bytes. paii
+//! checks it against the public keys under `[trust.keys]` in paii.toml.
+//!
+//! Keys are plain strings, so the whole flow works offline:
+//!
+//! ```text
+//! paii registry keygen --output registry.key    # prints ed25519:<public key>
+//! paii registry sign plugins.toml --key registry.key
+//! paii registry verify plugins.toml
+//! ```
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+
+/// Prefix of a public key string
+const PUBLIC_PREFIX: &str = "ed25519:";
+
+/// Prefix of a secret key string
+const SECRET_PREFIX: &str = "ed25519-secret:";
+
+/// Extension of a detached signature, appended to the registry file name or URL
+pub const SIGNATURE_EXT: &str = "sig";
+
+/// Generate a key pair, returned as `(secret, public)` key strings
+pub fn generate() -> Result<(String, String)> {
+    let mut seed = [0u8; 32];
+    getrandom::fill(&mut seed).map_err(|e| eyre::eyre!("Failed to gather randomness: {}", e))?;
+    let key = SigningKey::from_bytes(&seed);
+    Ok((
+        format!("{}{}", SECRET_PREFIX, STANDARD.encode(key.to_bytes())),
+        encode_public(&key.verifying_key()),
+    ))
+}
+
+/// The public key string for a secret key string
+pub fn public_key(secret: &str) -> Result<String> {
+    Ok(encode_public(&parse_secret(secret)?.verifying_key()))
+}
+
+/// Sign `content`, returning the base64 signature
+pub fn sign(content: &[u8], secret: &str) -> Result<String> {
+    let signature = parse_secret(secret)?.sign(content);
+    Ok(STANDARD.encode(signature.to_bytes()))
+}
+
+/// Check a base64 signature over `content` against the trusted keys,
+/// returning the name of the key that made it
+pub fn verify(content: &[u8], signature: &str, trusted: &BTreeMap<String, String>) -> Result<String> {
+    let bytes = STANDARD
+        .decode(signature.trim())
+        .context("Signature is not valid base64")?;
+    let signature = Signature::from_slice(&bytes).map_err(|_| eyre::eyre!("Signature is not an ed25519 signature"))?;
+
+    for (name, key) in trusted {
+        let key = parse_public(key).wrap_err(format!("Trusted key '{}'", name))?;
+        if key.verify(content, &signature).is_ok() {
+            return Ok(name.clone());
+        }
+    }
+    eyre::bail!("signature does not match any trusted key")
+}
+
+fn encode_public(key: &VerifyingKey) -> String {
+    format!("{}{}", PUBLIC_PREFIX, STANDARD.encode(key.to_bytes()))
+}
+
+fn parse_public(key: &str) -> Result<VerifyingKey> {
+    let bytes = decode_key(key, PUBLIC_PREFIX)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| eyre::eyre!("Not a valid ed25519 public key"))
+}
+
+fn parse_secret(secret: &str) -> Result<SigningKey> {
+    Ok(SigningKey::from_bytes(&decode_key(secret, SECRET_PREFIX)?))
+}
+
+fn decode_key(key: &str, prefix: &str) -> Result<[u8; 32]> {
+    let encoded = key
+        .trim()
+        .strip_prefix(prefix)
+        .ok_or_else(|| eyre::eyre!("Key must start with '{}'", prefix))?;
+    let bytes = STANDARD.decode(encoded).context("Key is not valid base64")?;
+    bytes.try_into().map_err(|_| eyre::eyre!("Key must be 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (secret, public) = generate().unwrap();
+        assert!(public.starts_with("ed25519:"));
+        assert_eq!(public_key(&secret).unwrap(), public);
+
+        let (_, other) = generate().unwrap();
+        let mut trusted = BTreeMap::new();
+        trusted.insert("other".to_string(), other);
+        trusted.insert("scott".to_string(), public);
+
+        let content = b"[registry]\nname = \"core\"\n";
+        let signature = sign(content, &secret).unwrap();
+        assert_eq!(verify(content, &signature, &trusted).unwrap(), "scott");
+
+        // Tampered content or an untrusted signer fails
+        assert!(verify(b"[registry]\nname = \"evil\"\n", &signature, &trusted).is_err());
+        trusted.remove("scott");
+        assert!(verify(content, &signature, &trusted).is_err());
+        assert!(verify(content, "not base64!", &trusted).is_err());
+    }
+
+    #[test]
+    fn test_bad_keys() {
+        assert!(public_key("ed25519:AAAA").is_err());
+        assert!(public_key("rsa:AAAA").is_err());
+
+        let (secret, _) = generate().unwrap();
+        let signature = sign(b"x", &secret).unwrap();
+        let mut trusted = BTreeMap::new();
+        trusted.insert("broken".to_string(), "ed25519:short".to_string());
+        assert!(verify(b"x", &signature, &trusted).is_err());
+    }
+}
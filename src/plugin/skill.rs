@@ -0,0 +1,423 @@
+//! Claude Code skills provided by plugins
+//!
+//! A plugin provides a skill by shipping a `SKILL.md` at its root. On
+//! install the skill is synced into `<scope>/.claude/skills/<name>/` along
+//! with the files SKILL.md links to and any `workflows/` directory. A
+//! `.paii-plugin` marker records which plugin owns the synced directory, so
+//! remove only touches skills paii installed and collisions with hand-written
+//! skills (or another plugin's) are caught before anything is overwritten.
+
+use eyre::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::ClaudeScope;
+
+/// Skill definition file
+pub const SKILL_FILE: &str = "SKILL.md";
+
+/// Marker naming the plugin that owns a synced skill directory
+pub const MARKER_FILE: &str = ".paii-plugin";
+
+/// Directory of workflow files synced alongside SKILL.md
+const WORKFLOWS_DIR: &str = "workflows";
+
+const MAX_NAME_LEN: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 1024;
+
+static LINK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\]\(([^)\s]+)\)").unwrap());
+
+/// SKILL.md frontmatter
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SkillFrontmatter {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, rename = "allowed-tools", skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<String>,
+}
+
+impl SkillFrontmatter {
+    /// Parse the YAML frontmatter at the top of a SKILL.md
+    pub fn parse(content: &str) -> Result<Self> {
+        parse_frontmatter(content)
+    }
+
+    /// Check the fields Claude Code requires
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if self.name.is_empty() {
+            problems.push("missing `name`".to_string());
+        } else if !is_valid_name(&self.name) {
+            problems.push(format!(
+                "`name` '{}' must be lowercase letters, numbers and hyphens (max {} characters)",
+                self.name, MAX_NAME_LEN
+            ));
+        }
+
+        if self.description.trim().is_empty() {
+            problems.push("missing `description`".to_string());
+        } else if self.description.chars().count() > MAX_DESCRIPTION_LEN {
+            problems.push(format!("`description` exceeds {} characters", MAX_DESCRIPTION_LEN));
+        }
+
+        if !problems.is_empty() {
+            eyre::bail!("{}", problems.join("; "));
+        }
+        Ok(())
+    }
+}
+
+/// Parse the YAML frontmatter at the top of a Markdown file
+pub fn parse_frontmatter<T: DeserializeOwned>(content: &str) -> Result<T> {
+    let rest = content
+        .trim_start_matches('\u{feff}')
+        .strip_prefix("---")
+        .ok_or_else(|| eyre::eyre!("missing frontmatter (file must start with ---)"))?;
+    let end = rest
+        .find("\n---")
+        .ok_or_else(|| eyre::eyre!("unterminated frontmatter (no closing ---)"))?;
+
+    serde_yaml::from_str(&rest[..end]).context("invalid frontmatter YAML")
+}
+
+/// Whether a name is lowercase letters, numbers and hyphens
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// A skill shipped by a plugin
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub frontmatter: SkillFrontmatter,
+    /// Directory containing SKILL.md
+    pub dir: PathBuf,
+    /// Supporting files and directories, relative to `dir`
+    pub files: Vec<PathBuf>,
+}
+
+impl Skill {
+    /// Load and validate the skill in a plugin directory, if it has one
+    pub fn load(plugin_dir: &Path) -> Result<Option<Self>> {
+        let skill_md = plugin_dir.join(SKILL_FILE);
+        if !skill_md.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&skill_md).context(format!("Failed to read {}", skill_md.display()))?;
+        let frontmatter = SkillFrontmatter::parse(&content)
+            .and_then(|fm| fm.validate().map(|_| fm))
+            .context(format!("Invalid {}", skill_md.display()))?;
+
+        Ok(Some(Self {
+            frontmatter,
+            dir: plugin_dir.to_path_buf(),
+            files: referenced_files(plugin_dir, &content),
+        }))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.frontmatter.name
+    }
+}
+
+/// Relative links in SKILL.md that resolve inside the skill directory,
+/// plus `workflows/` when present
+fn referenced_files(dir: &Path, content: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = LINK_RE
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let target = cap[1].split('#').next().unwrap_or_default();
+            let path = Path::new(target);
+            let inside = path
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            (!target.is_empty() && !target.contains("://") && inside && dir.join(path).exists())
+                .then(|| path.components().collect())
+        })
+        .collect();
+
+    if dir.join(WORKFLOWS_DIR).is_dir() {
+        files.push(PathBuf::from(WORKFLOWS_DIR));
+    }
+
+    files.sort();
+    files.dedup();
+    // A file inside an already-synced directory comes along with it
+    let dirs: Vec<PathBuf> = files.iter().filter(|f| dir.join(f).is_dir()).cloned().collect();
+    files.retain(|f| !dirs.iter().any(|d| f != d && f.starts_with(d)));
+    files
+}
+
+/// Owner of an existing skill directory: `Some(plugin)` for paii-managed
+/// skills, `None` for skills written by hand
+pub fn owner(skill_dir: &Path) -> Option<String> {
+    fs::read_to_string(skill_dir.join(MARKER_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Make sure syncing `skill` for `plugin` won't clobber someone else's skill
+pub fn check_collision(skill: &Skill, plugin: &str, skills_dir: &Path) -> Result<()> {
+    let target = skills_dir.join(skill.name());
+    if !target.exists() && target.symlink_metadata().is_err() {
+        return Ok(());
+    }
+
+    match owner(&target) {
+        Some(owner) if owner == plugin => Ok(()),
+        Some(owner) => eyre::bail!(
+            "Skill '{}' is already provided by plugin '{}' ({})",
+            skill.name(),
+            owner,
+            target.display()
+        ),
+        None => eyre::bail!(
+            "Skill '{}' already exists at {} and is not managed by paii",
+            skill.name(),
+            target.display()
+        ),
+    }
+}
+
+/// Sync a plugin's skill into a skills directory.
+///
+/// With `link`, files are symlinked (dev installs) so edits show up without a
+/// re-sync; otherwise they are copied. Returns the synced skill directory.
+pub fn sync(skill: &Skill, plugin: &str, skills_dir: &Path, link: bool) -> Result<PathBuf> {
+    let target = skills_dir.join(skill.name());
+    if target.symlink_metadata().is_ok() {
+        remove_path(&target)?;
+    }
+    fs::create_dir_all(&target).context(format!("Failed to create {}", target.display()))?;
+
+    for rel in std::iter::once(Path::new(SKILL_FILE)).chain(skill.files.iter().map(PathBuf::as_path)) {
+        let src = skill.dir.join(rel);
+        let dst = target.join(rel);
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if link {
+            symlink(&fs::canonicalize(&src)?, &dst)?;
+        } else if src.is_dir() {
+            copy_dir(&src, &dst)?;
+        } else {
+            fs::copy(&src, &dst).context(format!("Failed to copy {}", src.display()))?;
+        }
+    }
+
+    fs::write(target.join(MARKER_FILE), format!("{}\n", plugin))?;
+    Ok(target)
+}
+
+/// Remove every skill a plugin synced into a skills directory
+pub fn unsync(plugin: &str, skills_dir: &Path) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+    if !skills_dir.is_dir() {
+        return Ok(removed);
+    }
+
+    for entry in fs::read_dir(skills_dir)? {
+        let path = entry?.path();
+        if owner(&path).as_deref() == Some(plugin) {
+            fs::remove_dir_all(&path).context(format!("Failed to remove {}", path.display()))?;
+            removed.push(path.file_name().unwrap_or_default().to_string_lossy().to_string());
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+/// A skill as Claude Code sees it in a skills directory
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledSkill {
+    pub name: String,
+    pub description: String,
+    pub scope: ClaudeScope,
+    pub path: PathBuf,
+    /// Plugin that synced it, if paii manages it
+    pub plugin: Option<String>,
+    /// Frontmatter problem, if any
+    pub error: Option<String>,
+}
+
+/// Scan a skills directory
+pub fn installed(skills_dir: &Path, scope: ClaudeScope) -> Vec<InstalledSkill> {
+    let Ok(entries) = fs::read_dir(skills_dir) else {
+        return Vec::new();
+    };
+
+    let mut skills: Vec<InstalledSkill> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|path| path.join(SKILL_FILE).exists())
+        .map(|path| {
+            let dir_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let parsed = fs::read_to_string(path.join(SKILL_FILE))
+                .map_err(eyre::Report::from)
+                .and_then(|content| SkillFrontmatter::parse(&content))
+                .and_then(|fm| fm.validate().map(|_| fm));
+
+            let (name, description, error) = match parsed {
+                Ok(fm) => (fm.name, fm.description, None),
+                Err(e) => (dir_name, String::new(), Some(format!("{:#}", e))),
+            };
+            InstalledSkill {
+                name,
+                description,
+                scope,
+                plugin: owner(&path),
+                path,
+                error,
+            }
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    if path.symlink_metadata()?.file_type().is_symlink() || path.is_file() {
+        fs::remove_file(path)?;
+    } else {
+        fs::remove_dir_all(path)?;
+    }
+    Ok(())
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let (from, to) = (entry.path(), dst.join(entry.file_name()));
+        if from.is_dir() {
+            copy_dir(&from, &to)?;
+        } else {
+            fs::copy(&from, &to)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn symlink(src: &Path, dst: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(src, dst).context(format!("Failed to link {}", dst.display()))
+}
+
+#[cfg(not(unix))]
+pub fn symlink(_src: &Path, _dst: &Path) -> Result<()> {
+    eyre::bail!("Dev mode (symlinks) not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, frontmatter: &str, body: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(SKILL_FILE), format!("---\n{}\n---\n\n{}", frontmatter, body)).unwrap();
+    }
+
+    #[test]
+    fn test_frontmatter_validation() {
+        let ok = SkillFrontmatter::parse("---\nname: rust-coder\ndescription: Write Rust.\n---\n# Body").unwrap();
+        assert!(ok.validate().is_ok());
+
+        let bad = SkillFrontmatter::parse("---\nname: Rust_Coder\n---\n").unwrap();
+        let err = bad.validate().unwrap_err().to_string();
+        assert!(err.contains("lowercase"));
+        assert!(err.contains("missing `description`"));
+
+        assert!(SkillFrontmatter::parse("# No frontmatter").is_err());
+    }
+
+    #[test]
+    fn test_sync_copies_referenced_files_and_unsyncs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plugin = tmp.path().join("plugin");
+        write_skill(
+            &plugin,
+            "name: deploy\ndescription: Deploy things. Use when shipping.",
+            "See [the checklist](docs/checklist.md) and [site](https://example.com).",
+        );
+        fs::create_dir_all(plugin.join("docs")).unwrap();
+        fs::write(plugin.join("docs/checklist.md"), "- [ ] ship").unwrap();
+        fs::create_dir_all(plugin.join("workflows")).unwrap();
+        fs::write(plugin.join("workflows/release.md"), "release").unwrap();
+        fs::write(plugin.join("plugin.toml"), "").unwrap();
+
+        let skill = Skill::load(&plugin).unwrap().unwrap();
+        assert_eq!(
+            skill.files,
+            vec![PathBuf::from("docs/checklist.md"), PathBuf::from("workflows")]
+        );
+
+        let skills_dir = tmp.path().join("skills");
+        let target = sync(&skill, "deploy-plugin", &skills_dir, false).unwrap();
+        assert!(target.join("docs/checklist.md").exists());
+        assert!(target.join("workflows/release.md").exists());
+        assert!(!target.join("plugin.toml").exists());
+
+        let listed = installed(&skills_dir, ClaudeScope::User);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].plugin.as_deref(), Some("deploy-plugin"));
+
+        assert_eq!(unsync("other", &skills_dir).unwrap(), Vec::<String>::new());
+        assert_eq!(unsync("deploy-plugin", &skills_dir).unwrap(), vec!["deploy"]);
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_bundled_plugins_have_valid_skills() {
+        let plugins = Path::new(env!("CARGO_MANIFEST_DIR")).join("plugins");
+        for entry in fs::read_dir(plugins).unwrap() {
+            let dir = entry.unwrap().path();
+            crate::plugin::loader::load_plugin(&dir).unwrap();
+            assert!(
+                Skill::load(&dir).unwrap().is_some(),
+                "{} has no SKILL.md",
+                dir.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_collisions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        let plugin = tmp.path().join("plugin");
+        write_skill(&plugin, "name: notes\ndescription: Take notes.", "");
+        let skill = Skill::load(&plugin).unwrap().unwrap();
+
+        // Hand-written skill with the same name
+        write_skill(&skills_dir.join("notes"), "name: notes\ndescription: Mine.", "");
+        assert!(
+            check_collision(&skill, "notes", &skills_dir)
+                .unwrap_err()
+                .to_string()
+                .contains("not managed by paii")
+        );
+
+        // Another plugin's skill
+        fs::write(skills_dir.join("notes").join(MARKER_FILE), "other\n").unwrap();
+        assert!(
+            check_collision(&skill, "notes", &skills_dir)
+                .unwrap_err()
+                .to_string()
+                .contains("plugin 'other'")
+        );
+
+        // Our own skill can be re-synced
+        fs::write(skills_dir.join("notes").join(MARKER_FILE), "notes\n").unwrap();
+        assert!(check_collision(&skill, "notes", &skills_dir).is_ok());
+    }
+}
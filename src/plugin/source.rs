@@ -0,0 +1,248 @@
+//! Install sources
+//!
+//! `paii plugin install` takes a local plugin directory, a registry spec
+//! (`[registry:]name[@version]`) or a git source:
+//!
+//! ```text
+//! https://github.com/owner/repo.git:plugins/otto#v1.2.0
+//! git@github.com:owner/repo.git#main
+//! owner/repo:plugins/otto          # shorthand for https://github.com/owner/repo.git,
+//!                                  # unless ./owner/repo exists
+//! file:///srv/git/plugins.git#0123abcd
+//! /srv/git/plugins.git:otto        # local repository, bare or not
+//! ```
+//!
+//! `:subdir` selects the plugin directory within the repository and `#ref`
+//! a branch, tag or commit (the default branch if omitted).
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Host `owner/repo` shorthand points at
+const SHORTHAND_HOST: &str = "https://github.com";
+
+/// A plugin in a git repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitSource {
+    /// Clone URL, or absolute path of a local repository
+    pub url: String,
+    /// Branch, tag or commit
+    pub git_ref: Option<String>,
+    /// Plugin directory within the repository
+    pub subdir: Option<String>,
+}
+
+impl fmt::Display for GitSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.url)?;
+        if let Some(ref subdir) = self.subdir {
+            write!(f, ":{}", subdir)?;
+        }
+        if let Some(ref git_ref) = self.git_ref {
+            write!(f, "#{}", git_ref)?;
+        }
+        Ok(())
+    }
+}
+
+/// What an install source refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A plugin directory on disk
+    Path(PathBuf),
+    Git(GitSource),
+    /// `[registry:]name[@version]`
+    Registry(String),
+}
+
+impl Source {
+    /// Classify an install source; `None` if it is none of the above
+    pub fn parse(spec: &str) -> Option<Self> {
+        let path = Path::new(spec);
+        if path.exists() {
+            if is_git_repo(path) && !path.join("plugin.toml").exists() {
+                return Some(Source::Git(GitSource {
+                    url: absolute(path),
+                    git_ref: None,
+                    subdir: None,
+                }));
+            }
+            return Some(Source::Path(path.to_path_buf()));
+        }
+        if !spec.contains('/') && !spec.contains('\\') {
+            return Some(Source::Registry(spec.to_string()));
+        }
+        GitSource::parse(spec).map(Source::Git)
+    }
+}
+
+impl GitSource {
+    /// Parse `<repository>[:subdir][#ref]`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (rest, git_ref) = match spec.rsplit_once('#') {
+            Some((rest, git_ref)) => (rest, Some(git_ref).filter(|r| !r.is_empty())),
+            None => (spec, None),
+        };
+
+        // `:subdir` is the first colon in the repository path, after any
+        // scheme, host and port
+        let path_start = if let Some(i) = rest.find("://") {
+            i + 3 + rest[i + 3..].find('/')?
+        } else if is_scp_like(rest) {
+            rest.find(':')? + 1
+        } else {
+            0
+        };
+        let (repo, subdir) = match rest[path_start..].find(':') {
+            Some(i) => (&rest[..path_start + i], Some(&rest[path_start + i + 1..])),
+            None => (rest, None),
+        };
+        let subdir = subdir
+            .map(|s| s.trim_matches('/'))
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        let url = if repo.contains("://") || is_scp_like(repo) {
+            repo.to_string()
+        } else if repo.starts_with(['/', '.', '~']) || Path::new(repo).exists() {
+            // A relative repository path is only shorthand when nothing
+            // exists there
+            let path = PathBuf::from(shellexpand::tilde(repo).as_ref());
+            if !path.exists() {
+                return None;
+            }
+            absolute(&path)
+        } else if is_shorthand(repo) {
+            format!("{}/{}.git", SHORTHAND_HOST, repo.trim_end_matches(".git"))
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            url,
+            git_ref: git_ref.map(String::from),
+            subdir,
+        })
+    }
+}
+
+/// `user@host:path`, as git accepts for ssh
+fn is_scp_like(s: &str) -> bool {
+    match s.split_once(':') {
+        Some((host, _)) => host.contains('@') && !host.contains('/'),
+        None => false,
+    }
+}
+
+/// `owner/repo`
+fn is_shorthand(s: &str) -> bool {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    matches!(s.split_once('/'), Some((owner, repo)) if valid(owner) && valid(repo))
+}
+
+/// A working tree or a bare repository
+fn is_git_repo(path: &Path) -> bool {
+    path.join(".git").exists() || (path.join("HEAD").is_file() && path.join("objects").is_dir())
+}
+
+fn absolute(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(spec: &str) -> GitSource {
+        GitSource::parse(spec).unwrap_or_else(|| panic!("{} should parse", spec))
+    }
+
+    #[test]
+    fn test_git_urls() {
+        let source = git("https://github.com/owner/repo.git:plugins/otto#v1.2.0");
+        assert_eq!(source.url, "https://github.com/owner/repo.git");
+        assert_eq!(source.subdir.as_deref(), Some("plugins/otto"));
+        assert_eq!(source.git_ref.as_deref(), Some("v1.2.0"));
+        assert_eq!(
+            source.to_string(),
+            "https://github.com/owner/repo.git:plugins/otto#v1.2.0"
+        );
+
+        // A port is not a subdir
+        let source = git("ssh://git@example.com:2222/team/plugins.git#main");
+        assert_eq!(source.url, "ssh://git@example.com:2222/team/plugins.git");
+        assert_eq!(source.subdir, None);
+
+        let source = git("git@github.com:owner/repo.git:incident");
+        assert_eq!(source.url, "git@github.com:owner/repo.git");
+        assert_eq!(source.subdir.as_deref(), Some("incident"));
+
+        let source = git("owner/repo:plugins/otto/");
+        assert_eq!(source.url, "https://github.com/owner/repo.git");
+        assert_eq!(source.subdir.as_deref(), Some("plugins/otto"));
+
+        let source = git("file:///srv/git/plugins.git#0123abcd");
+        assert_eq!(source.url, "file:///srv/git/plugins.git");
+        assert_eq!(source.git_ref.as_deref(), Some("0123abcd"));
+
+        assert!(GitSource::parse("not a/source with spaces").is_none());
+        assert!(GitSource::parse("./missing/repo:sub").is_none());
+    }
+
+    #[test]
+    fn test_classify() {
+        let tmp = tempfile::tempdir().unwrap();
+        let plugin = tmp.path().join("plugin");
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(plugin.join("plugin.toml"), "").unwrap();
+        let bare = tmp.path().join("repo.git");
+        fs::create_dir_all(bare.join("objects")).unwrap();
+        fs::write(bare.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        assert_eq!(
+            Source::parse(plugin.to_str().unwrap()),
+            Some(Source::Path(plugin.clone()))
+        );
+        assert_eq!(
+            Source::parse("core:otto@^1"),
+            Some(Source::Registry("core:otto@^1".to_string()))
+        );
+
+        let Some(Source::Git(source)) = Source::parse(bare.to_str().unwrap()) else {
+            panic!("bare repository should be a git source");
+        };
+        assert_eq!(source.url, absolute(&bare));
+
+        let spec = format!("{}:plugins/otto#v1", bare.display());
+        let Some(Source::Git(source)) = Source::parse(&spec) else {
+            panic!("{} should be a git source", spec);
+        };
+        assert_eq!(source.url, absolute(&bare));
+        assert_eq!(source.subdir.as_deref(), Some("plugins/otto"));
+        assert_eq!(source.git_ref.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn test_relative_repo_is_not_shorthand() {
+        // Relative to the working directory, as typed on the command line
+        let tmp = tempfile::Builder::new().prefix("repos").tempdir_in(".").unwrap();
+        let bare = tmp.path().join("plugins.git");
+        fs::create_dir_all(bare.join("objects")).unwrap();
+        fs::write(bare.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let dir = tmp.path().file_name().unwrap().to_str().unwrap();
+        let source = git(&format!("{}/plugins.git:otto#v1", dir));
+        assert_eq!(source.url, absolute(&bare));
+        assert_eq!(source.subdir.as_deref(), Some("otto"));
+        assert_eq!(source.git_ref.as_deref(), Some("v1"));
+    }
+}
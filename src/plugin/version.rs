@@ -0,0 +1,73 @@
+//! Semver handling for plugin versions and constraints
+//!
+//! Plugin versions are semver (`1.2.3`). Constraints use Cargo's syntax:
+//! `^1.2`, `~1.2.3`, `>=0.1.0, <2`, `=1.0.0`, `*`. A bare version such as
+//! `1.2` means `^1.2`.
+
+use eyre::{Context, Result};
+use semver::{Version, VersionReq};
+
+/// The running paii's version
+pub fn paii_version() -> Version {
+    Version::parse(env!("CARGO_PKG_VERSION")).expect("CARGO_PKG_VERSION is semver")
+}
+
+/// Parse a plugin version, tolerating a leading `v`
+pub fn parse_version(version: &str) -> Result<Version> {
+    let trimmed = version.trim();
+    Version::parse(trimmed.strip_prefix('v').unwrap_or(trimmed))
+        .context(format!("Invalid version '{}' (expected semver like 1.2.3)", version))
+}
+
+/// Parse a version constraint
+pub fn parse_req(req: &str) -> Result<VersionReq> {
+    VersionReq::parse(req.trim()).context(format!("Invalid version constraint '{}'", req))
+}
+
+/// Split `name@constraint` into the name and its constraint
+pub fn split_spec(spec: &str) -> Result<(&str, Option<VersionReq>)> {
+    match spec.split_once('@') {
+        Some((name, req)) => Ok((name, Some(parse_req(req)?))),
+        None => Ok((spec, None)),
+    }
+}
+
+/// Whether the running paii satisfies a `core_version` requirement.
+/// No requirement is always satisfied.
+pub fn core_satisfied(core_version: Option<&str>) -> Result<bool> {
+    match core_version {
+        Some(req) => Ok(parse_req(req)?.matches(&paii_version())),
+        None => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_version("v1.2.3").unwrap(), Version::new(1, 2, 3));
+        assert!(parse_version("1.2").is_err());
+        assert!(parse_req("^1.2").unwrap().matches(&Version::new(1, 9, 0)));
+        assert!(!parse_req("1.2").unwrap().matches(&Version::new(2, 0, 0)));
+        assert!(parse_req("not a version").is_err());
+    }
+
+    #[test]
+    fn test_split_spec() {
+        let (name, req) = split_spec("work:incident@^1.2").unwrap();
+        assert_eq!(name, "work:incident");
+        assert_eq!(req.unwrap().to_string(), "^1.2");
+        assert!(split_spec("otto").unwrap().1.is_none());
+        assert!(split_spec("otto@latest").is_err());
+    }
+
+    #[test]
+    fn test_core_satisfied() {
+        assert!(core_satisfied(None).unwrap());
+        assert!(core_satisfied(Some(">=0.0.1")).unwrap());
+        assert!(!core_satisfied(Some(">=999.0.0")).unwrap());
+        assert!(core_satisfied(Some("bogus")).is_err());
+    }
+}